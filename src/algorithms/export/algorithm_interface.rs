@@ -1,6 +1,6 @@
 use chrono::Duration;
 
-use crate::{Derivative, Instruction, Position, Price, TradingErrorKind};
+use crate::{BrokerErrorKind, Derivative, Instruction, MarketContext, MultiAssetAlgorithmInterface, OrderData, Position, Price, TradingErrorKind};
 use crate::error::Error;
 
 pub trait AlgorithmInterface {
@@ -23,13 +23,60 @@ pub trait AlgorithmInterface {
     /// Please note that your calculations shouldn't take longer then the time step
     /// defined by the user. If so the algorithm will be shutdown and instructions
     /// have no effect.
-    fn algorithm(&mut self, positions: &[Position], prices: &[Price]) -> Result<&[Instruction], Error<TradingErrorKind>>;
+    /// If you need more information about the market implement `trade` as well.
+    fn algorithm(&mut self, positions: &[Position], prices: &[Price]) -> Result<&[Instruction<'_>], Error<TradingErrorKind>>;
+
+    /// The `trade` function is the counterpart of `algorithm` that gets the whole
+    /// `MarketContext` of the current time step: the time, the OHLCV window, the deposit balance,
+    /// the open orders and positions and the traded derivative.
+    /// Runners always call `trade`. By default it forwards the positions and the close prices
+    /// to `algorithm`. If you implement `trade`, `algorithm` is only called by your own code.
+    fn trade(&mut self, context: &MarketContext<'_>) -> Result<&[Instruction<'_>], Error<TradingErrorKind>> {
+        self.algorithm(context.positions(), context.prices())
+    }
 
     /// The `shutdown` function will be called at the end, when the user decides to stop
     /// trading. It's meant to clean things up. Please note that you can't buy anything
     /// here.
     /// If any positions remain open after `shutdown` returned they will be handled
-    /// according to the users preferences.
+    /// according to the users preferences, see `EndOfSession`.
+    #[allow(unused)]
+    fn shutdown(&mut self, positions: &[Position], prices: &[Price]) -> Result<&[Instruction<'_>], Error<TradingErrorKind>> { Ok(&[]) }
+
+    /// The `on_fill` function will be called when an order was filled.
+    /// Instead of diffing the positions in the next step you can react to the new position
+    /// right away. All the following callbacks can return follow up instructions, which will be
+    /// executed immediately.
+    #[allow(unused)]
+    fn on_fill(&mut self, order: &OrderData, position: &Position) -> Result<&[Instruction<'_>], Error<TradingErrorKind>> { Ok(&[]) }
+
+    /// The `on_order_cancelled` function will be called when an open order was cancelled.
+    #[allow(unused)]
+    fn on_order_cancelled(&mut self, order: &OrderData) -> Result<&[Instruction<'_>], Error<TradingErrorKind>> { Ok(&[]) }
+
+    /// The `on_order_rejected` function will be called when the broker refused an order.
     #[allow(unused)]
-    fn shutdown(&mut self, positions: &[Position], prices: &[Price]) -> Result<&[Instruction], Error<TradingErrorKind>> { Ok(&[Instruction::None]) }
+    fn on_order_rejected(&mut self, order: &OrderData, error: &Error<BrokerErrorKind>) -> Result<&[Instruction<'_>], Error<TradingErrorKind>> { Ok(&[]) }
+
+    /// The `on_stop_loss_triggered` function will be called when the stop loss of a position
+    /// was hit and the position was closed.
+    #[allow(unused)]
+    fn on_stop_loss_triggered(&mut self, position: &Position) -> Result<&[Instruction<'_>], Error<TradingErrorKind>> { Ok(&[]) }
+
+    /// The `on_take_profit_triggered` function will be called when the take profit of a position
+    /// was hit and the position was closed.
+    #[allow(unused)]
+    fn on_take_profit_triggered(&mut self, position: &Position) -> Result<&[Instruction<'_>], Error<TradingErrorKind>> { Ok(&[]) }
+
+    /// The `on_broker_error` function will be called when the broker returned an error that
+    /// is not related to a single order.
+    #[allow(unused)]
+    fn on_broker_error(&mut self, error: &Error<BrokerErrorKind>) -> Result<&[Instruction<'_>], Error<TradingErrorKind>> { Ok(&[]) }
+
+    /// The `as_multi_asset` function tells the runner that the algorithm trades multiple
+    /// derivatives at once. If it returns Some, the `MultiAssetAlgorithmInterface` will be used
+    /// instead of the single asset functions above.
+    /// Exported algorithms that implement `MultiAssetAlgorithmInterface` should return
+    /// `Some(self)`.
+    fn as_multi_asset(&mut self) -> Option<&mut dyn MultiAssetAlgorithmInterface> { None }
 }
@@ -0,0 +1,270 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::fmt::Formatter;
+
+use crate::{Error, ErrorKind};
+
+/// The type of an algorithm parameter
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum ParameterType {
+    Integer,
+    Float,
+    Boolean,
+}
+
+impl fmt::Display for ParameterType {
+    fn fmt(&self, formatter: &mut Formatter<'_>) -> fmt::Result {
+        use ParameterType::*;
+        match self {
+            Integer => write!(formatter, "integer"),
+            Float => write!(formatter, "float"),
+            Boolean => write!(formatter, "boolean"),
+        }
+    }
+}
+
+/// The value of an algorithm parameter
+#[derive(Copy, Clone, Debug, PartialEq, PartialOrd)]
+pub enum ParameterValue {
+    Integer(i64),
+    Float(f64),
+    Boolean(bool),
+}
+
+impl ParameterValue {
+    pub fn parameter_type(&self) -> ParameterType {
+        use ParameterValue::*;
+        match self {
+            Integer(_) => ParameterType::Integer,
+            Float(_) => ParameterType::Float,
+            Boolean(_) => ParameterType::Boolean,
+        }
+    }
+}
+
+impl fmt::Display for ParameterValue {
+    fn fmt(&self, formatter: &mut Formatter<'_>) -> fmt::Result {
+        use ParameterValue::*;
+        match self {
+            Integer(integer) => write!(formatter, "{}", integer),
+            Float(float) => write!(formatter, "{}", float),
+            Boolean(boolean) => write!(formatter, "{}", boolean),
+        }
+    }
+}
+
+impl From<i64> for ParameterValue {
+    fn from(integer: i64) -> Self { ParameterValue::Integer(integer) }
+}
+
+impl From<f64> for ParameterValue {
+    fn from(float: f64) -> Self { ParameterValue::Float(float) }
+}
+
+impl From<bool> for ParameterValue {
+    fn from(boolean: bool) -> Self { ParameterValue::Boolean(boolean) }
+}
+
+/// The declaration of a single algorithm parameter
+///
+/// Parameter definitions are part of the `AlgorithmRegistration` and describe which values
+/// a user can choose when instantiating an algorithm.
+/// The type of a parameter is the type of its default value. `min` and `max` are inclusive
+/// bounds and are only used for numeric parameters.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct ParameterDefinition {
+    pub name: &'static str,
+    pub description: &'static str,
+
+    pub default: ParameterValue,
+    pub min: Option<ParameterValue>,
+    pub max: Option<ParameterValue>,
+}
+
+impl ParameterDefinition {
+    pub const fn integer(name: &'static str, description: &'static str, default: i64, min: i64, max: i64) -> Self {
+        Self {
+            name,
+            description,
+            default: ParameterValue::Integer(default),
+            min: Some(ParameterValue::Integer(min)),
+            max: Some(ParameterValue::Integer(max)),
+        }
+    }
+
+    pub const fn float(name: &'static str, description: &'static str, default: f64, min: f64, max: f64) -> Self {
+        Self {
+            name,
+            description,
+            default: ParameterValue::Float(default),
+            min: Some(ParameterValue::Float(min)),
+            max: Some(ParameterValue::Float(max)),
+        }
+    }
+
+    pub const fn boolean(name: &'static str, description: &'static str, default: bool) -> Self {
+        Self {
+            name,
+            description,
+            default: ParameterValue::Boolean(default),
+            min: None,
+            max: None,
+        }
+    }
+
+    pub fn parameter_type(&self) -> ParameterType {
+        self.default.parameter_type()
+    }
+
+    /// checks that a value has the type of this parameter and lies within its range
+    ///
+    /// Floats that are not finite are rejected, since NaN can't be compared with the bounds.
+    pub fn validate(&self, value: ParameterValue) -> Result<(), Error<ErrorKind>> {
+        if value.parameter_type() != self.parameter_type() {
+            return Err(Error::new(
+                format!(
+                    "The parameter `{}` needs to be of type {}, but got {} ({})",
+                    self.name, self.parameter_type(), value, value.parameter_type()
+                ),
+                ErrorKind::InvalidParameter,
+            ));
+        }
+
+        if let ParameterValue::Float(float) = value {
+            if !float.is_finite() {
+                return Err(Error::new(
+                    format!("The parameter `{}` needs to be a finite number, but got {}", self.name, value),
+                    ErrorKind::InvalidParameter,
+                ));
+            }
+        }
+
+        let below_min = self.min.is_some_and(|min| value < min);
+        let above_max = self.max.is_some_and(|max| value > max);
+        if below_min || above_max {
+            return Err(Error::new(
+                format!(
+                    "The parameter `{}` needs to be in the range [{}, {}], but got {}",
+                    self.name,
+                    self.min.map_or(String::from("-"), |min| min.to_string()),
+                    self.max.map_or(String::from("-"), |max| max.to_string()),
+                    value
+                ),
+                ErrorKind::InvalidParameter,
+            ));
+        }
+
+        Ok(())
+    }
+}
+
+impl fmt::Display for ParameterDefinition {
+    fn fmt(&self, formatter: &mut Formatter<'_>) -> fmt::Result {
+        write!(formatter, "{} ({}, default: {}", self.name, self.parameter_type(), self.default)?;
+        if let (Some(min), Some(max)) = (self.min, self.max) {
+            write!(formatter, ", range: [{}, {}]", min, max)?;
+        }
+        write!(formatter, ")")?;
+        if !self.description.is_empty() {
+            write!(formatter, ": {}", self.description)?;
+        }
+        Ok(())
+    }
+}
+
+/// The parameter values an algorithm is instantiated with
+///
+/// Users create an instance with the values they want to change, the remaining parameters
+/// will be filled with their defaults by `Parameters::resolve`.
+/// Algorithms read their values with the typed getters.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Parameters {
+    values: HashMap<String, ParameterValue>,
+}
+
+impl Parameters {
+    /// creates an empty instance of `Parameters`
+    pub fn empty() -> Self {
+        Self {
+            values: HashMap::new()
+        }
+    }
+
+    /// creates an instance that contains the default value of each definition
+    pub fn defaults(definitions: &[ParameterDefinition]) -> Self {
+        let mut parameters = Self::empty();
+        for definition in definitions {
+            parameters.set(definition.name, definition.default);
+        }
+        parameters
+    }
+
+    /// validates user supplied values against a parameter schema
+    ///
+    /// Missing values are replaced by their defaults.
+    /// Unknown parameters, values of the wrong type and values outside of the declared range
+    /// will lead to an error.
+    pub fn resolve(definitions: &[ParameterDefinition], supplied: &Parameters) -> Result<Self, Error<ErrorKind>> {
+        if let Some(unknown) = supplied.values
+                                       .keys()
+                                       .find(|name| !definitions.iter().any(|definition| definition.name == name.as_str())) {
+            return Err(Error::new(
+                format!("The algorithm has no parameter called `{}`", unknown),
+                ErrorKind::InvalidParameter,
+            ));
+        }
+
+        let mut parameters = Self::empty();
+        for definition in definitions {
+            let value = supplied.get(definition.name).unwrap_or(definition.default);
+            definition.validate(value)?;
+            parameters.set(definition.name, value);
+        }
+
+        Ok(parameters)
+    }
+
+    pub fn with<V: Into<ParameterValue>>(mut self, name: &str, value: V) -> Self {
+        self.set(name, value);
+        self
+    }
+
+    pub fn set<V: Into<ParameterValue>>(&mut self, name: &str, value: V) {
+        self.values.insert(name.to_string(), value.into());
+    }
+
+    pub fn get(&self, name: &str) -> Option<ParameterValue> {
+        self.values.get(name).copied()
+    }
+
+    pub fn integer(&self, name: &str) -> Option<i64> {
+        match self.get(name) {
+            Some(ParameterValue::Integer(integer)) => Some(integer),
+            _ => None
+        }
+    }
+
+    pub fn float(&self, name: &str) -> Option<f64> {
+        match self.get(name) {
+            Some(ParameterValue::Float(float)) => Some(float),
+            _ => None
+        }
+    }
+
+    pub fn boolean(&self, name: &str) -> Option<bool> {
+        match self.get(name) {
+            Some(ParameterValue::Boolean(boolean)) => Some(boolean),
+            _ => None
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item=(&str, ParameterValue)> {
+        self.values
+            .iter()
+            .map(|(name, value)| (name.as_str(), *value))
+    }
+}
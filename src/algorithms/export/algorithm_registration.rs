@@ -1,4 +1,4 @@
-use crate::AlgorithmInterface;
+use crate::{AlgorithmInterface, ParameterDefinition, Parameters};
 
 #[derive(Clone, Debug)]
 pub struct AlgorithmRegistration {
@@ -11,5 +11,7 @@ pub struct AlgorithmRegistration {
     pub min_data_length: u64,
     pub max_data_length: u64,
 
-    pub initial_algorithm_state_fn: unsafe extern fn() -> Box<dyn AlgorithmInterface>,
+    pub parameters: &'static [ParameterDefinition],
+
+    pub initial_algorithm_state_fn: unsafe extern "Rust" fn(&Parameters) -> Box<dyn AlgorithmInterface>,
 }
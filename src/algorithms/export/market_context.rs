@@ -0,0 +1,100 @@
+use chrono::{DateTime, Local};
+
+use crate::{Candle, Currency, Deposit, Derivative, MarketValue, Order, Position, Price, StockExchange, TradingHours};
+
+/// Everything an algorithm can know about the market in a single time step
+///
+/// The context is created by the runner of an algorithm and passed to
+/// `AlgorithmInterface::trade`.
+///
+/// #### Fields:
+/// * __time__: The time of the current step
+/// * __derivative__: The traded derivative
+/// * __candles__: The OHLCV window, the latest candle is the last one
+/// * __prices__: The close prices of the window
+/// * __balance__, __currency__: The balance of the deposit that is traded with
+/// * __orders__: The open orders of the deposit
+/// * __positions__: The open positions of the deposit
+/// * __trading_hours__: The trading hours of the derivative, see `Derivative::trading_hours`
+#[derive(Clone, Debug)]
+pub struct MarketContext<'a> {
+    time: DateTime<Local>,
+    derivative: &'a Derivative,
+    candles: &'a [Candle],
+    prices: Vec<Price>,
+
+    balance: Price,
+    currency: Currency,
+    orders: &'a [Order],
+    positions: &'a [Position],
+    trading_hours: Option<TradingHours>,
+}
+
+impl<'a> MarketContext<'a> {
+    /// creates a context without any deposit information
+    ///
+    /// Use `MarketContext::with_deposit` to add the balance, orders and positions of a deposit.
+    pub fn new(time: DateTime<Local>, derivative: &'a Derivative, candles: &'a [Candle]) -> Self {
+        Self {
+            time,
+            derivative,
+            candles,
+            prices: Candle::closes(candles),
+            balance: Price::zero(),
+            currency: Currency::EUR,
+            orders: &[],
+            positions: &[],
+            trading_hours: derivative.trading_hours
+                .clone()
+                .or_else(|| derivative.exchange.map(|exchange| exchange.trading_hours())),
+        }
+    }
+
+    pub fn with_deposit(mut self, deposit: &'a Deposit) -> Self {
+        self.balance = deposit.balance();
+        self.currency = deposit.currency();
+        self.orders = deposit.orders();
+        self.positions = deposit.positions();
+        self
+    }
+
+    /// uses the trading hours of the derivative at the exchange its orders are placed on
+    pub fn with_stock_exchange(mut self, stock_exchange: StockExchange) -> Self {
+        self.trading_hours = Some(self.derivative.trading_hours(stock_exchange));
+        self
+    }
+
+    pub fn time(&self) -> DateTime<Local> { self.time }
+    pub fn derivative(&self) -> &'a Derivative { self.derivative }
+    pub fn candles(&self) -> &'a [Candle] { self.candles }
+    pub fn prices(&self) -> &[Price] { &self.prices }
+    pub fn balance(&self) -> Price { self.balance }
+    pub fn currency(&self) -> Currency { self.currency }
+    pub fn orders(&self) -> &'a [Order] { self.orders }
+    pub fn positions(&self) -> &'a [Position] { self.positions }
+    pub fn trading_hours(&self) -> Option<&TradingHours> { self.trading_hours.as_ref() }
+
+    /// returns true if the trading hours are known and the market is open at the time of the step
+    /// ```
+    /// # use chrono::{Local, TimeZone};
+    /// # use trading_utils::*;
+    /// let share = Derivative::new("SAP".to_string(), DerivativeKind::Stock, Currency::EUR);
+    /// // a wednesday at noon in Germany
+    /// let time = Local.timestamp_opt(1_615_978_800, 0).unwrap();
+    ///
+    /// let context = MarketContext::new(time, &share, &[]);
+    /// assert!(context.trading_hours().is_none() && !context.is_market_open());
+    ///
+    /// let context = context.with_stock_exchange(StockExchange::LSExchange);
+    /// assert!(context.is_market_open());
+    /// assert!(!context.with_stock_exchange(StockExchange::NYSE).is_market_open());
+    /// ```
+    pub fn is_market_open(&self) -> bool {
+        self.trading_hours
+            .as_ref()
+            .is_some_and(|trading_hours| trading_hours.is_open(self.time))
+    }
+
+    /// returns the latest candle of the window
+    pub fn last_candle(&self) -> Option<&'a Candle> { self.candles.last() }
+}
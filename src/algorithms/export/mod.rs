@@ -1,22 +1,69 @@
 pub use self::algorithm_interface::*;
+pub use self::algorithm_parameter::*;
 pub use self::algorithm_registration::*;
+pub use self::market_context::*;
+pub use self::multi_asset_interface::*;
+pub use self::order_event::*;
 
 pub mod algorithm_interface;
+pub mod algorithm_parameter;
 pub mod algorithm_registration;
+pub mod market_context;
+pub mod multi_asset_interface;
+pub mod order_event;
 
+/// exports one or more algorithms, so they can be loaded as a dynamic library
+///
+/// The algorithm can either be a fixed expression or, when parameters are declared,
+/// a closure that builds the algorithm from the resolved `Parameters`:
+/// ```ignore
+/// export_algorithm!(
+///     "sma", "simple moving average cross over", 20, 0,
+///     [
+///         ParameterDefinition::integer("short", "the short interval", 20, 1, 200),
+///         ParameterDefinition::integer("long", "the long interval", 50, 1, 200),
+///     ],
+///     |parameters: &Parameters| Sma::new(parameters.integer("short").unwrap(), parameters.integer("long").unwrap())
+/// );
+/// ```
+///
+/// To export several algorithms from the same library wrap each registration in parentheses:
+/// ```ignore
+/// export_algorithm! {
+///     ("sma-20-50", "simple moving average cross over (20/50)", 50, 0, Sma::new(20, 50));
+///     ("sma-10-30", "simple moving average cross over (10/30)", 30, 0, Sma::new(10, 30));
+/// }
+/// ```
+/// All registrations are collected in the static `ALGORITHM_REGISTRATIONS` slice.
 #[macro_export]
 macro_rules! export_algorithm {
-    ($name:literal, $description:literal, $algorithm:expr) => (export_algorithm!($name, $description, 0, 0, $algorithm));
-    ($name:literal, $description:literal, $min_data_length:literal, $max_data_length:literal, $algorithm:expr) => {
-        #[doc(hidden)]
-        pub extern fn initial_algorithm_state() -> ::std::boxed::Box<dyn AlgorithmInterface> {
-            ::std::boxed::Box::new($algorithm)
-        }
-
+    ($(($($registration:tt)*));+ $(;)?) => {
         #[doc(hidden)]
         #[no_mangle]
         // needs to be static
-        pub static ALGORITHM_REGISTRATION: $crate::AlgorithmRegistration = $crate::AlgorithmRegistration {
+        pub static ALGORITHM_REGISTRATIONS: &[$crate::AlgorithmRegistration] = &[
+            $($crate::algorithm_registration!($($registration)*)),+
+        ];
+    };
+    ($($registration:tt)*) => (export_algorithm! { ($($registration)*) });
+}
+
+/// creates an `AlgorithmRegistration`
+///
+/// This macro is used by `export_algorithm!` and takes the same arguments as a single
+/// registration.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! algorithm_registration {
+    ($name:literal, $description:literal, $algorithm:expr) => ($crate::algorithm_registration!($name, $description, 0, 0, $algorithm));
+    ($name:literal, $description:literal, $min_data_length:literal, $max_data_length:literal, $algorithm:expr) => (
+        $crate::algorithm_registration!(
+            $name, $description, $min_data_length, $max_data_length, [],
+            |_: &$crate::Parameters| $algorithm
+        )
+    );
+    ($name:literal, $description:literal, $min_data_length:literal, $max_data_length:literal, [$($parameter:expr),* $(,)?], $algorithm:expr) => (
+        $crate::AlgorithmRegistration {
             rustc_version: $crate::RUSTC_VERSION,
             utils_version: $crate::UTILS_VERSION,
 
@@ -26,7 +73,14 @@ macro_rules! export_algorithm {
             min_data_length: $min_data_length,
             max_data_length: $max_data_length,
 
-            initial_algorithm_state_fn: initial_algorithm_state,
-        };
-    };
+            parameters: &[$($parameter),*],
+
+            initial_algorithm_state_fn: {
+                extern "Rust" fn initial_algorithm_state(parameters: &$crate::Parameters) -> ::std::boxed::Box<dyn $crate::AlgorithmInterface> {
+                    ::std::boxed::Box::new(($algorithm)(parameters))
+                }
+                initial_algorithm_state
+            },
+        }
+    );
 }
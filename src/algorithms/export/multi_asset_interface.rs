@@ -0,0 +1,99 @@
+use chrono::{DateTime, Duration, Local};
+
+use crate::{AlignedSeries, Candle, Currency, Deposit, Derivative, Instruction, MarketValue, Order, Position, Price, TradingErrorKind};
+use crate::error::Error;
+
+/// An algorithm that trades multiple derivatives at once
+///
+/// This is the counterpart of `AlgorithmInterface` for strategies like pairs trading, spreads or
+/// portfolio rotation. The prices of all derivatives are aligned by their timestamp, so the
+/// i-th price of every derivative belongs to the same moment.
+/// Buy instructions need to name the derivative they target.
+///
+/// To export a multi asset algorithm implement `AlgorithmInterface::as_multi_asset` for it.
+pub trait MultiAssetAlgorithmInterface {
+    /// The `init` function will be called exactly once before trading begins.
+    /// This is the only time you'll get to know the derivatives that will be traded
+    /// and the time steps they will be traded in.
+    #[allow(unused)]
+    fn init(&mut self, derivatives: &[Derivative], time_steps: Duration) -> Result<(), Error<TradingErrorKind>> { Ok(()) }
+
+    /// The `collect_prices` function will be called while the amount of aligned prices is less
+    /// then the minimal data length.
+    /// It's not possible to give instructions here.
+    #[allow(unused)]
+    fn collect_prices(&mut self, series: &AlignedSeries) -> Result<(), Error<TradingErrorKind>> { Ok(()) }
+
+    /// The `trade` function will be called in user defined time steps with the aligned windows
+    /// of all derivatives.
+    fn trade(&mut self, context: &MultiAssetContext<'_>) -> Result<&[Instruction<'_>], Error<TradingErrorKind>>;
+
+    /// The `shutdown` function will be called at the end, when the user decides to stop
+    /// trading. Please note that you can't buy anything here.
+    #[allow(unused)]
+    fn shutdown(&mut self, context: &MultiAssetContext<'_>) -> Result<&[Instruction<'_>], Error<TradingErrorKind>> { Ok(&[]) }
+}
+
+/// Everything a multi asset algorithm can know about the market in a single time step
+///
+/// For more information have a look at `MarketContext`
+#[derive(Clone, Debug)]
+pub struct MultiAssetContext<'a> {
+    time: DateTime<Local>,
+    derivatives: &'a [Derivative],
+    series: &'a AlignedSeries,
+
+    balance: Price,
+    currency: Currency,
+    orders: &'a [Order],
+    positions: &'a [Position],
+}
+
+impl<'a> MultiAssetContext<'a> {
+    /// creates a context without any deposit information
+    ///
+    /// Use `MultiAssetContext::with_deposit` to add the balance, orders and positions of a deposit.
+    pub fn new(time: DateTime<Local>, derivatives: &'a [Derivative], series: &'a AlignedSeries) -> Self {
+        Self {
+            time,
+            derivatives,
+            series,
+            balance: Price::zero(),
+            currency: Currency::EUR,
+            orders: &[],
+            positions: &[],
+        }
+    }
+
+    pub fn with_deposit(mut self, deposit: &'a Deposit) -> Self {
+        self.balance = deposit.balance();
+        self.currency = deposit.currency();
+        self.orders = deposit.orders();
+        self.positions = deposit.positions();
+        self
+    }
+
+    pub fn time(&self) -> DateTime<Local> { self.time }
+    pub fn derivatives(&self) -> &'a [Derivative] { self.derivatives }
+    pub fn series(&self) -> &'a AlignedSeries { self.series }
+    pub fn balance(&self) -> Price { self.balance }
+    pub fn currency(&self) -> Currency { self.currency }
+    pub fn orders(&self) -> &'a [Order] { self.orders }
+    pub fn positions(&self) -> &'a [Position] { self.positions }
+
+    pub fn derivative(&self, symbol: &str) -> Option<&'a Derivative> {
+        self.derivatives
+            .iter()
+            .find(|derivative| derivative.symbol == symbol)
+    }
+
+    /// returns the aligned OHLCV window of a derivative
+    pub fn candles(&self, symbol: &str) -> Option<&'a [Candle]> {
+        self.series.candles(symbol)
+    }
+
+    /// returns the aligned close prices of a derivative
+    pub fn prices(&self, symbol: &str) -> Option<&'a [Price]> {
+        self.series.prices(symbol)
+    }
+}
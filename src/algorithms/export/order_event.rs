@@ -0,0 +1,68 @@
+use std::fmt;
+use std::fmt::Formatter;
+
+use crate::{AlgorithmInterface, BrokerErrorKind, Error, Event, EventLevel, Instruction, OrderData, Position, TradingErrorKind};
+
+/// Something that happened to the orders or positions of an algorithm
+///
+/// Order events are created by the executor of the instructions and dispatched to the
+/// callbacks of the `AlgorithmInterface`.
+#[derive(Clone, Debug)]
+#[allow(clippy::large_enum_variant)]
+pub enum OrderEvent {
+    Filled { order: OrderData, position: Position },
+    Cancelled { order: OrderData },
+    Rejected { order: OrderData, error: Error<BrokerErrorKind> },
+    StopLossTriggered { position: Position },
+    TakeProfitTriggered { position: Position },
+    BrokerError { error: Error<BrokerErrorKind> },
+}
+
+impl OrderEvent {
+    /// calls the callback of an algorithm that belongs to this event
+    ///
+    /// Returns the follow up instructions of the algorithm.
+    pub fn dispatch<'a, A: AlgorithmInterface + ?Sized>(&self, algorithm: &'a mut A) -> Result<&'a [Instruction<'a>], Error<TradingErrorKind>> {
+        use OrderEvent::*;
+        match self {
+            Filled { order, position } => algorithm.on_fill(order, position),
+            Cancelled { order } => algorithm.on_order_cancelled(order),
+            Rejected { order, error } => algorithm.on_order_rejected(order, error),
+            StopLossTriggered { position } => algorithm.on_stop_loss_triggered(position),
+            TakeProfitTriggered { position } => algorithm.on_take_profit_triggered(position),
+            BrokerError { error } => algorithm.on_broker_error(error),
+        }
+    }
+}
+
+impl Event for OrderEvent {
+    fn level(&self) -> EventLevel {
+        use OrderEvent::*;
+        match self {
+            Filled { .. } | Cancelled { .. } => EventLevel::Info,
+            StopLossTriggered { .. } | TakeProfitTriggered { .. } => EventLevel::Info,
+            Rejected { .. } => EventLevel::Warn,
+            BrokerError { .. } => EventLevel::Error,
+        }
+    }
+}
+
+impl fmt::Display for OrderEvent {
+    fn fmt(&self, formatter: &mut Formatter<'_>) -> fmt::Result {
+        use OrderEvent::*;
+        match self {
+            Filled { order, position } =>
+                write!(formatter, "the order `{}` was filled (position `{}`)", order.raw_id(), position.id),
+            Cancelled { order } =>
+                write!(formatter, "the order `{}` was cancelled", order.raw_id()),
+            Rejected { order, error } =>
+                write!(formatter, "the order `{}` was rejected: {}", order.raw_id(), error.msg()),
+            StopLossTriggered { position } =>
+                write!(formatter, "the stop loss of the position `{}` was triggered", position.id),
+            TakeProfitTriggered { position } =>
+                write!(formatter, "the take profit of the position `{}` was triggered", position.id),
+            BrokerError { error } =>
+                write!(formatter, "the broker returned an error: {}", error.msg()),
+        }
+    }
+}
@@ -1,26 +1,31 @@
 use std::ffi::OsStr;
 use std::fmt;
 use std::fmt::Formatter;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
 
-use chrono::Duration;
 use libloading::Library;
 
-use crate::{AlgorithmInterface, Derivative, Error, ErrorKind, Instruction, Position, Price, TradingErrorKind};
+use crate::{AlgorithmEvent, AlgorithmInstance, AlgorithmInterface, AlgorithmRegistration, Error, ErrorKind, EventBus, ParameterDefinition, Parameters};
 
-/// The AlgorithmRegistration type represents a mutable pointer to an instance of
-/// AlgorithmRegistration
+/// The AlgorithmRegistrations type represents a pointer to the slice of
+/// AlgorithmRegistrations
 ///
-/// This pointer is needed to load the static instance created by export_algorithm!
-type AlgorithmRegistration = *mut crate::AlgorithmRegistration;
+/// This pointer is needed to load the static slice created by export_algorithm!
+type AlgorithmRegistrations = *const &'static [AlgorithmRegistration];
 
-/// a wrapper around an extern AlgorithmInterface
+/// A loaded library together with its algorithm registrations and its canonical path
+type LoadedLibrary = (Arc<Library>, &'static [AlgorithmRegistration], PathBuf);
+
+/// a factory for an extern AlgorithmInterface
 ///
-/// This wrapper provides convenient access to a Box<dyn AlgorithmInterface.
+/// An Algorithm is the loaded registration of an algorithm. It does not trade itself, but
+/// spawns any number of independent `AlgorithmInstance`s, for example one per traded derivative.
 /// This dynamic AlgorithmInterface usually is a dynamically loaded library that contains
 /// an algorithm.
-/// The _lib field is for the borrow checker to keep the library alive as long as an instance
-/// of it is used.
+/// The _lib field is for the borrow checker to keep the library alive as long as the algorithm
+/// or one of its instances is used. Since a library can export multiple algorithms it's shared
+/// between them.
 pub struct Algorithm {
     name: &'static str,
     description: &'static str,
@@ -28,9 +33,13 @@ pub struct Algorithm {
     min_data_length: u64,
     max_data_length: u64,
 
+    parameter_definitions: &'static [ParameterDefinition],
+    parameters: Parameters,
+    initial_algorithm_state_fn: unsafe extern "Rust" fn(&Parameters) -> Box<dyn AlgorithmInterface>,
+
     path: PathBuf,
-    algorithm_box: Box<dyn AlgorithmInterface>,
-    _lib: Library,
+    events: EventBus<AlgorithmEvent>,
+    _lib: Arc<Library>,
 }
 
 impl Algorithm {
@@ -38,93 +47,135 @@ impl Algorithm {
     ///
     /// this function loads an algorithm from a dynamically loaded library.
     /// To return Ok:
-    ///     * the path needs to be valid
-    ///     * the provided library needs to contain a static variable called `ALGORITHM_REGISTRATION`
-    ///     * This variable needs to contain an instance of the AlgorithmRegistration struct
+    /// * the path needs to be valid
+    /// * the provided library needs to contain a static variable called `ALGORITHM_REGISTRATIONS`
+    /// * This variable needs to contain exactly one instance of the AlgorithmRegistration struct
+    ///
+    /// Instances spawned by `Algorithm::spawn` will use the default values of the parameters.
+    /// If a library exports multiple algorithms use `Algorithm::load_all`
     pub fn load<P: AsRef<OsStr>>(path: &P) -> Result<Self, Error<ErrorKind>> {
-        let os_path = std::fs::canonicalize(path.as_ref().to_os_string())?;
+        Self::load_with_parameters(path, &Parameters::empty())
+    }
 
-        if !os_path.exists() {
-            return Err(Error::new(
-                "Could not find the supplied path".to_string(),
-                ErrorKind::IO,
+    /// loads an algorithm by path and sets the parameters used by `Algorithm::spawn`
+    ///
+    /// Parameters that are not supplied will be set to their default value.
+    /// For more information have a look at `Algorithm::load` and `Parameters::resolve`
+    pub fn load_with_parameters<P: AsRef<OsStr>>(path: &P, parameters: &Parameters) -> Result<Self, Error<ErrorKind>> {
+        let events = EventBus::new();
+        let (lib, registrations, os_path) = load_library(path, &events)?;
+
+        match registrations {
+            [registration] => Self::from_registration(registration, parameters, &lib, &os_path, &events),
+            _ => Err(Error::new(
+                format!(
+                    "The library {:?} exports {} algorithms\n\
+                    Use `Algorithm::load_all` to load all of them",
+                    os_path, registrations.len()
+                ),
+                ErrorKind::LibLoading,
             ))
         }
+    }
 
-        let lib = Library::new(path)?;
+    /// loads all algorithms exported by a library
+    ///
+    /// Instances spawned by `Algorithm::spawn` will use the default values of the parameters.
+    /// For more information have a look at `Algorithm::load`
+    pub fn load_all<P: AsRef<OsStr>>(path: &P) -> Result<Vec<Self>, Error<ErrorKind>> {
+        Self::load_all_with_events(path, &EventBus::new())
+    }
 
-        let algorithm_registration = unsafe {
-            lib
-                .get::<AlgorithmRegistration>(b"ALGORITHM_REGISTRATION\0")?
-                .read()
-        };
+    /// loads all algorithms exported by a library and reports what happens to an `EventBus`
+    ///
+    /// The bus is shared with the algorithms and the instances they spawn.
+    /// For more information have a look at `Algorithm::load_all`
+    pub fn load_all_with_events<P: AsRef<OsStr>>(path: &P, events: &EventBus<AlgorithmEvent>) -> Result<Vec<Self>, Error<ErrorKind>> {
+        let (lib, registrations, os_path) = load_library(path, events)?;
 
-        // check that the rustc and the trading-utils version of the provided algorithm is
-        // the same as the rustc and the trading-utils version of this crate.
-        // This should make sure that no undefined behaviour occurs when loading the algorithm.
-        // To pass this test the algorithm just needs to use the same version of rustc and
-        // trading-utils like trading-desk used when it was compiled
-        if algorithm_registration.rustc_version != crate::RUSTC_VERSION
-            || algorithm_registration.utils_version != crate::UTILS_VERSION {
-            return Err(Error::new(
-                format!(
-                    "The algorithm `{}` has a mismatched version\n\
-                    Algorithm version: [{}/{}]\nUtils version: [{}/{}]\n\
-                    Please update either trading-desk or the algorithm",
-                    algorithm_registration.name,
-                    algorithm_registration.rustc_version,
-                    algorithm_registration.utils_version,
-                    crate::RUSTC_VERSION,
-                    crate::UTILS_VERSION
-                ),
-                ErrorKind::MisMatchedVersion,
-            ));
-        }
+        registrations
+            .iter()
+            .map(|registration| Self::from_registration(registration, &Parameters::empty(), &lib, &os_path, events))
+            .collect()
+    }
 
-        let algorithm_box = unsafe { (algorithm_registration.initial_algorithm_state_fn)() };
+    fn from_registration(
+        algorithm_registration: &'static AlgorithmRegistration,
+        parameters: &Parameters,
+        lib: &Arc<Library>,
+        os_path: &Path,
+        events: &EventBus<AlgorithmEvent>,
+    ) -> Result<Self, Error<ErrorKind>> {
+        let parameters = Parameters::resolve(algorithm_registration.parameters, parameters)?;
 
         Ok(Self {
             name: algorithm_registration.name,
             description: algorithm_registration.description,
             min_data_length: algorithm_registration.min_data_length,
             max_data_length: algorithm_registration.max_data_length,
-            path: os_path,
-            algorithm_box,
-            _lib: lib,
+            parameter_definitions: algorithm_registration.parameters,
+            parameters,
+            initial_algorithm_state_fn: algorithm_registration.initial_algorithm_state_fn,
+            path: os_path.to_path_buf(),
+            events: events.clone(),
+            _lib: Arc::clone(lib),
         })
     }
 
     #[inline]
-    pub const fn name(&self) -> &'static str { &self.name }
+    pub const fn name(&self) -> &'static str { self.name }
     #[inline]
-    pub const fn description(&self) -> &'static str { &self.description }
+    pub const fn description(&self) -> &'static str { self.description }
     #[inline]
     pub const fn min_data_length(&self) -> u64 { self.min_data_length }
     #[inline]
     pub const fn max_data_length(&self) -> u64 { self.max_data_length }
     #[inline]
+    pub const fn parameter_definitions(&self) -> &'static [ParameterDefinition] { self.parameter_definitions }
+    #[inline]
+    pub const fn parameters(&self) -> &Parameters { &self.parameters }
+    #[inline]
     pub const fn path(&self) -> &PathBuf { &self.path }
-}
 
-impl AlgorithmInterface for Algorithm {
-    #[inline]
-    fn init(&mut self, derivative: &Derivative, time_steps: Duration) -> Result<(), Error<TradingErrorKind>> {
-        self.algorithm_box.init(derivative, time_steps)
+    /// sets the parameters used by `Algorithm::spawn`
+    ///
+    /// Instances that were already spawned keep their parameters.
+    /// If the parameters are invalid the current parameters are kept and an error is returned.
+    pub fn set_parameters(&mut self, parameters: &Parameters) -> Result<(), Error<ErrorKind>> {
+        self.parameters = Parameters::resolve(self.parameter_definitions, parameters)?;
+        Ok(())
     }
 
-    #[inline]
-    fn collect_prices(&mut self, prices: &[Price]) -> Result<(), Error<TradingErrorKind>> {
-        self.algorithm_box.collect_prices(prices)
+    /// creates a new, independent instance of the algorithm
+    ///
+    /// The instance is created by the `initial_algorithm_state_fn` of the registration and
+    /// uses the parameters of this algorithm.
+    pub fn spawn(&self) -> AlgorithmInstance {
+        self.spawn_resolved(self.parameters.clone())
     }
 
-    #[inline]
-    fn algorithm(&mut self, positions: &[Position], prices: &[Price]) -> Result<&[Instruction], Error<TradingErrorKind>> {
-        self.algorithm_box.algorithm(positions, prices)
+    /// creates a new, independent instance of the algorithm with user supplied parameters
+    ///
+    /// Parameters that are not supplied will be set to their default value.
+    /// For more information have a look at `Algorithm::spawn` and `Parameters::resolve`
+    pub fn spawn_with_parameters(&self, parameters: &Parameters) -> Result<AlgorithmInstance, Error<ErrorKind>> {
+        let parameters = Parameters::resolve(self.parameter_definitions, parameters)?;
+        Ok(self.spawn_resolved(parameters))
     }
 
-    #[inline]
-    fn shutdown(&mut self, positions: &[Position], prices: &[Price]) -> Result<&[Instruction], Error<TradingErrorKind>> {
-        self.algorithm_box.shutdown(positions, prices)
+    fn spawn_resolved(&self, parameters: Parameters) -> AlgorithmInstance {
+        let algorithm_box = unsafe { (self.initial_algorithm_state_fn)(&parameters) };
+        self.events.emit(AlgorithmEvent::Spawned { algorithm: self.name.to_string() });
+
+        AlgorithmInstance::new(
+            self.name,
+            self.min_data_length,
+            self.max_data_length,
+            parameters,
+            algorithm_box,
+            self.events.clone(),
+            Arc::clone(&self._lib),
+        )
     }
 }
 
@@ -134,12 +185,76 @@ impl fmt::Display for Algorithm {
             formatter,
             "{} ({:?})\n\n\
             minimal data length: {}\n\
-            maximal data length: {}\n\n\
-            {}",
+            maximal data length: {}\n",
             self.name, self.path,
             self.min_data_length,
             self.max_data_length,
-            self.description
-        )
+        )?;
+
+        if !self.parameter_definitions.is_empty() {
+            write!(formatter, "\nparameters:\n")?;
+            for definition in self.parameter_definitions {
+                let value = self.parameters.get(definition.name).unwrap_or(definition.default);
+                writeln!(formatter, "\t{}\n\t\tvalue: {}", definition, value)?;
+            }
+        }
+
+        write!(formatter, "\n{}", self.description)
+    }
+}
+
+/// loads a dynamic library and the algorithm registrations it exports
+///
+/// The version of each registration is checked before it is returned.
+fn load_library<P: AsRef<OsStr>>(path: &P, events: &EventBus<AlgorithmEvent>) -> Result<LoadedLibrary, Error<ErrorKind>> {
+    let os_path = std::fs::canonicalize(path.as_ref())?;
+
+    if !os_path.exists() {
+        return Err(Error::new(
+            "Could not find the supplied path".to_string(),
+            ErrorKind::IO,
+        ))
+    }
+
+    let lib = Library::new(path)?;
+
+    // the slice points into the static memory of the library, which lives as long as the
+    // returned `Arc<Library>`
+    let algorithm_registrations: &'static [AlgorithmRegistration] = unsafe {
+        lib
+            .get::<AlgorithmRegistrations>(b"ALGORITHM_REGISTRATIONS\0")?
+            .read()
+    };
+
+    // check that the rustc and the trading-utils version of the provided algorithm is
+    // the same as the rustc and the trading-utils version of this crate.
+    // This should make sure that no undefined behaviour occurs when loading the algorithm.
+    // To pass this test the algorithm just needs to use the same version of rustc and
+    // trading-utils like trading-desk used when it was compiled
+    for algorithm_registration in algorithm_registrations {
+        if algorithm_registration.rustc_version != crate::RUSTC_VERSION
+            || algorithm_registration.utils_version != crate::UTILS_VERSION {
+            events.emit(AlgorithmEvent::MismatchedVersion {
+                path: os_path.clone(),
+                algorithm: algorithm_registration.name.to_string(),
+                rustc_version: algorithm_registration.rustc_version.to_string(),
+                utils_version: algorithm_registration.utils_version.to_string(),
+            });
+            return Err(Error::new(
+                format!(
+                    "The algorithm `{}` has a mismatched version\n\
+                    Algorithm version: [{}/{}]\nUtils version: [{}/{}]\n\
+                    Please update either trading-desk or the algorithm",
+                    algorithm_registration.name,
+                    algorithm_registration.rustc_version,
+                    algorithm_registration.utils_version,
+                    crate::RUSTC_VERSION,
+                    crate::UTILS_VERSION
+                ),
+                ErrorKind::MisMatchedVersion,
+            ));
+        }
     }
+
+    Ok((Arc::new(lib), algorithm_registrations, os_path))
 }
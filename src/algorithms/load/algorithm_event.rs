@@ -0,0 +1,114 @@
+use std::fmt;
+use std::fmt::Formatter;
+use std::path::PathBuf;
+
+use crate::{Error, ErrorKind, Event, EventLevel, TradingErrorKind};
+
+/// A call of the `AlgorithmInterface` made on an `AlgorithmInstance`
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum LifecycleCall {
+    Init,
+    CollectPrices,
+    Algorithm,
+    Shutdown,
+    OnFill,
+    OnOrderCancelled,
+    OnOrderRejected,
+    OnStopLossTriggered,
+    OnTakeProfitTriggered,
+    OnBrokerError,
+}
+
+impl fmt::Display for LifecycleCall {
+    fn fmt(&self, formatter: &mut Formatter<'_>) -> fmt::Result {
+        use LifecycleCall::*;
+        match self {
+            Init => write!(formatter, "init"),
+            CollectPrices => write!(formatter, "collect_prices"),
+            Algorithm => write!(formatter, "algorithm"),
+            Shutdown => write!(formatter, "shutdown"),
+            OnFill => write!(formatter, "on_fill"),
+            OnOrderCancelled => write!(formatter, "on_order_cancelled"),
+            OnOrderRejected => write!(formatter, "on_order_rejected"),
+            OnStopLossTriggered => write!(formatter, "on_stop_loss_triggered"),
+            OnTakeProfitTriggered => write!(formatter, "on_take_profit_triggered"),
+            OnBrokerError => write!(formatter, "on_broker_error"),
+        }
+    }
+}
+
+/// The events emitted while loading and running algorithms
+///
+/// Algorithm names are owned, since the events can outlive the library they were loaded from.
+#[derive(Clone, Debug)]
+pub enum AlgorithmEvent {
+    /// a dynamic library was found while searching a directory
+    LibraryFound { path: PathBuf },
+    /// an algorithm was compiled with another version of rustc or trading-utils
+    MismatchedVersion {
+        path: PathBuf,
+        algorithm: String,
+        rustc_version: String,
+        utils_version: String,
+    },
+    /// an algorithm was loaded successfully
+    Loaded { path: PathBuf, algorithm: String },
+    /// a file or directory does not contain any algorithms
+    Skipped { path: PathBuf, reason: String },
+    /// a library could not be loaded
+    Failed { path: PathBuf, error: Error<ErrorKind> },
+    /// a new instance of an algorithm was created
+    Spawned { algorithm: String },
+    /// an instance was called successfully
+    Called { algorithm: String, call: LifecycleCall },
+    /// an instance returned an error
+    CallFailed {
+        algorithm: String,
+        call: LifecycleCall,
+        error: Error<TradingErrorKind>,
+    },
+}
+
+impl Event for AlgorithmEvent {
+    fn level(&self) -> EventLevel {
+        use AlgorithmEvent::*;
+        match self {
+            LibraryFound { .. } => EventLevel::Debug,
+            MismatchedVersion { .. } => EventLevel::Error,
+            Loaded { .. } => EventLevel::Info,
+            Skipped { .. } => EventLevel::Warn,
+            Failed { .. } => EventLevel::Error,
+            Spawned { .. } => EventLevel::Debug,
+            Called { .. } => EventLevel::Trace,
+            CallFailed { .. } => EventLevel::Error,
+        }
+    }
+}
+
+impl fmt::Display for AlgorithmEvent {
+    fn fmt(&self, formatter: &mut Formatter<'_>) -> fmt::Result {
+        use AlgorithmEvent::*;
+        match self {
+            LibraryFound { path } =>
+                write!(formatter, "found library {:?}", path),
+            MismatchedVersion { path, algorithm, rustc_version, utils_version } =>
+                write!(
+                    formatter,
+                    "the algorithm `{}` ({:?}) has a mismatched version [{}/{}], expected [{}/{}]",
+                    algorithm, path, rustc_version, utils_version, crate::RUSTC_VERSION, crate::UTILS_VERSION
+                ),
+            Loaded { path, algorithm } =>
+                write!(formatter, "loaded the algorithm `{}` ({:?})", algorithm, path),
+            Skipped { path, reason } =>
+                write!(formatter, "skipped {:?}: {}", path, reason),
+            Failed { path, error } =>
+                write!(formatter, "could not load {:?}: {}", path, error.msg()),
+            Spawned { algorithm } =>
+                write!(formatter, "spawned an instance of `{}`", algorithm),
+            Called { algorithm, call } =>
+                write!(formatter, "called `{}` of `{}`", call, algorithm),
+            CallFailed { algorithm, call, error } =>
+                write!(formatter, "`{}` of `{}` failed: {}", call, algorithm, error.msg()),
+        }
+    }
+}
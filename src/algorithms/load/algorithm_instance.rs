@@ -0,0 +1,156 @@
+use std::sync::Arc;
+
+use chrono::Duration;
+use libloading::Library;
+
+use crate::{AlgorithmEvent, AlgorithmInterface, BrokerErrorKind, Derivative, Error, EventBus, Instruction, LifecycleCall, MarketContext, MultiAssetAlgorithmInterface, OrderData, Parameters, Position, Price, TradingErrorKind};
+
+/// a single instance of an extern AlgorithmInterface
+///
+/// Instances are created by `Algorithm::spawn` and have their own state, so the same algorithm
+/// can trade several derivatives at once.
+/// The derivative and the time steps passed to `init` are kept, so the caller knows what
+/// the instance is trading.
+/// Every call of the `AlgorithmInterface` is reported to the `EventBus` of the algorithm.
+/// The _lib field keeps the library alive as long as the instance is used. It needs to be the
+/// last field, so the algorithm_box is dropped before the library is unloaded.
+pub struct AlgorithmInstance {
+    name: &'static str,
+
+    min_data_length: u64,
+    max_data_length: u64,
+
+    parameters: Parameters,
+    derivative: Option<Derivative>,
+    time_steps: Option<Duration>,
+
+    events: EventBus<AlgorithmEvent>,
+    algorithm_box: Box<dyn AlgorithmInterface>,
+    _lib: Arc<Library>,
+}
+
+impl AlgorithmInstance {
+    pub(crate) fn new(
+        name: &'static str,
+        min_data_length: u64,
+        max_data_length: u64,
+        parameters: Parameters,
+        algorithm_box: Box<dyn AlgorithmInterface>,
+        events: EventBus<AlgorithmEvent>,
+        lib: Arc<Library>,
+    ) -> Self {
+        Self {
+            name,
+            min_data_length,
+            max_data_length,
+            parameters,
+            derivative: None,
+            time_steps: None,
+            events,
+            algorithm_box,
+            _lib: lib,
+        }
+    }
+
+    #[inline]
+    pub const fn name(&self) -> &'static str { self.name }
+    #[inline]
+    pub const fn min_data_length(&self) -> u64 { self.min_data_length }
+    #[inline]
+    pub const fn max_data_length(&self) -> u64 { self.max_data_length }
+    #[inline]
+    pub const fn parameters(&self) -> &Parameters { &self.parameters }
+    /// the derivative this instance was initialised with
+    #[inline]
+    pub const fn derivative(&self) -> Option<&Derivative> { self.derivative.as_ref() }
+    /// the time steps this instance was initialised with
+    #[inline]
+    pub const fn time_steps(&self) -> Option<Duration> { self.time_steps }
+}
+
+impl AlgorithmInterface for AlgorithmInstance {
+    fn init(&mut self, derivative: &Derivative, time_steps: Duration) -> Result<(), Error<TradingErrorKind>> {
+        let result = self.algorithm_box.init(derivative, time_steps);
+        report(&self.events, self.name, LifecycleCall::Init, &result);
+        result?;
+
+        self.derivative = Some(derivative.clone());
+        self.time_steps = Some(time_steps);
+
+        Ok(())
+    }
+
+    fn collect_prices(&mut self, prices: &[Price]) -> Result<(), Error<TradingErrorKind>> {
+        let result = self.algorithm_box.collect_prices(prices);
+        report(&self.events, self.name, LifecycleCall::CollectPrices, &result);
+        result
+    }
+
+    fn algorithm(&mut self, positions: &[Position], prices: &[Price]) -> Result<&[Instruction<'_>], Error<TradingErrorKind>> {
+        let result = self.algorithm_box.algorithm(positions, prices);
+        report(&self.events, self.name, LifecycleCall::Algorithm, &result);
+        result
+    }
+
+    fn trade(&mut self, context: &MarketContext<'_>) -> Result<&[Instruction<'_>], Error<TradingErrorKind>> {
+        let result = self.algorithm_box.trade(context);
+        report(&self.events, self.name, LifecycleCall::Algorithm, &result);
+        result
+    }
+
+    fn shutdown(&mut self, positions: &[Position], prices: &[Price]) -> Result<&[Instruction<'_>], Error<TradingErrorKind>> {
+        let result = self.algorithm_box.shutdown(positions, prices);
+        report(&self.events, self.name, LifecycleCall::Shutdown, &result);
+        result
+    }
+
+    fn on_fill(&mut self, order: &OrderData, position: &Position) -> Result<&[Instruction<'_>], Error<TradingErrorKind>> {
+        let result = self.algorithm_box.on_fill(order, position);
+        report(&self.events, self.name, LifecycleCall::OnFill, &result);
+        result
+    }
+
+    fn on_order_cancelled(&mut self, order: &OrderData) -> Result<&[Instruction<'_>], Error<TradingErrorKind>> {
+        let result = self.algorithm_box.on_order_cancelled(order);
+        report(&self.events, self.name, LifecycleCall::OnOrderCancelled, &result);
+        result
+    }
+
+    fn on_order_rejected(&mut self, order: &OrderData, error: &Error<BrokerErrorKind>) -> Result<&[Instruction<'_>], Error<TradingErrorKind>> {
+        let result = self.algorithm_box.on_order_rejected(order, error);
+        report(&self.events, self.name, LifecycleCall::OnOrderRejected, &result);
+        result
+    }
+
+    fn on_stop_loss_triggered(&mut self, position: &Position) -> Result<&[Instruction<'_>], Error<TradingErrorKind>> {
+        let result = self.algorithm_box.on_stop_loss_triggered(position);
+        report(&self.events, self.name, LifecycleCall::OnStopLossTriggered, &result);
+        result
+    }
+
+    fn on_take_profit_triggered(&mut self, position: &Position) -> Result<&[Instruction<'_>], Error<TradingErrorKind>> {
+        let result = self.algorithm_box.on_take_profit_triggered(position);
+        report(&self.events, self.name, LifecycleCall::OnTakeProfitTriggered, &result);
+        result
+    }
+
+    fn on_broker_error(&mut self, error: &Error<BrokerErrorKind>) -> Result<&[Instruction<'_>], Error<TradingErrorKind>> {
+        let result = self.algorithm_box.on_broker_error(error);
+        report(&self.events, self.name, LifecycleCall::OnBrokerError, &result);
+        result
+    }
+
+    #[inline]
+    fn as_multi_asset(&mut self) -> Option<&mut dyn MultiAssetAlgorithmInterface> {
+        self.algorithm_box.as_multi_asset()
+    }
+}
+
+/// reports the result of a call to the event bus
+fn report<T>(events: &EventBus<AlgorithmEvent>, algorithm: &str, call: LifecycleCall, result: &Result<T, Error<TradingErrorKind>>) {
+    let algorithm = algorithm.to_string();
+    events.emit(match result {
+        Ok(_) => AlgorithmEvent::Called { algorithm, call },
+        Err(error) => AlgorithmEvent::CallFailed { algorithm, call, error: error.clone() },
+    });
+}
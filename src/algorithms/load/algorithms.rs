@@ -4,8 +4,9 @@ use std::fmt;
 use std::fmt::Formatter;
 use std::fs::read_dir;
 use std::path::{Path, PathBuf};
+use std::sync::Arc;
 
-use crate::{Error, ErrorKind};
+use crate::{AlgorithmEvent, AlgorithmInstance, Error, ErrorKind, EventBus, EventListener, LoadReport, Parameters};
 use crate::load::algorithm::Algorithm;
 
 #[cfg(target_os = "windows")]
@@ -16,103 +17,172 @@ const DYL_EXTENSION: &str = "so";
 #[derive(Default)]
 pub struct Algorithms {
     algorithms: HashMap<&'static str, Algorithm>,
+    events: EventBus<AlgorithmEvent>,
 }
 
 impl Algorithms {
     /// creates an empty instance of `Algorithms`
     pub fn empty() -> Self {
         Self {
-            algorithms: HashMap::new()
+            algorithms: HashMap::new(),
+            events: EventBus::new(),
         }
     }
 
+    /// subscribes a listener to the events of loading and running algorithms
+    ///
+    /// The listener also receives the events of all algorithms and instances created by
+    /// this instance of `Algorithms`.
+    pub fn subscribe(&self, listener: Arc<dyn EventListener<AlgorithmEvent>>) {
+        self.events.subscribe(listener);
+    }
+
+    pub fn events(&self) -> &EventBus<AlgorithmEvent> { &self.events }
+
     /// loads all algorithms of a directory
     ///
     /// This method provides a convenient way to search for all dynamically loaded library's in a
     /// directory. It searches for these library's in a specific pattern:
-    ///     1. Dynamically loaded library's directly places inside the directory (./)
-    ///     2. Dynamically loaded library's directly places inside a separate directory (./<library-dir>/)
-    ///     3. Dynamically loaded library's in the release directory of a crate (./<crate>/target/release/)
-    ///     4. Dynamically loaded library's in the debug directory of a crate (./<crate>/target/debug/)
+    /// 1. Dynamically loaded library's directly places inside the directory (./)
+    /// 2. Dynamically loaded library's directly places inside a separate directory (./<library-dir>/)
+    /// 3. Dynamically loaded library's in the release directory of a crate (./<crate>/target/release/)
+    /// 4. Dynamically loaded library's in the debug directory of a crate (./<crate>/target/debug/)
     ///
-    /// Duplicated algorithm names will lead to an error
-    /// If a file is no dynamic library or a folder does not contains a dynamic library
-    /// a warning message will be printed
-    pub fn load_all<P: AsRef<Path>>(&mut self, path: &P) -> Result<(), Error<ErrorKind>> {
-        for entry in read_dir(path)? {
-            let entry = entry?;
-            let path = entry.path();
+    /// Errors of single entries, like duplicated algorithm names, don't abort the search.
+    /// Instead the returned report contains all loaded, skipped and failed entries.
+    /// Each of them is also emitted as an `AlgorithmEvent`.
+    pub fn load_all<P: AsRef<Path>>(&mut self, path: &P) -> Result<LoadReport, Error<ErrorKind>> {
+        let mut report = LoadReport::default();
 
-            if path.is_dir() {
-                if let Some(library) = find_dynamic_library_in_dir(&path) {
-                    self.load(&library)?;
-                    continue;
-                }
-                if let Some(library) = find_dynamic_library_in_crate(&path) {
-                    self.load(&library)?;
+        for entry in read_dir(path)? {
+            let path = match entry {
+                Ok(entry) => entry.path(),
+                Err(error) => {
+                    let path = path.as_ref().to_path_buf();
+                    self.fail(&mut report, path, error.into());
                     continue;
                 }
-                println!("This directory does not contain any algorithms: {:?}", path);
+            };
+
+            let library = if path.is_dir() {
+                find_dynamic_library_in_dir(&path)
+                    .or_else(|| find_dynamic_library_in_crate(&path))
+                    .ok_or("the directory does not contain any algorithms")
             } else if path.is_file() {
                 match path.extension() {
-                    Some(extension) if extension == DYL_EXTENSION => self.load(&path)?,
-                    _ => println!("This file is no algorithms: {:?}", path)
+                    Some(extension) if extension == DYL_EXTENSION => Ok(path.clone()),
+                    _ => Err("the file is no dynamic library")
+                }
+            } else {
+                Err("the path is neither a directory nor a file")
+            };
+
+            match library {
+                Ok(library) => {
+                    self.events.emit(AlgorithmEvent::LibraryFound { path: library.clone() });
+                    match self.load(&library) {
+                        Ok(names) => report.loaded.push((library, names)),
+                        Err(error) => self.fail(&mut report, library, error),
+                    }
+                }
+                Err(reason) => {
+                    self.events.emit(AlgorithmEvent::Skipped { path: path.clone(), reason: reason.to_string() });
+                    report.skipped.push((path, reason.to_string()));
                 }
-            } else { unreachable!("Path is neither directory nor file") }
+            }
         }
 
-        Ok(())
+        Ok(report)
     }
 
-    /// loads an algorithm by path
-    /// for more information have a look at `Algorithm::load`
-    pub fn load<P: AsRef<OsStr>>(&mut self, path: &P) -> Result<(), Error<ErrorKind>> {
-        let algorithm = Algorithm::load(path)?;
-
-        if self.algorithms.contains_key(algorithm.name()) {
-            return Err(Error::new(
-                format!(
-                    "An algorithm with the name `{}` already exists\n\
-                    consider removing one of both",
-                    algorithm.name()
-                ),
-                ErrorKind::LibLoading,
-            ));
-        }
+    fn fail(&self, report: &mut LoadReport, path: PathBuf, error: Error<ErrorKind>) {
+        self.events.emit(AlgorithmEvent::Failed { path: path.clone(), error: error.clone() });
+        report.failed.push((path, error));
+    }
+
+    /// loads all algorithms of a library by path
+    ///
+    /// Either all or none of the algorithms exported by the library will be added.
+    /// Duplicated algorithm names, both inside the library and across already loaded
+    /// library's, will lead to an error.
+    /// Returns the names of the loaded algorithms.
+    /// for more information have a look at `Algorithm::load_all`
+    pub fn load<P: AsRef<OsStr>>(&mut self, path: &P) -> Result<Vec<String>, Error<ErrorKind>> {
+        let algorithms = Algorithm::load_all_with_events(path, &self.events)?;
+
+        for (i, algorithm) in algorithms.iter().enumerate() {
+            let duplicate = self.algorithms
+                                .get(algorithm.name())
+                                .or_else(|| algorithms[..i].iter().find(|other| other.name() == algorithm.name()));
+
+            if let Some(duplicate) = duplicate {
+                return Err(Error::new(
+                    format!(
+                        "An algorithm with the name `{}` already exists\n\
+                        ({:?} and {:?})\n\
+                        consider removing one of both",
+                        algorithm.name(),
+                        duplicate.path(), algorithm.path(),
+                    ),
+                    ErrorKind::LibLoading,
+                ));
+            }
 
-        if algorithm.max_data_length() < algorithm.min_data_length() && algorithm.max_data_length() != 0 {
-            return Err(Error::new(
-                format!(
-                    "The algorithm `{}` is not configured correctly\n\
-                    (min_data_length: {}, max_data_length: {})\n\
-                    (max_data_length needs to be greater or equal to min_data_length or needs to be equal to 0)",
-                    algorithm.name(),
-                    algorithm.min_data_length(), algorithm.max_data_length(),
-                ),
-                ErrorKind::LibLoading,
-            ));
+            if algorithm.max_data_length() < algorithm.min_data_length() && algorithm.max_data_length() != 0 {
+                return Err(Error::new(
+                    format!(
+                        "The algorithm `{}` is not configured correctly\n\
+                        (min_data_length: {}, max_data_length: {})\n\
+                        (max_data_length needs to be greater or equal to min_data_length or needs to be equal to 0)",
+                        algorithm.name(),
+                        algorithm.min_data_length(), algorithm.max_data_length(),
+                    ),
+                    ErrorKind::LibLoading,
+                ));
+            }
         }
 
-        self.algorithms.insert(
-            algorithm.name(),
-            algorithm,
-        );
+        let mut names = Vec::with_capacity(algorithms.len());
+        for algorithm in algorithms {
+            self.events.emit(AlgorithmEvent::Loaded {
+                path: algorithm.path().clone(),
+                algorithm: algorithm.name().to_string(),
+            });
+            names.push(algorithm.name().to_string());
+
+            self.algorithms.insert(
+                algorithm.name(),
+                algorithm,
+            );
+        }
 
-        Ok(())
+        Ok(names)
     }
 
     /// returns a reference to a `Algorithm`
-    /// can be used to trade using this algorithm
+    /// can be used to spawn instances that trade using this algorithm
     pub fn get(&self, algorithm: &str) -> Option<&Algorithm> {
         self.algorithms.get(algorithm)
     }
 
     /// returns a mutable reference to a `Algorithm`
-    /// can be used to trade using this algorithm
+    /// can be used to change the parameters of this algorithm
     pub fn get_mut(&mut self, algorithm: &str) -> Option<&mut Algorithm> {
         self.algorithms.get_mut(algorithm)
     }
 
+    /// creates a new instance of an `Algorithm` with user supplied parameters
+    /// for more information have a look at `Algorithm::spawn_with_parameters`
+    pub fn spawn(&self, algorithm: &str, parameters: &Parameters) -> Result<AlgorithmInstance, Error<ErrorKind>> {
+        self.algorithms
+            .get(algorithm)
+            .ok_or_else(|| Error::new(
+                format!("There is no algorithm called `{}`", algorithm),
+                ErrorKind::Other,
+            ))?
+            .spawn_with_parameters(parameters)
+    }
+
     pub fn contains(&self, name: &str) -> bool {
         self.algorithms.contains_key(name)
     }
@@ -120,7 +190,7 @@ impl Algorithms {
 
 impl fmt::Display for Algorithms {
     fn fmt(&self, formatter: &mut Formatter<'_>) -> fmt::Result {
-        let algorithms = if self.algorithms.len() > 0 {
+        let algorithms = if !self.algorithms.is_empty() {
             self.algorithms
                 .iter()
                 .fold(
@@ -143,15 +213,13 @@ impl fmt::Display for Algorithms {
 /// it can find.
 pub fn find_dynamic_library_in_dir<P: AsRef<Path>>(path: &P) -> Option<PathBuf> {
     if let Ok(dir) = read_dir(path) {
-        for entry in dir {
-            if let Ok(entry) = entry {
-                let path = entry.path();
-
-                if path.is_file() {
-                    if let Some(extension) = path.extension() {
-                        if extension == DYL_EXTENSION {
-                            return Some(path);
-                        }
+        for entry in dir.flatten() {
+            let path = entry.path();
+
+            if path.is_file() {
+                if let Some(extension) = path.extension() {
+                    if extension == DYL_EXTENSION {
+                        return Some(path);
                     }
                 }
             }
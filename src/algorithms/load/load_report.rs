@@ -0,0 +1,49 @@
+use std::fmt;
+use std::fmt::Formatter;
+use std::path::PathBuf;
+
+use crate::{Error, ErrorKind};
+
+/// The result of `Algorithms::load_all`
+///
+/// #### Fields:
+/// * __loaded__: The library's that were loaded and the names of the algorithms they contain
+/// * __skipped__: The files and directories that don't contain any algorithms and the reason why
+/// * __failed__: The library's that could not be loaded and the corresponding error
+#[derive(Clone, Debug, Default)]
+pub struct LoadReport {
+    pub loaded: Vec<(PathBuf, Vec<String>)>,
+    pub skipped: Vec<(PathBuf, String)>,
+    pub failed: Vec<(PathBuf, Error<ErrorKind>)>,
+}
+
+impl LoadReport {
+    pub fn is_success(&self) -> bool {
+        self.failed.is_empty()
+    }
+
+    /// returns the names of all loaded algorithms
+    pub fn algorithms(&self) -> impl Iterator<Item=&str> {
+        self.loaded
+            .iter()
+            .flat_map(|(_, names)| names.iter().map(String::as_str))
+    }
+}
+
+impl fmt::Display for LoadReport {
+    fn fmt(&self, formatter: &mut Formatter<'_>) -> fmt::Result {
+        writeln!(formatter, "LOADED:")?;
+        for (path, names) in &self.loaded {
+            writeln!(formatter, "\t{:?}: {}", path, names.join(", "))?;
+        }
+        writeln!(formatter, "SKIPPED:")?;
+        for (path, reason) in &self.skipped {
+            writeln!(formatter, "\t{:?}: {}", path, reason)?;
+        }
+        write!(formatter, "FAILED:")?;
+        for (path, error) in &self.failed {
+            write!(formatter, "\n\t{:?}: {}", path, error.msg().replace('\n', "\n\t\t"))?;
+        }
+        Ok(())
+    }
+}
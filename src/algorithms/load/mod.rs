@@ -1,8 +1,14 @@
 pub use algorithm::*;
+pub use algorithm_event::*;
+pub use algorithm_instance::*;
 pub use algorithms::*;
+pub use load_report::*;
 
 pub mod algorithms;
 pub mod algorithm;
+pub mod algorithm_event;
+pub mod algorithm_instance;
+pub mod load_report;
 
 
 #[macro_export]
@@ -0,0 +1,174 @@
+use std::collections::BTreeMap;
+
+use chrono::{DateTime, Local};
+
+use crate::{Candle, Price};
+
+/// The candles of a single symbol inside of an `AlignedSeries`
+#[derive(Clone, Debug, PartialEq)]
+pub struct SymbolSeries {
+    symbol: String,
+    candles: Vec<Candle>,
+    prices: Vec<Price>,
+}
+
+impl SymbolSeries {
+    pub fn symbol(&self) -> &str { &self.symbol }
+    pub fn candles(&self) -> &[Candle] { &self.candles }
+    pub fn prices(&self) -> &[Price] { &self.prices }
+}
+
+/// Candle series of multiple symbols that share the same timestamps
+///
+/// The i-th candle of every symbol belongs to the i-th timestamp. Timestamps that are not
+/// available for all symbols are dropped, so the series can be compared directly
+/// (for example for pairs trading or spreads).
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct AlignedSeries {
+    times: Vec<DateTime<Local>>,
+    series: Vec<SymbolSeries>,
+}
+
+impl AlignedSeries {
+    /// creates an empty series for the given symbols
+    pub fn empty<S: AsRef<str>>(symbols: &[S]) -> Self {
+        Self {
+            times: Vec::new(),
+            series: symbols
+                .iter()
+                .map(|symbol| SymbolSeries {
+                    symbol: symbol.as_ref().to_string(),
+                    candles: Vec::new(),
+                    prices: Vec::new(),
+                })
+                .collect(),
+        }
+    }
+
+    /// aligns the candles of multiple symbols by their timestamp
+    ///
+    /// Only timestamps that exist in every series are kept.
+    pub fn align(series: &[(&str, &[Candle])]) -> Self {
+        let symbols = series
+            .iter()
+            .map(|(symbol, _)| *symbol)
+            .collect::<Vec<_>>();
+        let mut aligner = SeriesAligner::new(&symbols);
+
+        for (symbol, candles) in series {
+            for candle in *candles {
+                aligner.push(symbol, *candle);
+            }
+        }
+
+        aligner.into_series()
+    }
+
+    pub fn times(&self) -> &[DateTime<Local>] { &self.times }
+    pub fn series(&self) -> &[SymbolSeries] { &self.series }
+    pub fn len(&self) -> usize { self.times.len() }
+    pub fn is_empty(&self) -> bool { self.times.is_empty() }
+
+    pub fn symbols(&self) -> impl Iterator<Item=&str> {
+        self.series
+            .iter()
+            .map(|series| series.symbol.as_str())
+    }
+
+    pub fn get(&self, symbol: &str) -> Option<&SymbolSeries> {
+        self.series
+            .iter()
+            .find(|series| series.symbol == symbol)
+    }
+
+    pub fn candles(&self, symbol: &str) -> Option<&[Candle]> {
+        self.get(symbol).map(SymbolSeries::candles)
+    }
+
+    pub fn prices(&self, symbol: &str) -> Option<&[Price]> {
+        self.get(symbol).map(SymbolSeries::prices)
+    }
+
+    /// removes the oldest timestamps until at most `max_length` remain
+    ///
+    /// A `max_length` of 0 means unlimited.
+    pub fn truncate_front(&mut self, max_length: usize) {
+        if max_length == 0 || self.times.len() <= max_length {
+            return;
+        }
+        let remove = self.times.len() - max_length;
+
+        self.times.drain(..remove);
+        for series in self.series.iter_mut() {
+            series.candles.drain(..remove);
+            series.prices.drain(..remove);
+        }
+    }
+
+    fn push_row(&mut self, time: DateTime<Local>, candles: Vec<Candle>) {
+        self.times.push(time);
+        for (series, candle) in self.series.iter_mut().zip(candles) {
+            series.candles.push(candle);
+            series.prices.push(candle.close);
+        }
+    }
+}
+
+/// Aligns candles of multiple symbols while they arrive
+///
+/// Candles can be pushed in any order. As soon as a timestamp is complete, which means every
+/// symbol has a candle for it, the row is appended to the aligned series. Incomplete timestamps
+/// that are older than a completed one are dropped, since they can't be completed anymore
+/// without breaking the order of the series.
+#[derive(Clone, Debug)]
+pub struct SeriesAligner {
+    pending: BTreeMap<DateTime<Local>, Vec<Option<Candle>>>,
+    aligned: AlignedSeries,
+}
+
+impl SeriesAligner {
+    pub fn new<S: AsRef<str>>(symbols: &[S]) -> Self {
+        Self {
+            pending: BTreeMap::new(),
+            aligned: AlignedSeries::empty(symbols),
+        }
+    }
+
+    /// adds the candle of a symbol
+    ///
+    /// Returns true if a new aligned row was completed.
+    /// Candles of unknown symbols and candles that are older than the last aligned row are ignored.
+    pub fn push(&mut self, symbol: &str, candle: Candle) -> bool {
+        let index = match self.aligned.series.iter().position(|series| series.symbol == symbol) {
+            Some(index) => index,
+            None => return false
+        };
+        if self.aligned.times.last().is_some_and(|last| candle.time <= *last) {
+            return false;
+        }
+
+        let symbols = self.aligned.series.len();
+        let row = self.pending
+                      .entry(candle.time)
+                      .or_insert_with(|| vec![None; symbols]);
+        row[index] = Some(candle);
+
+        if row.iter().all(Option::is_some) {
+            let time = candle.time;
+            let candles = row.iter().flatten().copied().collect();
+
+            // everything up to this timestamp is either completed or can't be completed anymore
+            self.pending = self.pending.split_off(&time);
+            self.pending.remove(&time);
+            self.aligned.push_row(time, candles);
+
+            true
+        } else {
+            false
+        }
+    }
+
+    pub fn series(&self) -> &AlignedSeries { &self.aligned }
+    pub fn series_mut(&mut self) -> &mut AlignedSeries { &mut self.aligned }
+    pub fn into_series(self) -> AlignedSeries { self.aligned }
+}
@@ -0,0 +1,122 @@
+use crate::{OrderData, OrderMoment, OrderType, OrderValidity, Position, Price, StopLoss, TakeProfit};
+
+/// An entry order that is protected by a take profit and a stop loss once it's filled
+///
+/// The take profit and the stop loss of the entry are the levels of the bracket. When the entry
+/// is filled the bracket gets active: it spawns a limit order at the take profit and a stop
+/// order at the stop loss, both for the filled pieces. Relative levels are resolved against
+/// the fill price. The two children cancel each other, as soon as one of them closes the
+/// position the other one is cancelled.
+///
+/// The bracket stays a single `Order` in the order list of a `Deposit`, so its children can be
+/// found, changed and cancelled by their ids like any other order data.
+/// ```
+/// # use chrono::Local;
+/// # use trading_utils::*;
+/// let share = Derivative::new("SAP".to_string(), DerivativeKind::Stock, Currency::EUR);
+/// let entry = OrderData::new(
+///     "entry".to_string(), share, StockExchange::LSExchange, 10,
+///     OrderType::LimitOrder(Price::from(100.0)), PositionType::LongCall,
+///     TakeProfit::Relative(Price::from(10.0)), StopLoss::Relative(Price::from(-5.0)),
+///     OrderMoment::Instant, OrderValidity::OneDay,
+/// );
+/// let mut bracket = Bracket::new(entry);
+/// assert_eq!(bracket.data()[0].raw_id(), "entry");
+///
+/// let position = Position::from_order(Order::Bracket(bracket.clone()), Local::now(), Price::from(98.0)).unwrap();
+/// bracket.activate(&position, Price::from(98.0));
+///
+/// assert_eq!(bracket.take_profit_order().unwrap().order_type(), &OrderType::LimitOrder(Price::from(108.0)));
+/// assert_eq!(bracket.stop_loss_order().unwrap().order_type(), &OrderType::StopOrder(Price::from(93.0)));
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Bracket {
+    entry: OrderData,
+    position: Option<String>,
+    children: Vec<OrderData>,
+}
+
+impl Bracket {
+    pub fn new(entry: OrderData) -> Self {
+        Self {
+            entry,
+            position: None,
+            children: Vec::new(),
+        }
+    }
+
+    pub fn entry(&self) -> &OrderData { &self.entry }
+    /// the id of the position the entry opened
+    pub fn position(&self) -> Option<&String> { self.position.as_ref() }
+    /// the take profit and the stop loss, empty until the entry is filled
+    pub fn children(&self) -> &[OrderData] { &self.children }
+    /// returns true if the entry was filled
+    pub fn is_active(&self) -> bool { self.position.is_some() }
+
+    /// the limit order that takes the profit
+    pub fn take_profit_order(&self) -> Option<&OrderData> {
+        self.children
+            .iter()
+            .find(|child| matches!(child.order_type(), OrderType::LimitOrder(_)))
+    }
+
+    /// the stop order that limits the loss
+    pub fn stop_loss_order(&self) -> Option<&OrderData> {
+        self.children
+            .iter()
+            .find(|child| matches!(child.order_type(), OrderType::StopOrder(_)))
+    }
+
+    /// returns the entry or, once the bracket is active, its children
+    pub fn data(&self) -> &[OrderData] {
+        if self.is_active() {
+            &self.children
+        } else {
+            std::slice::from_ref(&self.entry)
+        }
+    }
+
+    pub fn data_mut(&mut self) -> &mut [OrderData] {
+        if self.is_active() {
+            &mut self.children
+        } else {
+            std::slice::from_mut(&mut self.entry)
+        }
+    }
+
+    /// activates the bracket after the entry was filled at the fill price
+    ///
+    /// The children get the raw id of the entry with a `-take-profit` or `-stop-loss` suffix,
+    /// the pieces of the position and are valid until they are cancelled. A trailing stop loss
+    /// keeps its distance in the stop loss of its child, so it can be trailed.
+    pub fn activate(&mut self, position: &Position, fill_price: Price) {
+        let child = |suffix: &str, order_type: OrderType, stop_loss: StopLoss| OrderData::new(
+            format!("{}-{}", self.entry.raw_id(), suffix),
+            self.entry.derivative().clone(),
+            self.entry.stock_exchange(),
+            position.pieces(),
+            order_type,
+            self.entry.position_type(),
+            TakeProfit::None,
+            stop_loss,
+            OrderMoment::Instant,
+            OrderValidity::Forever,
+        );
+
+        let mut children = Vec::new();
+        if let Some(level) = self.entry.take_profit().level(fill_price) {
+            children.push(child("take-profit", OrderType::LimitOrder(level), StopLoss::None));
+        }
+        if let Some(level) = self.entry.stop_loss().level(fill_price) {
+            let trailing = match self.entry.stop_loss() {
+                StopLoss::Trailing(distance) => StopLoss::Trailing(*distance),
+                _ => StopLoss::None,
+            };
+            children.push(child("stop-loss", OrderType::StopOrder(level), trailing));
+        }
+
+        self.position = Some(position.id.clone());
+        self.children = children;
+    }
+}
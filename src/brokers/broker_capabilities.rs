@@ -15,6 +15,7 @@ pub enum BrokerCapability {
     SellLimitOrder,
     SellStopOrder,
 
+    OneCancelsTheOtherOrder,
     AllOrNoneOrder,
     ImmediateOrCancelOrder,
     FillOrKillOrder,
@@ -3,6 +3,7 @@ use std::fmt;
 use crate::GeneralErrorKind;
 
 #[derive(Debug, Copy, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(rename_all = "snake_case"))]
 pub enum BrokerErrorKind {
     ConnectionFailed,
     CouldNotLogin,
@@ -15,8 +15,8 @@ pub trait BrokerInterface {
 
     fn all_orders(&self) -> Result<&[Order], Error<BrokerErrorKind>>;
     fn get_order(&self) -> Result<&Order, Error<BrokerErrorKind>>;
-    fn change_order(&self) -> Result<(), Error<BrokerErrorKind>>;
-    fn delete_order(&self) -> Result<Order, Error<BrokerErrorKind>>;
+    fn change_order(&self, deposit: &mut Deposit, order: u64) -> Result<(), Error<BrokerErrorKind>>;
+    fn delete_order(&self, deposit: &mut Deposit, order: u64) -> Result<Order, Error<BrokerErrorKind>>;
     fn update_deposit_orders(&self, deposit: &mut Deposit) -> Result<(), Error<BrokerErrorKind>>;
 
     fn all_positions(&self) -> Result<&[Position], Error<BrokerErrorKind>>;
@@ -25,4 +25,16 @@ pub trait BrokerInterface {
 
     fn buy(&self, deposit: &mut Deposit, order: u64) -> Result<&Position, Error<BrokerErrorKind>>;
     fn sell(&self, deposit: &mut Deposit, position: u64) -> Result<Position, Error<BrokerErrorKind>>;
+    fn sell_partially(&self, deposit: &mut Deposit, position: u64, pieces: u64) -> Result<Position, Error<BrokerErrorKind>>;
+
+    /// places the exits of an active `Bracket` of the deposit, they close its open position
+    ///
+    /// Brokers that can't protect existing positions keep the default, which returns an error.
+    #[allow(unused)]
+    fn place_exits(&self, deposit: &mut Deposit, order: u64) -> Result<(), Error<BrokerErrorKind>> {
+        Err(Error::new(
+            format!("{} can't place exits for existing positions", Self::NAME),
+            BrokerErrorKind::Other,
+        ))
+    }
 }
\ No newline at end of file
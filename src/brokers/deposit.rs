@@ -3,7 +3,9 @@ use std::hash::{Hash, Hasher};
 
 use crate::{Currency, MarketValue, Order, OrderData, Position, Price, Transaction};
 
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(from = "DepositFields"))]
 pub struct Deposit {
+    #[cfg_attr(feature = "serde", serde(serialize_with = "crate::serde_util::serialize_id"))]
     id: u64,
     raw_id: String,
 
@@ -33,11 +35,22 @@ impl Deposit {
     }
 
     pub const fn id(&self) -> u64 { self.id }
+    pub fn raw_id(&self) -> &String { &self.raw_id }
     pub const fn currency(&self) -> Currency { self.currency }
+    pub const fn balance(&self) -> Price { self.balance }
     pub fn transactions(&self) -> &Vec<Transaction> { &self.transactions }
     pub fn orders(&self) -> &Vec<Order> { &self.orders }
     pub fn positions(&self) -> &Vec<Position> { &self.positions }
 
+    /// replaces the balance, used by brokers to reconcile the deposit
+    pub fn update_balance(&mut self, balance: Price) { self.balance = balance }
+    /// replaces the transactions, used by brokers to reconcile the deposit
+    pub fn update_transactions(&mut self, transactions: Vec<Transaction>) { self.transactions = transactions }
+    /// replaces the open orders, used by brokers to reconcile the deposit
+    pub fn update_orders(&mut self, orders: Vec<Order>) { self.orders = orders }
+    /// replaces the open positions, used by brokers to reconcile the deposit
+    pub fn update_positions(&mut self, positions: Vec<Position>) { self.positions = positions }
+
     pub fn order_id_exists(&self, id: u64) -> bool {
         self.orders
             .iter()
@@ -47,6 +60,14 @@ impl Deposit {
 
     pub fn add_order(&mut self, order: Order) { self.orders.push(order); }
 
+    /// removes the order that contains the order data with the given id
+    pub fn remove_order(&mut self, order_id: u64) -> Option<Order> {
+        let index = self.orders
+                        .iter()
+                        .position(|order| order.has_id(order_id))?;
+        Some(self.orders.remove(index))
+    }
+
     pub fn change_order(&mut self, order_id: u64) -> Option<&mut OrderData> {
         for order in self.orders.iter_mut() {
             let order_option = order.find_order_mut(order_id);
@@ -56,4 +77,31 @@ impl Deposit {
         }
         None
     }
-}
\ No newline at end of file
+}
+
+/// The serialized fields of a `Deposit`
+///
+/// The id is not read, since the hash of the raw id isn't stable between versions of rustc.
+/// It's computed again by `Deposit::empty`.
+#[cfg(feature = "serde")]
+#[derive(serde::Deserialize)]
+struct DepositFields {
+    raw_id: String,
+    currency: Currency,
+    transactions: Vec<Transaction>,
+    balance: Price,
+    orders: Vec<Order>,
+    positions: Vec<Position>,
+}
+
+#[cfg(feature = "serde")]
+impl From<DepositFields> for Deposit {
+    fn from(fields: DepositFields) -> Self {
+        let mut deposit = Deposit::empty(fields.raw_id, fields.currency);
+        deposit.transactions = fields.transactions;
+        deposit.balance = fields.balance;
+        deposit.orders = fields.orders;
+        deposit.positions = fields.positions;
+        deposit
+    }
+}
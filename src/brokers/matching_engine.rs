@@ -0,0 +1,351 @@
+use std::collections::HashMap;
+
+use chrono::{DateTime, Local};
+
+use crate::{Bracket, BrokerErrorKind, Candle, Deposit, Derivative, Direction, Error, Fill, MarketValue, Order, OrderData, OrderEvent, OrderMoment, OrderType, Position, Price, Transaction, TransactionKind};
+
+/// Fills the open orders of a `Deposit` against candles, like a broker would
+///
+/// The engine is the core of simulated brokers, an `Executor` with
+/// `Executor::with_matching_engine` uses it to simulate the broker of a session. For every
+/// candle of a derivative it checks the open orders of the derivative:
+/// * market orders are filled at the open
+/// * limit orders are filled at their price or better, if the candle reached it
+/// * stop orders are filled at their price or worse, if the candle reached it
+///
+/// Orders that buy open long positions and orders that sell open short positions, see
+/// `PositionType::direction`. Every fill opens a new position and changes the balance of the
+/// deposit by its value and the fee. The fills are booked as `Buy` or `Sell` transactions and
+/// the fees as `Fee` transactions, so the transactions always add up to the balance. The first
+/// fill of a deposit with a balance but without transactions books the balance as `CashIn`.
+/// * __OneCancelsTheOther__: the first leg that can be filled is filled, the others are cancelled
+/// * __AllOrNone__: all legs are filled once all of them can be filled
+/// * __ImmediateOrCancel__, __FillOrKill__: are cancelled if they can't be filled by the first
+///   candle
+/// * __Bracket__: the entry opens a position and activates the bracket, its children are
+///   checked from the next candle on. A bracket without take profit and stop loss is done
+///   once its entry is filled. If both children could be filled by the same candle the
+///   stop loss is filled, since the order of the prices within a candle is unknown. The bracket
+///   is cancelled if its position was closed otherwise.
+///
+/// Orders planned for a later moment wait until the candle reaches it. The `OrderValidity` of an
+/// order starts with the first candle that checks it, or with its planned moment. Orders whose
+/// validity ended are cancelled, the exits of an active bracket count from its activation.
+///
+/// A candle only checks the legs of its own symbol. So a `OneCancelsTheOther` order can have
+/// legs for several symbols, but all legs of `AllOrNone` and `FillOrKill` orders need to trade
+/// the same symbol.
+/// ```
+/// # use chrono::{Duration, Local};
+/// # use trading_utils::*;
+/// let share = Derivative::new("SAP".to_string(), DerivativeKind::Stock, Currency::EUR);
+/// let mut deposit = Deposit::empty("deposit".to_string(), Currency::EUR);
+/// deposit.update_balance(Price::from(1_000.0));
+/// deposit.add_order(Order::Bracket(Bracket::new(OrderData::new(
+///     "entry".to_string(), share, StockExchange::LSExchange, 10,
+///     OrderType::LimitOrder(Price::from(50.0)), PositionType::LongCall,
+///     TakeProfit::Relative(Price::from(5.0)), StopLoss::Relative(Price::from(-2.0)),
+///     OrderMoment::Instant, OrderValidity::OneDay,
+/// ))));
+///
+/// let mut engine = MatchingEngine::new();
+/// let candle = |day, open, high, low| Candle::new(
+///     Local::now() + Duration::days(day), Price::from(open), Price::from(high), Price::from(low), Price::from(open), 0,
+/// );
+///
+/// // the entry is filled at 49, so the take profit is at 54 and the stop loss at 47
+/// let events = engine.process(&mut deposit, "SAP", &candle(0, 49.0, 51.0, 48.0));
+/// assert!(matches!(events[0], OrderEvent::Filled { .. }));
+/// assert_eq!(deposit.balance(), Price::from(510.0));
+///
+/// let events = engine.process(&mut deposit, "SAP", &candle(1, 52.0, 55.0, 51.0));
+/// assert!(matches!(events[0], OrderEvent::TakeProfitTriggered { .. }));
+/// assert!(matches!(events[1], OrderEvent::Cancelled { .. }));
+/// assert_eq!(deposit.balance(), Price::from(1_050.0));
+/// assert!(deposit.orders().is_empty() && deposit.positions().is_empty());
+///
+/// // the initial balance, the buy and the sell
+/// assert_eq!(deposit.transactions().len(), 3);
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct MatchingEngine {
+    fee: Price,
+    /// the time each open order was checked first, by the id of its first leg
+    placed: HashMap<u64, DateTime<Local>>,
+}
+
+impl MatchingEngine {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// charges a fee for every fill
+    pub fn with_fee(mut self, fee: Price) -> Self {
+        self.fee = fee;
+        self
+    }
+
+    pub fn fee(&self) -> Price { self.fee }
+
+    /// fills the open orders of the symbol that the candle reached
+    ///
+    /// Returns the resulting events in the order they happened.
+    pub fn process(&mut self, deposit: &mut Deposit, symbol: &str, candle: &Candle) -> Vec<OrderEvent> {
+        let mut events = Vec::new();
+        let mut remaining = Vec::new();
+
+        for order in deposit.orders().clone() {
+            let trades_symbol = order
+                .data()
+                .iter()
+                .any(|order_data| order_data.derivative().symbol == symbol);
+            if !trades_symbol {
+                remaining.push(order);
+                continue;
+            }
+
+            let placed = *self.placed
+                .entry(order.data()[0].id())
+                .or_insert(candle.time);
+            if is_expired(&order.data()[0], placed, candle.time) {
+                for order_data in order.data() {
+                    events.push(OrderEvent::Cancelled { order: order_data.clone() });
+                }
+                continue;
+            }
+
+            let kept = match order {
+                Order::Bracket(bracket) if bracket.is_active() => self.process_exits(deposit, bracket, candle, &mut events),
+                order => self.process_entries(deposit, order, symbol, candle, &mut events),
+            };
+            remaining.extend(kept);
+        }
+
+        self.placed.retain(|id, _| remaining.iter().any(|order| order.data().first().map(OrderData::id) == Some(*id)));
+        deposit.update_orders(remaining);
+        events
+    }
+
+    /// closes a position, or only some pieces of it, at the close of the candle
+    ///
+    /// The closed pieces are booked like a fill. A position without pieces is removed from the
+    /// deposit, an active bracket for it is cancelled with the next candle.
+    pub fn close(&self, deposit: &mut Deposit, position: u64, pieces: Option<u64>, candle: &Candle) -> Result<Position, Error<BrokerErrorKind>> {
+        let mut positions = deposit.positions().clone();
+        let index = positions
+            .iter()
+            .position(|open| open.hashed_id() == position)
+            .ok_or_else(|| Error::new(format!("The position {} does not exist", position), BrokerErrorKind::NoSuchPosition))?;
+
+        let open = &mut positions[index];
+        let pieces = pieces.unwrap_or(open.pieces());
+        open.scale_out(Fill::new(candle.time, pieces, candle.close).with_fee(self.fee))
+            .map_err(|error| Error::new(error.msg().to_string(), BrokerErrorKind::Other))?;
+        let closed = open.clone();
+        if !closed.is_open() {
+            positions.remove(index);
+        }
+
+        let value = closed.derivative().notional(candle.close, pieces);
+        let amount = match closed.direction() {
+            Direction::Long => value,
+            Direction::Short => Price::zero() - value,
+        };
+        self.book(deposit, &closed.id, candle.time, closed.derivative(), amount);
+        deposit.update_positions(positions);
+
+        Ok(closed)
+    }
+
+    /// fills the entries of an order, returns the order if it's still open
+    fn process_entries(&self, deposit: &mut Deposit, order: Order, symbol: &str, candle: &Candle, events: &mut Vec<OrderEvent>) -> Option<Order> {
+        let prices: Vec<Option<Price>> = order
+            .data()
+            .iter()
+            .map(|order_data| if order_data.derivative().symbol == symbol {
+                fill_price(order_data, order_data.position_type().direction(), candle)
+            } else {
+                None
+            })
+            .collect();
+        let original = order.clone();
+
+        match order {
+            Order::Single(order_data) | Order::ImmediateOrCancel(order_data) if prices[0].is_some() => {
+                self.open(deposit, &original, &order_data, prices[0], candle, events);
+                None
+            }
+            Order::Bracket(mut bracket) => match prices[0] {
+                Some(price) => {
+                    let position = self.open(deposit, &original, bracket.entry(), Some(price), candle, events);
+                    bracket.activate(&position, price);
+                    // without exits there's nothing left to do
+                    Some(Order::Bracket(bracket)).filter(|order| !order.data().is_empty())
+                }
+                None => Some(Order::Bracket(bracket)),
+            },
+            Order::OneCancelsTheOther(data) => match prices.iter().position(Option::is_some) {
+                Some(filled) => {
+                    for (index, order_data) in data.iter().enumerate() {
+                        if index == filled {
+                            self.open(deposit, &original, order_data, prices[index], candle, events);
+                        } else {
+                            events.push(OrderEvent::Cancelled { order: order_data.clone() });
+                        }
+                    }
+                    None
+                }
+                None => Some(Order::OneCancelsTheOther(data)),
+            },
+            Order::AllOrNone(data) | Order::FillOrKill(data) if prices.iter().all(Option::is_some) => {
+                for (order_data, price) in data.iter().zip(prices) {
+                    self.open(deposit, &original, order_data, price, candle, events);
+                }
+                None
+            }
+            order @ Order::ImmediateOrCancel(_) | order @ Order::FillOrKill(_) => {
+                for order_data in order.data() {
+                    events.push(OrderEvent::Cancelled { order: order_data.clone() });
+                }
+                None
+            }
+            order => Some(order),
+        }
+    }
+
+    /// fills the take profit or the stop loss of an active bracket, returns the bracket if it's
+    /// still open
+    fn process_exits(&self, deposit: &mut Deposit, bracket: Bracket, candle: &Candle, events: &mut Vec<OrderEvent>) -> Option<Order> {
+        let index = deposit
+            .positions()
+            .iter()
+            .position(|position| Some(&position.id) == bracket.position() && position.is_open());
+        let index = match index {
+            Some(index) => index,
+            None => {
+                for child in bracket.children() {
+                    events.push(OrderEvent::Cancelled { order: child.clone() });
+                }
+                return None;
+            }
+        };
+
+        // the children close the position, so they trade in the opposite direction
+        let exit = deposit.positions()[index].direction().opposite();
+        let stop_loss = bracket
+            .stop_loss_order()
+            .and_then(|child| Some((child, fill_price(child, exit, candle)?)));
+        let take_profit = bracket
+            .take_profit_order()
+            .and_then(|child| Some((child, fill_price(child, exit, candle)?)));
+        let (filled, price) = match stop_loss.or(take_profit) {
+            Some(filled) => filled,
+            None => return Some(Order::Bracket(bracket)),
+        };
+
+        // the position may have been reduced since the bracket was activated
+        let mut positions = deposit.positions().clone();
+        let mut position = positions.remove(index);
+        let pieces = position.pieces();
+        if position.close(candle.time, price, self.fee).is_err() {
+            return Some(Order::Bracket(bracket));
+        }
+        let value = position.derivative().notional(price, pieces);
+        let amount = match position.direction() {
+            Direction::Long => value,
+            Direction::Short => Price::zero() - value,
+        };
+        self.book(deposit, filled.raw_id(), candle.time, position.derivative(), amount);
+        deposit.update_positions(positions);
+
+        let triggered = matches!(filled.order_type(), OrderType::StopOrder(_));
+        events.push(if triggered {
+            OrderEvent::StopLossTriggered { position }
+        } else {
+            OrderEvent::TakeProfitTriggered { position }
+        });
+        for child in bracket.children().iter().filter(|child| child.id() != filled.id()) {
+            events.push(OrderEvent::Cancelled { order: child.clone() });
+        }
+
+        None
+    }
+
+    /// opens a position for a filled order data and pays for it
+    fn open(&self, deposit: &mut Deposit, order: &Order, order_data: &OrderData, price: Option<Price>, candle: &Candle, events: &mut Vec<OrderEvent>) -> Position {
+        let price = price.unwrap_or(candle.open);
+        let position = Position::new(
+            order_data.raw_id().clone(),
+            order_data.derivative().clone(),
+            order_data.position_type(),
+            order.clone(),
+            Fill::new(candle.time, order_data.pieces(), price).with_fee(self.fee),
+        );
+
+        let value = order_data.derivative().notional(price, order_data.pieces());
+        let amount = match position.direction() {
+            Direction::Long => Price::zero() - value,
+            Direction::Short => value,
+        };
+        self.book(deposit, order_data.raw_id(), candle.time, order_data.derivative(), amount);
+
+        let mut positions = deposit.positions().clone();
+        positions.push(position.clone());
+        deposit.update_positions(positions);
+
+        events.push(OrderEvent::Filled { order: order_data.clone(), position: position.clone() });
+        position
+    }
+
+    /// changes the balance by the amount of a fill and the fee and books both as transactions
+    fn book(&self, deposit: &mut Deposit, raw_id: &str, time: DateTime<Local>, derivative: &Derivative, amount: Price) {
+        let currency = deposit.currency();
+        let mut transactions = deposit.transactions().clone();
+        if transactions.is_empty() && deposit.balance() != Price::zero() {
+            transactions.push(Transaction::new(format!("{}-balance", raw_id), time, TransactionKind::CashIn, deposit.balance(), currency));
+        }
+
+        let kind = if amount < Price::zero() { TransactionKind::Buy } else { TransactionKind::Sell };
+        transactions.push(Transaction::new(raw_id.to_string(), time, kind, amount, currency).with_derivative(derivative.clone()));
+        if self.fee != Price::zero() {
+            transactions.push(Transaction::new(format!("{}-fee", raw_id), time, TransactionKind::Fee, Price::zero() - self.fee, currency).with_derivative(derivative.clone()));
+        }
+
+        deposit.update_balance(deposit.balance() + amount - self.fee);
+        deposit.update_transactions(transactions);
+    }
+}
+
+/// returns true if the validity of an order that was placed at the time ended before the candle
+fn is_expired(order_data: &OrderData, placed: DateTime<Local>, time: DateTime<Local>) -> bool {
+    let start = match order_data.moment() {
+        OrderMoment::Planed(moment) if *moment > placed => *moment,
+        _ => placed,
+    };
+    order_data
+        .validity()
+        .expiry(start)
+        .is_some_and(|expiry| time >= expiry)
+}
+
+/// the price the candle fills the order data at, if it fills it at all
+///
+/// Orders in the long direction buy, so limits are filled at or below and stops at or above
+/// their price. Orders in the short direction sell, so it's the other way around.
+fn fill_price(order_data: &OrderData, direction: Direction, candle: &Candle) -> Option<Price> {
+    if let OrderMoment::Planed(moment) = order_data.moment() {
+        if candle.time < *moment {
+            return None;
+        }
+    }
+
+    let min = |a: Price, b: Price| if a < b { a } else { b };
+    let max = |a: Price, b: Price| if a > b { a } else { b };
+    match (order_data.order_type(), direction) {
+        (OrderType::MarketOrder, _) => Some(candle.open),
+        (OrderType::LimitOrder(limit), Direction::Long) => (candle.low <= *limit).then(|| min(candle.open, *limit)),
+        (OrderType::LimitOrder(limit), Direction::Short) => (candle.high >= *limit).then(|| max(candle.open, *limit)),
+        (OrderType::StopOrder(stop), Direction::Long) => (candle.high >= *stop).then(|| max(candle.open, *stop)),
+        (OrderType::StopOrder(stop), Direction::Short) => (candle.low <= *stop).then(|| min(candle.open, *stop)),
+    }
+}
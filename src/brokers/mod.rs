@@ -2,8 +2,10 @@ pub use broker_capabilities::*;
 pub use broker_error::*;
 pub use broker_interface::*;
 pub use deposit::*;
+pub use matching_engine::*;
 
 pub mod broker_capabilities;
 pub mod broker_error;
 pub mod broker_interface;
 pub mod deposit;
+pub mod matching_engine;
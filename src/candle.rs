@@ -0,0 +1,92 @@
+use chrono::{DateTime, Local};
+
+use crate::{MarketValue, Price};
+
+/// A single OHLCV candle
+///
+/// #### Fields:
+/// * __time__: The start of the period the candle covers
+/// * __open__, __high__, __low__, __close__: The prices of the period
+/// * __volume__: The amount of pieces traded in the period
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Candle {
+    pub time: DateTime<Local>,
+    pub open: Price,
+    pub high: Price,
+    pub low: Price,
+    pub close: Price,
+    pub volume: u64,
+}
+
+impl Candle {
+    pub fn new(time: DateTime<Local>, open: Price, high: Price, low: Price, close: Price, volume: u64) -> Self {
+        Self {
+            time,
+            open,
+            high,
+            low,
+            close,
+            volume,
+        }
+    }
+
+    /// creates a candle from a single price, for example a tick
+    pub fn from_price(time: DateTime<Local>, price: Price) -> Self {
+        Self::new(time, price, price, price, price, 0)
+    }
+
+    /// returns the close prices of candles
+    pub fn closes(candles: &[Candle]) -> Vec<Price> {
+        candles
+            .iter()
+            .map(|candle| candle.close)
+            .collect()
+    }
+
+    /// returns the largest of the high minus the low, and the distances of the high and the low
+    /// to the previous close
+    pub fn true_range(&self, previous_close: Option<Price>) -> Price {
+        let range = self.high - self.low;
+        match previous_close {
+            Some(close) => {
+                let high = Price::from((self.high - close).abs());
+                let low = Price::from((self.low - close).abs());
+                [high, low]
+                    .iter()
+                    .fold(range, |max, range| if *range > max { *range } else { max })
+            }
+            None => range,
+        }
+    }
+
+    /// returns the average true range (ATR) of the last `periods` candles
+    ///
+    /// The true range of the first candle is its high minus its low. Returns `None` if there are
+    /// less candles than periods or no periods.
+    /// ```
+    /// # use chrono::Local;
+    /// # use trading_utils::{Candle, Price};
+    /// let candle = |high, low, close| Candle::new(Local::now(), Price::from(close), Price::from(high), Price::from(low), Price::from(close), 0);
+    /// let candles = [candle(11.0, 9.0, 10.0), candle(13.0, 11.0, 12.0), candle(12.0, 11.0, 11.5)];
+    ///
+    /// assert_eq!(Candle::average_true_range(&candles, 2), Some(Price::from(2.0)));
+    /// assert_eq!(Candle::average_true_range(&candles, 4), None);
+    /// ```
+    pub fn average_true_range(candles: &[Candle], periods: usize) -> Option<Price> {
+        if periods == 0 || candles.len() < periods {
+            return None;
+        }
+        let start = candles.len() - periods;
+        let true_ranges: Vec<Price> = candles[start..]
+            .iter()
+            .enumerate()
+            .map(|(index, candle)| {
+                let previous = (start + index).checked_sub(1).map(|previous| candles[previous].close);
+                candle.true_range(previous)
+            })
+            .collect();
+
+        Some(Price::simple_average(&true_ranges))
+    }
+}
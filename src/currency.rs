@@ -1,6 +1,86 @@
-#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+use crate::{Error, ErrorKind, Price};
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Currency {
     EUR,
     USD,
     CHF,
-}
\ No newline at end of file
+}
+
+/// Exchange rates to a base currency
+///
+/// A rate is the price of one unit of a currency in the base currency.
+/// ```
+/// # use trading_utils::{Currency, ExchangeRates, Price};
+/// let rates = ExchangeRates::new(Currency::EUR)
+///     .with_rate(Currency::USD, 0.5)
+///     .with_rate(Currency::CHF, 2.0);
+///
+/// assert_eq!(rates.to_base(Price::from(10.0), Currency::USD).unwrap(), Price::from(5.0));
+/// assert_eq!(rates.convert(Price::from(10.0), Currency::USD, Currency::CHF).unwrap(), Price::from(2.5));
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ExchangeRates {
+    base: Currency,
+    rates: Vec<(Currency, f64)>,
+}
+
+impl ExchangeRates {
+    pub fn new(base: Currency) -> Self {
+        Self {
+            base,
+            rates: Vec::new(),
+        }
+    }
+
+    /// sets the price of one unit of the currency in the base currency
+    pub fn with_rate(mut self, currency: Currency, rate: f64) -> Self {
+        self.set_rate(currency, rate);
+        self
+    }
+
+    pub fn set_rate(&mut self, currency: Currency, rate: f64) {
+        match self.rates.iter_mut().find(|(known, _)| *known == currency) {
+            Some((_, known_rate)) => *known_rate = rate,
+            None => self.rates.push((currency, rate)),
+        }
+    }
+
+    pub fn base(&self) -> Currency { self.base }
+
+    /// returns the price of one unit of the currency in the base currency
+    pub fn rate(&self, currency: Currency) -> Option<f64> {
+        if currency == self.base {
+            return Some(1.0);
+        }
+        self.rates
+            .iter()
+            .find(|(known, _)| *known == currency)
+            .map(|(_, rate)| *rate)
+    }
+
+    /// converts an amount into the base currency
+    pub fn to_base(&self, amount: Price, currency: Currency) -> Result<Price, Error<ErrorKind>> {
+        Ok(amount * self.rate_or_error(currency)?)
+    }
+
+    /// converts an amount between two currencies
+    pub fn convert(&self, amount: Price, from: Currency, to: Currency) -> Result<Price, Error<ErrorKind>> {
+        if from == to {
+            return Ok(amount);
+        }
+        Ok(amount * self.rate_or_error(from)? / self.rate_or_error(to)?)
+    }
+
+    fn rate_or_error(&self, currency: Currency) -> Result<f64, Error<ErrorKind>> {
+        match self.rate(currency) {
+            Some(rate) if rate > 0.0 => Ok(rate),
+            _ => Err(Error::new(
+                format!("There's no exchange rate from {:?} to {:?}", currency, self.base),
+                ErrorKind::InvalidParameter,
+            )),
+        }
+    }
+}
@@ -1,4 +0,0 @@
-#[derive(Clone, Debug)]
-pub struct Derivative {
-    pub symbol: String
-}
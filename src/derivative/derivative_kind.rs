@@ -0,0 +1,83 @@
+use crate::Price;
+
+/// What kind of instrument a `Derivative` is
+///
+/// #### Variants:
+/// * __Stock__: A share of a company (default)
+/// * __Etf__: A share of an exchange traded fund
+/// * __Option__: The right to buy (call) or sell (put) the underlying at the strike until or at
+///   the expiry
+/// * __Future__: The obligation to buy or sell the underlying at the expiry
+/// * __Cfd__: A contract for difference that pays the price change of the underlying
+/// * __Warrant__: An option that is issued by a bank instead of an exchange
+/// * __KnockOut__: A leveraged certificate that expires worthless as soon as the underlying
+///   reaches the barrier
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(tag = "type", content = "value", rename_all = "snake_case"))]
+pub enum DerivativeKind {
+    #[default]
+    Stock,
+    Etf,
+    Option {
+        right: OptionRight,
+        style: ExerciseStyle,
+    },
+    Future,
+    Cfd,
+    Warrant {
+        right: OptionRight,
+        style: ExerciseStyle,
+    },
+    KnockOut {
+        direction: KnockOutDirection,
+        barrier: Price,
+    },
+}
+
+impl DerivativeKind {
+    /// returns the right of options and warrants
+    pub fn option_right(&self) -> Option<OptionRight> {
+        match self {
+            DerivativeKind::Option { right, .. } | DerivativeKind::Warrant { right, .. } => Some(*right),
+            _ => None,
+        }
+    }
+
+    /// returns true if the instrument has a strike, options, warrants and knock-outs
+    pub fn has_strike(&self) -> bool {
+        matches!(self, DerivativeKind::Option { .. } | DerivativeKind::Warrant { .. } | DerivativeKind::KnockOut { .. })
+    }
+
+    /// returns true if the instrument ends at a fixed date, options, warrants and futures
+    pub fn has_expiry(&self) -> bool {
+        matches!(self, DerivativeKind::Option { .. } | DerivativeKind::Warrant { .. } | DerivativeKind::Future)
+    }
+}
+
+/// Whether an option gives the right to buy or to sell
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(rename_all = "snake_case"))]
+pub enum OptionRight {
+    Call,
+    Put,
+}
+
+/// When an option can be exercised
+///
+/// #### Variants:
+/// * __European__: Only at the expiry
+/// * __American__: At any time until the expiry
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(rename_all = "snake_case"))]
+pub enum ExerciseStyle {
+    European,
+    American,
+}
+
+/// Whether a knock-out profits from rising (long) or falling (short) prices of the underlying
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(rename_all = "snake_case"))]
+pub enum KnockOutDirection {
+    Long,
+    Short,
+}
@@ -0,0 +1,153 @@
+use std::convert::TryFrom;
+use std::fmt;
+use std::fmt::Formatter;
+
+use crate::{Error, ErrorKind};
+
+/// An International Securities Identification Number, for example `US0378331005`
+///
+/// An ISIN has two letters for the country, nine alphanumeric characters and a check digit.
+/// The check digit is validated, so an `Isin` is always well formed.
+/// ```
+/// # use trading_utils::Isin;
+/// let isin = Isin::new("us0378331005").unwrap();
+/// assert_eq!(isin.as_str(), "US0378331005");
+/// assert_eq!(isin.country(), "US");
+///
+/// assert!(Isin::new("US0378331006").is_err());
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(try_from = "String", into = "String"))]
+pub struct Isin(String);
+
+impl Isin {
+    pub fn new(isin: &str) -> Result<Self, Error<ErrorKind>> {
+        let isin = isin.trim().to_ascii_uppercase();
+        let bytes = isin.as_bytes();
+
+        let well_formed = bytes.len() == 12
+            && bytes[..2].iter().all(u8::is_ascii_uppercase)
+            && bytes[2..11].iter().all(u8::is_ascii_alphanumeric)
+            && bytes[11].is_ascii_digit();
+        if !well_formed {
+            return Err(invalid(format!("`{}` is not an ISIN, it needs 2 letters, 9 letters or digits and a check digit", isin)));
+        }
+
+        let check_digit = u32::from(bytes[11] - b'0');
+        if check_digit != Self::check_digit(&bytes[..11]) {
+            return Err(invalid(format!("The check digit of the ISIN `{}` is wrong", isin)));
+        }
+
+        Ok(Self(isin))
+    }
+
+    pub fn as_str(&self) -> &str { &self.0 }
+
+    /// the ISO 3166 code of the country that issued the ISIN
+    pub fn country(&self) -> &str { &self.0[..2] }
+
+    /// Luhn algorithm over the digits, letters count as two digits (A = 10, ..., Z = 35)
+    fn check_digit(payload: &[u8]) -> u32 {
+        let digits: Vec<u32> = payload
+            .iter()
+            .flat_map(|byte| {
+                let value = if byte.is_ascii_digit() {
+                    u32::from(byte - b'0')
+                } else {
+                    u32::from(byte - b'A') + 10
+                };
+                if value >= 10 { vec![value / 10, value % 10] } else { vec![value] }
+            })
+            .collect();
+
+        let sum: u32 = digits
+            .iter()
+            .rev()
+            .enumerate()
+            .map(|(i, &digit)| {
+                if i % 2 == 0 {
+                    let doubled = digit * 2;
+                    doubled / 10 + doubled % 10
+                } else {
+                    digit
+                }
+            })
+            .sum();
+
+        (10 - sum % 10) % 10
+    }
+}
+
+impl TryFrom<String> for Isin {
+    type Error = Error<ErrorKind>;
+
+    fn try_from(isin: String) -> Result<Self, Self::Error> {
+        Isin::new(&isin)
+    }
+}
+
+impl From<Isin> for String {
+    fn from(isin: Isin) -> Self {
+        isin.0
+    }
+}
+
+impl fmt::Display for Isin {
+    fn fmt(&self, formatter: &mut Formatter<'_>) -> fmt::Result {
+        write!(formatter, "{}", self.0)
+    }
+}
+
+/// A German securities identification number (Wertpapierkennnummer), for example `865985`
+///
+/// A WKN has six letters or digits, without `I` and `O` since they look like `1` and `0`.
+/// ```
+/// # use trading_utils::Wkn;
+/// assert_eq!(Wkn::new("a0b7x9").unwrap().as_str(), "A0B7X9");
+/// assert!(Wkn::new("86598").is_err());
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(try_from = "String", into = "String"))]
+pub struct Wkn(String);
+
+impl Wkn {
+    pub fn new(wkn: &str) -> Result<Self, Error<ErrorKind>> {
+        let wkn = wkn.trim().to_ascii_uppercase();
+
+        let well_formed = wkn.len() == 6
+            && wkn
+                .bytes()
+                .all(|byte| byte.is_ascii_alphanumeric() && byte != b'I' && byte != b'O');
+        if !well_formed {
+            return Err(invalid(format!("`{}` is not a WKN, it needs 6 letters or digits without I and O", wkn)));
+        }
+
+        Ok(Self(wkn))
+    }
+
+    pub fn as_str(&self) -> &str { &self.0 }
+}
+
+impl TryFrom<String> for Wkn {
+    type Error = Error<ErrorKind>;
+
+    fn try_from(wkn: String) -> Result<Self, Self::Error> {
+        Wkn::new(&wkn)
+    }
+}
+
+impl From<Wkn> for String {
+    fn from(wkn: Wkn) -> Self {
+        wkn.0
+    }
+}
+
+impl fmt::Display for Wkn {
+    fn fmt(&self, formatter: &mut Formatter<'_>) -> fmt::Result {
+        write!(formatter, "{}", self.0)
+    }
+}
+
+fn invalid(msg: String) -> Error<ErrorKind> {
+    Error::new(msg, ErrorKind::InvalidParameter)
+}
@@ -0,0 +1,275 @@
+use chrono::{DateTime, Local};
+
+pub use derivative_kind::*;
+pub use identifier::*;
+
+use crate::{Currency, Error, ErrorKind, MarketValue, OrderData, OrderType, Price, RoundingMode, StockExchange, StopLoss, TakeProfit, TradingErrorKind, TradingHours};
+
+pub mod derivative_kind;
+pub mod identifier;
+
+/// A tradable instrument and its contract specification
+///
+/// #### Fields:
+/// * __symbol__: The ticker symbol, it's used to identify the derivative in price feeds and stores
+/// * __isin__, __wkn__: Optional identifiers
+/// * __kind__: What kind of instrument it is, see `DerivativeKind`
+/// * __underlying__: The instrument an option, future, CFD, warrant or knock-out refers to
+/// * __strike__: The strike of options, warrants and knock-outs
+/// * __expiry__: The end of options, warrants and futures
+/// * __multiplier__: How many units of the underlying one piece represents, 100 for most stock
+///   options (default 1)
+/// * __tick_size__: The smallest price step, if it's `None` the tick size of the exchange is used
+///   for stocks and ETFs
+/// * __lot_size__: Orders need to be a multiple of it (default 1)
+/// * __min_order_value__: The smallest value of an order, `price * multiplier * pieces`
+/// * __currency__: The trading currency
+/// * __exchange__: The stock exchange the derivative is listed on
+/// * __trading_hours__: The hours the derivative can be traded, if it's `None` the trading hours
+///   of the exchange are used
+///
+/// ```
+/// # use chrono::{Local, TimeZone};
+/// # use trading_utils::*;
+/// let apple = Derivative::new("AAPL".to_string(), DerivativeKind::Stock, Currency::USD)
+///     .with_isin(Isin::new("US0378331005").unwrap())
+///     .with_exchange(StockExchange::NASDAQ);
+///
+/// let call = Derivative::new("AAPL 250117C00200000".to_string(), DerivativeKind::Option {
+///     right: OptionRight::Call,
+///     style: ExerciseStyle::American,
+/// }, Currency::USD)
+///     .with_underlying(apple)
+///     .with_strike(Price::from(200.0))
+///     .with_expiry(Local.with_ymd_and_hms(2025, 1, 17, 22, 0, 0).unwrap())
+///     .with_multiplier(100.0)
+///     .with_tick_size(Price::from(0.05));
+///
+/// assert!(call.validate().is_ok());
+/// assert_eq!(call.notional(Price::from(1.5), 2), Price::from(300.0));
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Derivative {
+    pub symbol: String,
+    pub isin: Option<Isin>,
+    pub wkn: Option<Wkn>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub kind: DerivativeKind,
+    pub underlying: Option<Box<Derivative>>,
+    pub strike: Option<Price>,
+    pub expiry: Option<DateTime<Local>>,
+    #[cfg_attr(feature = "serde", serde(default = "default_multiplier"))]
+    pub multiplier: f64,
+    pub tick_size: Option<Price>,
+    #[cfg_attr(feature = "serde", serde(default = "default_lot_size"))]
+    pub lot_size: u64,
+    pub min_order_value: Option<Price>,
+    pub currency: Currency,
+    pub exchange: Option<StockExchange>,
+    pub trading_hours: Option<TradingHours>,
+}
+
+impl Derivative {
+    pub fn new(symbol: String, kind: DerivativeKind, currency: Currency) -> Self {
+        Self {
+            symbol,
+            isin: None,
+            wkn: None,
+            kind,
+            underlying: None,
+            strike: None,
+            expiry: None,
+            multiplier: 1.0,
+            tick_size: None,
+            lot_size: 1,
+            min_order_value: None,
+            currency,
+            exchange: None,
+            trading_hours: None,
+        }
+    }
+
+    pub fn with_isin(mut self, isin: Isin) -> Self {
+        self.isin = Some(isin);
+        self
+    }
+
+    pub fn with_wkn(mut self, wkn: Wkn) -> Self {
+        self.wkn = Some(wkn);
+        self
+    }
+
+    pub fn with_underlying(mut self, underlying: Derivative) -> Self {
+        self.underlying = Some(Box::new(underlying));
+        self
+    }
+
+    pub fn with_strike(mut self, strike: Price) -> Self {
+        self.strike = Some(strike);
+        self
+    }
+
+    pub fn with_expiry(mut self, expiry: DateTime<Local>) -> Self {
+        self.expiry = Some(expiry);
+        self
+    }
+
+    pub fn with_multiplier(mut self, multiplier: f64) -> Self {
+        self.multiplier = multiplier;
+        self
+    }
+
+    pub fn with_tick_size(mut self, tick_size: Price) -> Self {
+        self.tick_size = Some(tick_size);
+        self
+    }
+
+    pub fn with_lot_size(mut self, lot_size: u64) -> Self {
+        self.lot_size = lot_size;
+        self
+    }
+
+    pub fn with_min_order_value(mut self, min_order_value: Price) -> Self {
+        self.min_order_value = Some(min_order_value);
+        self
+    }
+
+    pub fn with_exchange(mut self, exchange: StockExchange) -> Self {
+        self.exchange = Some(exchange);
+        self
+    }
+
+    pub fn with_trading_hours(mut self, trading_hours: TradingHours) -> Self {
+        self.trading_hours = Some(trading_hours);
+        self
+    }
+
+    /// checks that the specification is complete and consistent
+    ///
+    /// Options and warrants need a strike and an expiry, futures an expiry and knock-outs a strike.
+    /// The multiplier and the tick size need to be positive and the lot size at least 1.
+    pub fn validate(&self) -> Result<(), Error<ErrorKind>> {
+        let invalid = |msg: String| Err(Error::new(msg, ErrorKind::InvalidParameter));
+
+        if self.symbol.trim().is_empty() {
+            return invalid("A derivative needs a symbol".to_string());
+        }
+        if self.kind.has_strike() && self.strike.is_none() {
+            return invalid(format!("`{}` is a {:?} and needs a strike", self.symbol, self.kind));
+        }
+        if self.kind.has_expiry() && self.expiry.is_none() {
+            return invalid(format!("`{}` is a {:?} and needs an expiry", self.symbol, self.kind));
+        }
+        if self.multiplier.is_nan() || self.multiplier <= 0.0 {
+            return invalid(format!("The multiplier of `{}` needs to be positive, not {}", self.symbol, self.multiplier));
+        }
+        if self.tick_size.is_some_and(|tick_size| tick_size <= Price::zero()) {
+            return invalid(format!("The tick size of `{}` needs to be positive", self.symbol));
+        }
+        if self.lot_size == 0 {
+            return invalid(format!("The lot size of `{}` needs to be at least 1", self.symbol));
+        }
+        if self.min_order_value.is_some_and(|min_order_value| min_order_value < Price::zero()) {
+            return invalid(format!("The minimal order value of `{}` can't be negative", self.symbol));
+        }
+
+        Ok(())
+    }
+
+    /// returns true if the derivative has an expiry that is not after the given time
+    pub fn is_expired(&self, time: DateTime<Local>) -> bool {
+        self.expiry.is_some_and(|expiry| expiry <= time)
+    }
+
+    /// returns the smallest price step at the given price
+    ///
+    /// That's the tick size of the derivative or, for stocks and ETFs, the tick size of the
+    /// exchange it's listed on or traded at.
+    pub fn tick_size(&self, price: Price, exchange: StockExchange) -> Option<Price> {
+        match (self.tick_size, self.kind) {
+            (Some(tick_size), _) => Some(tick_size),
+            (None, DerivativeKind::Stock) | (None, DerivativeKind::Etf) =>
+                Some(self.exchange.unwrap_or(exchange).tick_size(price)),
+            (None, _) => None,
+        }
+    }
+
+    /// rounds the price to a valid price of the derivative, see `tick_size`
+    pub fn round_to_tick(&self, price: Price, exchange: StockExchange, mode: RoundingMode) -> Price {
+        match self.tick_size(price, exchange) {
+            Some(tick_size) => price.round_to_tick(tick_size, mode),
+            None => price,
+        }
+    }
+
+    /// returns the trading hours of the derivative or of the exchange it's listed on or traded at
+    pub fn trading_hours(&self, exchange: StockExchange) -> TradingHours {
+        self.trading_hours
+            .clone()
+            .unwrap_or_else(|| self.exchange.unwrap_or(exchange).trading_hours())
+    }
+
+    /// returns the value of the given pieces at the given price, `price * multiplier * pieces`
+    pub fn notional(&self, price: Price, pieces: u64) -> Price {
+        price * self.multiplier * pieces as f64
+    }
+
+    /// checks an order against the specification
+    ///
+    /// The pieces need to be a multiple of the lot size and the absolute prices of the order
+    /// need to be on the tick size. Limit and stop orders need to reach the minimal order value.
+    pub fn check_order(&self, order: &OrderData) -> Result<(), Error<TradingErrorKind>> {
+        let invalid = |msg: String| Err(Error::new(msg, TradingErrorKind::InvalidOrder));
+
+        if order.pieces() == 0 || !order.pieces().is_multiple_of(self.lot_size.max(1)) {
+            return invalid(format!(
+                "{} pieces of `{}` can't be traded, the lot size is {}",
+                order.pieces(), self.symbol, self.lot_size
+            ));
+        }
+
+        if let (Some(min_order_value), OrderType::LimitOrder(price) | OrderType::StopOrder(price)) = (self.min_order_value, order.order_type()) {
+            let value = self.notional(*price, order.pieces());
+            if value < min_order_value {
+                return invalid(format!(
+                    "The order value {} of `{}` is below the minimal order value {}",
+                    value.as_f64(), self.symbol, min_order_value.as_f64()
+                ));
+            }
+        }
+
+        let prices = [
+            match order.order_type() {
+                OrderType::LimitOrder(price) | OrderType::StopOrder(price) => Some(("order", *price)),
+                OrderType::MarketOrder => None,
+            },
+            match order.take_profit() {
+                TakeProfit::Absolute(price) => Some(("take profit", *price)),
+                _ => None,
+            },
+            match order.stop_loss() {
+                StopLoss::Absolute(price) => Some(("stop loss", *price)),
+                _ => None,
+            },
+        ];
+        for (name, price) in prices.iter().flatten() {
+            let rounded = self.round_to_tick(*price, order.stock_exchange(), RoundingMode::HalfUp);
+            if rounded != *price {
+                return invalid(format!(
+                    "The {} price {} of `{}` is not a multiple of the tick size {}",
+                    name, price.as_f64(), self.symbol,
+                    self.tick_size(*price, order.stock_exchange()).unwrap_or_else(Price::zero).as_f64()
+                ));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(feature = "serde")]
+fn default_multiplier() -> f64 { 1.0 }
+
+#[cfg(feature = "serde")]
+fn default_lot_size() -> u64 { 1 }
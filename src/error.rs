@@ -1,5 +1,7 @@
 use std::fmt::{Debug, Formatter};
 
+use crate::BrokerErrorKind;
+
 /// GeneralError marker Trait
 pub trait GeneralError: std::error::Error {}
 
@@ -11,6 +13,7 @@ pub trait GeneralErrorKind: Copy + Debug {}
 /// This error struct provides all the functionality needed when an error occurs while trading
 /// Usually the individual ErrorKinds provide more, domain specific, information and documentation.
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Error<K: GeneralErrorKind> {
     msg: String,
     kind: K,
@@ -80,6 +83,7 @@ impl From<std::io::Error> for Error<ErrorKind> {
 /// It should be used if there's no more detailed ErrorKind available or if the detailed
 /// ErrorKind information is unimportant or undesired.
 #[derive(Debug, Clone, Copy, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(rename_all = "snake_case"))]
 pub enum ErrorKind {
     Broker,
     Bank,
@@ -87,6 +91,8 @@ pub enum ErrorKind {
     LibLoading,
     IO,
     MisMatchedVersion,
+    InvalidParameter,
+    InvalidData,
     Other,
 }
 
@@ -97,7 +103,41 @@ impl GeneralErrorKind for ErrorKind {}
 /// The TradingErrorKind has the purpose to go into greater detail about common trading errors.
 ///
 /// It should be used for trading algorithms.
+///
+/// #### Variants:
+/// * __UnsupportedInstruction__: The broker does not support an instruction
+/// * __BuyingInShutdown__: An algorithm tried to open an order during `shutdown`
+/// * __NoSuchOrder__: An instruction refers to an order that does not exist
+/// * __NoSuchPosition__: An instruction refers to a position that does not exist
+/// * __Broker__: The broker returned an error
+/// * __FollowUpLimit__: The callbacks of an algorithm returned too many nested follow up instructions
+/// * __StepTimeout__: An algorithm took longer than its time step
+/// * __PriceFeed__: The price feed of an algorithm returned an error
+/// * __InvalidOrder__: An order doesn't match the specification of its derivative, for example
+///   the lot size or the tick size
+/// * __RiskLimit__: A `RiskManager` rejected an order, since it would violate a risk limit
 #[derive(Debug, Clone, Copy, Eq, PartialEq)]
-pub enum TradingErrorKind {}
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(rename_all = "snake_case"))]
+pub enum TradingErrorKind {
+    UnsupportedInstruction,
+    BuyingInShutdown,
+    NoSuchOrder,
+    NoSuchPosition,
+    Broker,
+    FollowUpLimit,
+    StepTimeout,
+    PriceFeed,
+    InvalidOrder,
+    RiskLimit,
+}
 
 impl GeneralErrorKind for TradingErrorKind {}
+
+impl From<Error<BrokerErrorKind>> for Error<TradingErrorKind> {
+    fn from(error: Error<BrokerErrorKind>) -> Self {
+        Self {
+            msg: format!("{} ({:?})", error.msg, error.kind),
+            kind: TradingErrorKind::Broker,
+        }
+    }
+}
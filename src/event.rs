@@ -0,0 +1,148 @@
+use std::fmt;
+use std::sync::{Arc, Mutex, RwLock};
+
+/// The severity of an event
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub enum EventLevel {
+    Trace,
+    Debug,
+    Info,
+    Warn,
+    Error,
+}
+
+/// Event marker Trait
+///
+/// Events are structured notifications about what is going on inside of trading-utils.
+/// They are emitted through an `EventBus` and can be received by an `EventListener`.
+pub trait Event: fmt::Display + fmt::Debug {
+    fn level(&self) -> EventLevel;
+}
+
+/// A receiver of events
+///
+/// Listeners are shared between threads, so they only get a shared reference to themselves.
+/// Every `Fn(&E)` closure is a listener.
+pub trait EventListener<E>: Send + Sync {
+    fn on_event(&self, event: &E);
+}
+
+impl<E, F: Fn(&E) + Send + Sync> EventListener<E> for F {
+    fn on_event(&self, event: &E) {
+        self(event)
+    }
+}
+
+/// Distributes events to all subscribed listeners
+///
+/// An EventBus is a handle, so all clones of a bus share the same listeners.
+/// If the `log` feature is enabled every emitted event is also written to the `log` crate
+/// with the target `trading_utils`.
+pub struct EventBus<E> {
+    listeners: Arc<RwLock<Vec<Arc<dyn EventListener<E>>>>>,
+}
+
+impl<E: Event> EventBus<E> {
+    /// creates a bus without any listeners
+    pub fn new() -> Self {
+        Self {
+            listeners: Arc::new(RwLock::new(Vec::new()))
+        }
+    }
+
+    pub fn subscribe(&self, listener: Arc<dyn EventListener<E>>) {
+        self.listeners
+            .write()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .push(listener);
+    }
+
+    pub fn emit(&self, event: E) {
+        #[cfg(feature = "log")]
+        log::log!(target: "trading_utils", log_level(event.level()), "{}", event);
+
+        // the listeners are called without holding the lock, so they can subscribe new ones
+        let listeners = self.listeners
+                            .read()
+                            .unwrap_or_else(|poisoned| poisoned.into_inner())
+                            .clone();
+        for listener in listeners.iter() {
+            listener.on_event(&event);
+        }
+    }
+}
+
+impl<E> Clone for EventBus<E> {
+    fn clone(&self) -> Self {
+        Self {
+            listeners: Arc::clone(&self.listeners)
+        }
+    }
+}
+
+impl<E: Event> Default for EventBus<E> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<E> fmt::Debug for EventBus<E> {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let listeners = self.listeners
+                            .read()
+                            .map_or(0, |listeners| listeners.len());
+        write!(formatter, "EventBus {{ listeners: {} }}", listeners)
+    }
+}
+
+/// A listener that keeps all received events in memory
+///
+/// This is useful for reports and tests.
+#[derive(Debug, Default)]
+pub struct EventLog<E> {
+    events: Mutex<Vec<E>>,
+}
+
+impl<E: Clone> EventLog<E> {
+    pub fn new() -> Self {
+        Self {
+            events: Mutex::new(Vec::new())
+        }
+    }
+
+    /// returns a copy of all events received so far
+    pub fn events(&self) -> Vec<E> {
+        self.events
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .clone()
+    }
+
+    pub fn clear(&self) {
+        self.events
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .clear();
+    }
+}
+
+impl<E: Clone + Send> EventListener<E> for EventLog<E> {
+    fn on_event(&self, event: &E) {
+        self.events
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .push(event.clone());
+    }
+}
+
+#[cfg(feature = "log")]
+fn log_level(level: EventLevel) -> log::Level {
+    use EventLevel::*;
+    match level {
+        Trace => log::Level::Trace,
+        Debug => log::Level::Debug,
+        Info => log::Level::Info,
+        Warn => log::Level::Warn,
+        Error => log::Level::Error,
+    }
+}
@@ -0,0 +1,868 @@
+use std::collections::HashMap;
+
+use chrono::{DateTime, Local};
+
+use crate::{AlgorithmInterface, Bracket, BrokerCapability, BrokerErrorKind, BrokerInterface, Candle, Deposit, Derivative, Direction, Error, EventBus, Instruction, MarketContext, MarketValue, MatchingEngine, Order, OrderChanges, OrderData, OrderEvent, OrderRequest, OrderType, Position, PositionType, Price, RiskManager, StockExchange, StopLoss, TakeProfit, TradingErrorKind};
+
+/// The default amount of nested follow up instructions that will be executed
+pub const DEFAULT_MAX_FOLLOW_UPS: usize = 8;
+
+/// The phase of an algorithm in which instructions are executed
+///
+/// While an algorithm is shut down it's not allowed to open new orders.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum ExecutionPhase {
+    Trading,
+    Shutdown,
+}
+
+/// An instruction that was validated and translated by an `Executor`
+///
+/// Unlike an `Instruction` an action doesn't borrow from the algorithm, so the algorithm can
+/// be called again while the actions are executed.
+#[derive(Clone, Debug, PartialEq)]
+#[allow(clippy::large_enum_variant)]
+pub enum PlannedAction {
+    /// submits a new order
+    Submit(Order),
+    /// closes a position, or only some pieces of it
+    Close { position: u64, pieces: Option<u64> },
+    /// cancels an open order
+    Cancel { order_id: u64 },
+    /// changes an open order
+    Modify { order_id: u64, changes: OrderChanges },
+}
+
+/// The validated and translated instructions of a single step
+#[derive(Clone, Debug, Default)]
+pub struct ExecutionPlan {
+    actions: Vec<PlannedAction>,
+    rejected: Vec<Error<TradingErrorKind>>,
+}
+
+impl ExecutionPlan {
+    /// creates a plan of actions that don't come from instructions, for example the exits of a
+    /// `TrailingStopEngine`
+    pub fn new(actions: Vec<PlannedAction>) -> Self {
+        Self {
+            actions,
+            rejected: Vec::new(),
+        }
+    }
+
+    pub fn actions(&self) -> &[PlannedAction] { &self.actions }
+    /// the instructions that did not pass the validation
+    pub fn rejected(&self) -> &[Error<TradingErrorKind>] { &self.rejected }
+    pub fn is_empty(&self) -> bool { self.actions.is_empty() && self.rejected.is_empty() }
+}
+
+/// What happened while a plan was executed
+///
+/// #### Fields:
+/// * __submitted__: The orders that were accepted by the broker
+/// * __closed__: The positions that were closed or reduced
+/// * __cancelled__: The orders that were cancelled
+/// * __modified__: The ids of the orders that were changed
+/// * __events__: All order events that were dispatched to the algorithm
+/// * __errors__: The instructions that were rejected and the callbacks that failed
+#[derive(Clone, Debug, Default)]
+pub struct ExecutionReport {
+    pub submitted: Vec<Order>,
+    pub closed: Vec<Position>,
+    pub cancelled: Vec<Order>,
+    pub modified: Vec<u64>,
+    pub events: Vec<OrderEvent>,
+    pub errors: Vec<Error<TradingErrorKind>>,
+}
+
+impl ExecutionReport {
+    /// returns true if no instruction was rejected and no callback failed
+    pub fn is_success(&self) -> bool {
+        self.errors.is_empty()
+    }
+
+    /// appends the results of another report
+    pub fn merge(&mut self, other: ExecutionReport) {
+        self.submitted.extend(other.submitted);
+        self.closed.extend(other.closed);
+        self.cancelled.extend(other.cancelled);
+        self.modified.extend(other.modified);
+        self.events.extend(other.events);
+        self.errors.extend(other.errors);
+    }
+}
+
+/// Executes the instructions of an algorithm with a broker
+///
+/// For every step the executor:
+/// * validates the instructions against the `CAPABILITIES` and `STOCK_EXCHANGES` of the broker,
+///   long orders need the `Buy*` and short orders the `Sell*` capabilities. Take profits, stop
+///   losses and closed positions trade in the opposite direction of their position
+/// * rejects instructions that open orders while the algorithm is shut down
+/// * vets new and changed orders with its `RiskManager`, if it has one
+/// * builds `Order`s with generated raw ids, records them in the `Deposit` and submits them.
+///   Submitted active `Bracket`s only place their exits for an open position, see
+///   `BrokerInterface::place_exits`
+/// * cancels, changes and sells existing orders and positions
+/// * reconciles the orders and positions of the deposit, if the broker supports it. Positions of
+///   active brackets that the broker closed are reported as `StopLossTriggered` or
+///   `TakeProfitTriggered`
+/// * dispatches the resulting `OrderEvent`s to the algorithm and executes its follow up
+///   instructions, events from elsewhere can be dispatched with `Executor::dispatch`
+///
+/// Invalid instructions don't stop the execution of the remaining ones, they are collected in
+/// the `ExecutionReport`.
+///
+/// With `Executor::with_matching_engine` the executor simulates the broker. The capabilities of
+/// the broker are still checked, but its orders and positions are only changed in the deposit:
+/// new orders wait for `Executor::match_orders`, positions are closed at the last close of
+/// their symbol, see `Executor::update_candle`. The `Runner` matches the orders with every
+/// new candle before the algorithm trades.
+pub struct Executor {
+    stock_exchange: StockExchange,
+    raw_id_prefix: String,
+    next_raw_id: u64,
+    max_follow_ups: usize,
+    events: EventBus<OrderEvent>,
+    risk_manager: Option<RiskManager>,
+    /// the last candle of every symbol
+    candles: HashMap<String, Candle>,
+    matching_engine: Option<MatchingEngine>,
+}
+
+impl Executor {
+    /// creates an executor that places orders on the given stock exchange
+    ///
+    /// The raw ids of the orders are prefixed by the creation time of the executor, so
+    /// multiple runs don't create the same ids.
+    pub fn new(stock_exchange: StockExchange) -> Self {
+        Self {
+            stock_exchange,
+            raw_id_prefix: format!("trading-utils-{}", Local::now().timestamp_millis()),
+            next_raw_id: 0,
+            max_follow_ups: DEFAULT_MAX_FOLLOW_UPS,
+            events: EventBus::new(),
+            risk_manager: None,
+            candles: HashMap::new(),
+            matching_engine: None,
+        }
+    }
+
+    pub fn with_raw_id_prefix(mut self, prefix: &str) -> Self {
+        self.raw_id_prefix = prefix.to_string();
+        self
+    }
+
+    pub fn with_max_follow_ups(mut self, max_follow_ups: usize) -> Self {
+        self.max_follow_ups = max_follow_ups;
+        self
+    }
+
+    /// uses an existing bus for the order events
+    pub fn with_events(mut self, events: &EventBus<OrderEvent>) -> Self {
+        self.events = events.clone();
+        self
+    }
+
+    /// vets every order with the risk manager before it's submitted or changed
+    pub fn with_risk_manager(mut self, risk_manager: RiskManager) -> Self {
+        self.risk_manager = Some(risk_manager);
+        self
+    }
+
+    /// simulates the broker, the engine fills the orders against the candles
+    pub fn with_matching_engine(mut self, matching_engine: MatchingEngine) -> Self {
+        self.matching_engine = Some(matching_engine);
+        self
+    }
+
+    pub fn stock_exchange(&self) -> StockExchange { self.stock_exchange }
+    pub fn max_follow_ups(&self) -> usize { self.max_follow_ups }
+    pub fn events(&self) -> &EventBus<OrderEvent> { &self.events }
+    pub fn risk_manager(&self) -> Option<&RiskManager> { self.risk_manager.as_ref() }
+    pub fn risk_manager_mut(&mut self) -> Option<&mut RiskManager> { self.risk_manager.as_mut() }
+    pub fn matching_engine(&self) -> Option<&MatchingEngine> { self.matching_engine.as_ref() }
+    /// the last candle of a symbol that was passed to `update_candle`
+    pub fn last_candle(&self, symbol: &str) -> Option<&Candle> { self.candles.get(symbol) }
+
+    /// passes a new candle of a symbol to the risk manager, it values the positions and the
+    /// market orders of the symbol at the close
+    pub fn update_candle(&mut self, symbol: &str, candle: &Candle) {
+        if let Some(risk_manager) = &mut self.risk_manager {
+            risk_manager.update_price(symbol, candle.close);
+        }
+        self.candles.insert(symbol.to_string(), *candle);
+    }
+
+    /// starts a step at the time, the risk manager starts a new day and checks the daily loss
+    ///
+    /// `trade` calls it with every step, callers that plan and execute the instructions
+    /// themselves, like the `Runner`, need to call it before.
+    pub fn update(&mut self, time: DateTime<Local>, deposit: &Deposit) {
+        if let Some(risk_manager) = &mut self.risk_manager {
+            risk_manager.update(time, deposit);
+        }
+    }
+
+    /// calls `AlgorithmInterface::trade` and executes the returned instructions
+    ///
+    /// An error is only returned if the algorithm itself failed.
+    #[allow(clippy::too_many_arguments)]
+    pub fn trade<B: BrokerInterface, A: AlgorithmInterface + ?Sized>(
+        &mut self,
+        broker: &B,
+        deposit: &mut Deposit,
+        algorithm: &mut A,
+        time: DateTime<Local>,
+        derivative: &Derivative,
+        candles: &[Candle],
+    ) -> Result<ExecutionReport, Error<TradingErrorKind>> {
+        if let Some(candle) = candles.last() {
+            self.update_candle(&derivative.symbol, candle);
+        }
+        self.update(time, deposit);
+
+        let plan = {
+            let context = MarketContext::new(time, derivative, candles)
+                .with_deposit(deposit)
+                .with_stock_exchange(self.stock_exchange);
+            let instructions = algorithm.trade(&context)?;
+            self.plan::<B>(instructions, derivative, ExecutionPhase::Trading)
+        };
+
+        Ok(self.execute(broker, deposit, algorithm, derivative, plan, ExecutionPhase::Trading))
+    }
+
+    /// calls `AlgorithmInterface::shutdown` and executes the returned instructions
+    ///
+    /// Instructions that open orders are rejected, also the follow up instructions.
+    /// An error is only returned if the algorithm itself failed.
+    pub fn shutdown<B: BrokerInterface, A: AlgorithmInterface + ?Sized>(
+        &mut self,
+        broker: &B,
+        deposit: &mut Deposit,
+        algorithm: &mut A,
+        derivative: &Derivative,
+        prices: &[Price],
+    ) -> Result<ExecutionReport, Error<TradingErrorKind>> {
+        let plan = {
+            let instructions = algorithm.shutdown(deposit.positions(), prices)?;
+            self.plan::<B>(instructions, derivative, ExecutionPhase::Shutdown)
+        };
+
+        Ok(self.execute(broker, deposit, algorithm, derivative, plan, ExecutionPhase::Shutdown))
+    }
+
+    /// validates the instructions and translates them into actions
+    ///
+    /// Requests without a derivative are planned for the `derivative`.
+    /// Orders without any leg, like an empty `OneCancelsTheOther`, are rejected.
+    /// New orders are checked against the specification of their derivative, see
+    /// `Derivative::check_order`.
+    /// The returned plan doesn't borrow from the instructions anymore.
+    pub fn plan<B: BrokerInterface>(&mut self, instructions: &[Instruction<'_>], derivative: &Derivative, phase: ExecutionPhase) -> ExecutionPlan {
+        let mut plan = ExecutionPlan::default();
+
+        for instruction in instructions {
+            match self.plan_instruction::<B>(instruction, derivative, phase) {
+                Ok(Some(action)) => plan.actions.push(action),
+                Ok(None) => {}
+                Err(error) => plan.rejected.push(error),
+            }
+        }
+
+        plan
+    }
+
+    fn plan_instruction<B: BrokerInterface>(&mut self, instruction: &Instruction<'_>, derivative: &Derivative, phase: ExecutionPhase) -> Result<Option<PlannedAction>, Error<TradingErrorKind>> {
+        if instruction.opens_orders() && phase == ExecutionPhase::Shutdown {
+            return Err(Error::new(
+                format!("It's not allowed to open orders during shutdown: {:?}", instruction),
+                TradingErrorKind::BuyingInShutdown,
+            ));
+        }
+        check_capabilities::<B>(instruction, self.stock_exchange)?;
+
+        let action = match instruction {
+            Instruction::Sell { position } => Some(PlannedAction::Close {
+                position: position.hashed_id(),
+                pieces: None,
+            }),
+            Instruction::SellPartially { position, pieces } => Some(PlannedAction::Close {
+                position: position.hashed_id(),
+                pieces: Some(*pieces),
+            }),
+            Instruction::CancelOrder { order_id } => Some(PlannedAction::Cancel { order_id: *order_id }),
+            Instruction::ModifyOrder { changes, .. } if changes.is_empty() => None,
+            Instruction::ModifyOrder { order_id, changes } => Some(PlannedAction::Modify {
+                order_id: *order_id,
+                changes: changes.clone(),
+            }),
+            Instruction::None => None,
+            _ => {
+                let (prefix, next_raw_id) = (&self.raw_id_prefix, &mut self.next_raw_id);
+                let order = instruction.to_order(derivative, self.stock_exchange, || {
+                    *next_raw_id += 1;
+                    format!("{}-{}", prefix, next_raw_id)
+                });
+                if let Some(order) = &order {
+                    if order.data().is_empty() {
+                        return Err(Error::new(
+                            format!("An order needs at least one leg: {:?}", instruction),
+                            TradingErrorKind::InvalidOrder,
+                        ));
+                    }
+                    order
+                        .data()
+                        .iter()
+                        .try_for_each(|data| data.derivative().check_order(data))?;
+                }
+                order.map(PlannedAction::Submit)
+            }
+        };
+
+        Ok(action)
+    }
+
+    /// executes a plan
+    ///
+    /// The resulting order events are dispatched to the algorithm. Its follow up instructions
+    /// are planned in the same phase and executed right away, up to `max_follow_ups` levels deep.
+    pub fn execute<B: BrokerInterface, A: AlgorithmInterface + ?Sized>(
+        &mut self,
+        broker: &B,
+        deposit: &mut Deposit,
+        algorithm: &mut A,
+        derivative: &Derivative,
+        plan: ExecutionPlan,
+        phase: ExecutionPhase,
+    ) -> ExecutionReport {
+        let mut report = ExecutionReport::default();
+        self.execute_plan(broker, deposit, algorithm, derivative, plan, phase, 0, &mut report);
+        report
+    }
+
+    /// fills the orders of the symbol that the candle reached with the matching engine
+    ///
+    /// The resulting events are dispatched like the ones of `execute`. Without a matching engine
+    /// the broker fills the orders and nothing happens.
+    #[allow(clippy::too_many_arguments)]
+    pub fn match_orders<B: BrokerInterface, A: AlgorithmInterface + ?Sized>(
+        &mut self,
+        broker: &B,
+        deposit: &mut Deposit,
+        algorithm: &mut A,
+        derivative: &Derivative,
+        symbol: &str,
+        candle: &Candle,
+    ) -> ExecutionReport {
+        let events = match &mut self.matching_engine {
+            Some(matching_engine) => matching_engine.process(deposit, symbol, candle),
+            None => return ExecutionReport::default(),
+        };
+        self.dispatch(broker, deposit, algorithm, derivative, events, ExecutionPhase::Trading)
+    }
+
+    /// dispatches events that happened outside of a plan to the algorithm, for example the fills
+    /// of a `MatchingEngine`
+    ///
+    /// The follow up instructions are executed like the ones of `execute`.
+    pub fn dispatch<B: BrokerInterface, A: AlgorithmInterface + ?Sized>(
+        &mut self,
+        broker: &B,
+        deposit: &mut Deposit,
+        algorithm: &mut A,
+        derivative: &Derivative,
+        events: Vec<OrderEvent>,
+        phase: ExecutionPhase,
+    ) -> ExecutionReport {
+        let mut report = ExecutionReport::default();
+        self.dispatch_events(broker, deposit, algorithm, derivative, events, phase, 0, &mut report);
+        report
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn execute_plan<B: BrokerInterface, A: AlgorithmInterface + ?Sized>(
+        &mut self,
+        broker: &B,
+        deposit: &mut Deposit,
+        algorithm: &mut A,
+        derivative: &Derivative,
+        plan: ExecutionPlan,
+        phase: ExecutionPhase,
+        depth: usize,
+        report: &mut ExecutionReport,
+    ) {
+        report.errors.extend(plan.rejected);
+
+        let mut events = Vec::new();
+        for action in plan.actions {
+            if let Some(risk_manager) = &mut self.risk_manager {
+                if let Err(error) = risk_manager.check(&action, deposit) {
+                    report.errors.push(error);
+                    continue;
+                }
+            }
+            let simulation = self.matching_engine
+                .as_ref()
+                .map(|matching_engine| (matching_engine, &self.candles));
+            if let Err(error) = execute_action(broker, simulation, deposit, action, report, &mut events) {
+                report.errors.push(error);
+            }
+        }
+        self.reconcile(broker, deposit, &mut events);
+        self.dispatch_events(broker, deposit, algorithm, derivative, events, phase, depth, report);
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn dispatch_events<B: BrokerInterface, A: AlgorithmInterface + ?Sized>(
+        &mut self,
+        broker: &B,
+        deposit: &mut Deposit,
+        algorithm: &mut A,
+        derivative: &Derivative,
+        events: Vec<OrderEvent>,
+        phase: ExecutionPhase,
+        depth: usize,
+        report: &mut ExecutionReport,
+    ) {
+        for event in events {
+            self.events.emit(event.clone());
+
+            let follow_ups = match event.dispatch(algorithm) {
+                Ok([]) => None,
+                Ok(_) if depth >= self.max_follow_ups => {
+                    report.errors.push(Error::new(
+                        format!(
+                            "The follow up instructions of `{}` were not executed, since more than {} nested follow ups were returned",
+                            event, self.max_follow_ups
+                        ),
+                        TradingErrorKind::FollowUpLimit,
+                    ));
+                    None
+                }
+                Ok(instructions) => Some(self.plan::<B>(instructions, derivative, phase)),
+                Err(error) => {
+                    report.errors.push(error);
+                    None
+                }
+            };
+            report.events.push(event);
+
+            if let Some(plan) = follow_ups {
+                self.execute_plan(broker, deposit, algorithm, derivative, plan, phase, depth + 1, report);
+            }
+        }
+    }
+
+    /// updates the orders and positions of the deposit, if the broker supports it and isn't
+    /// simulated
+    ///
+    /// The exits of active brackets are filled by the broker, so a position of a bracket that
+    /// is gone afterwards was closed by one of them.
+    fn reconcile<B: BrokerInterface>(&self, broker: &B, deposit: &mut Deposit, events: &mut Vec<OrderEvent>) {
+        if self.matching_engine.is_some() {
+            return;
+        }
+        let protected: Vec<(Bracket, Position)> = deposit
+            .orders()
+            .iter()
+            .filter_map(|order| match order {
+                Order::Bracket(bracket) => deposit
+                    .positions()
+                    .iter()
+                    .find(|position| Some(&position.id) == bracket.position() && position.is_open())
+                    .map(|position| (bracket.clone(), position.clone())),
+                _ => None,
+            })
+            .collect();
+
+        if B::CAPABILITIES.contains(&BrokerCapability::OrderOverview) {
+            if let Err(error) = broker.update_deposit_orders(deposit) {
+                events.push(OrderEvent::BrokerError { error });
+            }
+        }
+        if B::CAPABILITIES.contains(&BrokerCapability::PositionOverview) {
+            match broker.update_deposit_positions(deposit) {
+                Ok(()) => {
+                    for (bracket, position) in protected {
+                        self.close_bracket(deposit, bracket, position, events);
+                    }
+                }
+                Err(error) => events.push(OrderEvent::BrokerError { error }),
+            }
+        }
+    }
+
+    /// reports the exit that closed the position of a bracket, if the position is gone
+    ///
+    /// An exit the broker still lists wasn't filled. Otherwise it's the exit whose price is
+    /// closest to the last close, or the stop loss without a price. The other exits are
+    /// cancelled.
+    fn close_bracket(&self, deposit: &mut Deposit, bracket: Bracket, position: Position, events: &mut Vec<OrderEvent>) {
+        let closed = deposit
+            .positions()
+            .iter()
+            .find(|open| open.id == position.id)
+            .cloned();
+        if closed.as_ref().is_some_and(Position::is_open) {
+            return;
+        }
+
+        let candle = self.last_candle(&position.derivative().symbol);
+        let listed = |exit: &&OrderData| deposit.orders().iter().any(|order| order.has_id(exit.id()));
+        let mut exits: Vec<&OrderData> = bracket
+            .children()
+            .iter()
+            .filter(|exit| !listed(exit))
+            .collect();
+        if exits.is_empty() {
+            exits = bracket.children().iter().collect();
+        }
+        let distance = |exit: &OrderData| match (exit.order_type(), candle) {
+            (OrderType::LimitOrder(level) | OrderType::StopOrder(level), Some(candle)) => (level.as_f64() - candle.close.as_f64()).abs(),
+            _ => f64::MAX,
+        };
+        let filled = exits
+            .iter()
+            .copied()
+            .min_by(|a, b| distance(a).total_cmp(&distance(b)).then_with(|| is_take_profit(a).cmp(&is_take_profit(b))));
+        let filled = match filled {
+            Some(filled) => filled,
+            None => return,
+        };
+
+        // the broker dropped the position, so it's closed at the price of the exit
+        let position = closed.unwrap_or_else(|| {
+            let mut position = position;
+            let price = match filled.order_type() {
+                OrderType::LimitOrder(level) | OrderType::StopOrder(level) => *level,
+                OrderType::MarketOrder => candle.map_or(position.entry_price(), |candle| candle.close),
+            };
+            let time = candle.map_or_else(Local::now, |candle| candle.time);
+            let _ = position.close(time, price, Price::zero());
+            position
+        });
+
+        if let Some(order_id) = bracket.data().first().map(OrderData::id) {
+            deposit.remove_order(order_id);
+        }
+        events.push(if is_take_profit(filled) {
+            OrderEvent::TakeProfitTriggered { position }
+        } else {
+            OrderEvent::StopLossTriggered { position }
+        });
+        for exit in bracket.children().iter().filter(|exit| exit.id() != filled.id()) {
+            events.push(OrderEvent::Cancelled { order: exit.clone() });
+        }
+    }
+}
+
+/// executes an action with the broker, or with the matching engine of a simulation
+fn execute_action<B: BrokerInterface>(
+    broker: &B,
+    simulation: Option<(&MatchingEngine, &HashMap<String, Candle>)>,
+    deposit: &mut Deposit,
+    action: PlannedAction,
+    report: &mut ExecutionReport,
+    events: &mut Vec<OrderEvent>,
+) -> Result<(), Error<TradingErrorKind>> {
+    match action {
+        // the matching engine fills the order with the next candle
+        PlannedAction::Submit(order) if simulation.is_some() => {
+            if let Order::Bracket(bracket) = &order {
+                check_exits::<B>(bracket, deposit)?;
+            }
+            deposit.add_order(order.clone());
+            report.submitted.push(order);
+        }
+        PlannedAction::Submit(Order::Bracket(bracket)) if bracket.is_active() => {
+            check_exits::<B>(&bracket, deposit)?;
+            let order = Order::Bracket(bracket);
+            let order_id = order.data()[0].id();
+            deposit.add_order(order.clone());
+
+            match broker.place_exits(deposit, order_id) {
+                Ok(()) => report.submitted.push(order),
+                Err(error) => {
+                    deposit.remove_order(order_id);
+                    for order_data in order.data() {
+                        events.push(OrderEvent::Rejected {
+                            order: order_data.clone(),
+                            error: error.clone(),
+                        });
+                    }
+                }
+            }
+        }
+        PlannedAction::Submit(order) => {
+            let order_id = order.data()[0].id();
+            deposit.add_order(order.clone());
+
+            match broker.buy(deposit, order_id) {
+                Ok(position) => {
+                    // the broker may fill any leg of a multi leg order, the position tells which one
+                    let filled = order
+                        .data()
+                        .iter()
+                        .find(|order_data| *order_data.raw_id() == position.id)
+                        .unwrap_or(&order.data()[0]);
+                    events.push(OrderEvent::Filled {
+                        order: filled.clone(),
+                        position: position.clone(),
+                    });
+                    report.submitted.push(order);
+                }
+                Err(error) => {
+                    deposit.remove_order(order_id);
+                    for order_data in order.data() {
+                        events.push(OrderEvent::Rejected {
+                            order: order_data.clone(),
+                            error: error.clone(),
+                        });
+                    }
+                }
+            }
+        }
+        PlannedAction::Close { position, pieces } => {
+            let symbol = match deposit.positions().iter().find(|open| open.hashed_id() == position) {
+                Some(open) => open.derivative().symbol.clone(),
+                None => return Err(Error::new(
+                    format!("The position {} does not exist", position),
+                    TradingErrorKind::NoSuchPosition,
+                )),
+            };
+
+            let result = match (simulation, pieces) {
+                (Some((engine, candles)), pieces) => match candles.get(&symbol) {
+                    Some(candle) => engine.close(deposit, position, pieces, candle),
+                    None => Err(Error::new(
+                        format!("There is no price of `{}` to close the position {} at", symbol, position),
+                        BrokerErrorKind::Other,
+                    )),
+                },
+                (None, Some(pieces)) => broker.sell_partially(deposit, position, pieces),
+                (None, None) => broker.sell(deposit, position),
+            };
+            match result {
+                Ok(position) => report.closed.push(position),
+                Err(error) => events.push(OrderEvent::BrokerError { error }),
+            }
+        }
+        PlannedAction::Cancel { order_id } => {
+            let order = deposit
+                .orders()
+                .iter()
+                .find(|order| order.has_id(order_id))
+                .cloned()
+                .ok_or_else(|| no_such_order(order_id))?;
+
+            let result = match simulation {
+                Some(_) => Ok(order),
+                None => broker.delete_order(deposit, order_id),
+            };
+            match result {
+                Ok(order) => {
+                    deposit.remove_order(order_id);
+                    for order_data in order.data() {
+                        events.push(OrderEvent::Cancelled { order: order_data.clone() });
+                    }
+                    report.cancelled.push(order);
+                }
+                Err(error) => events.push(OrderEvent::BrokerError { error }),
+            }
+        }
+        PlannedAction::Modify { order_id, changes } => {
+            let order = deposit
+                .orders()
+                .iter()
+                .find(|order| order.has_id(order_id))
+                .ok_or_else(|| no_such_order(order_id))?;
+            if let Some(order_data) = order.find_order(order_id) {
+                check_changes::<B>(&changes, order, order_data)?;
+            }
+
+            let order_data = deposit
+                .change_order(order_id)
+                .ok_or_else(|| no_such_order(order_id))?;
+            let original = order_data.clone();
+            changes.apply(order_data);
+
+            let result = match simulation {
+                Some(_) => Ok(()),
+                None => broker.change_order(deposit, order_id),
+            };
+            match result {
+                Ok(()) => report.modified.push(order_id),
+                Err(error) => {
+                    // the broker didn't accept the changes, so the deposit has to keep the old order
+                    if let Some(order_data) = deposit.change_order(order_id) {
+                        *order_data = original;
+                    }
+                    events.push(OrderEvent::BrokerError { error });
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// the take profit of a bracket is a limit order, its stop loss a stop order
+fn is_take_profit(exit: &OrderData) -> bool {
+    matches!(exit.order_type(), OrderType::LimitOrder(_))
+}
+
+/// checks that the broker is able to execute an instruction
+fn check_capabilities<B: BrokerInterface>(instruction: &Instruction<'_>, stock_exchange: StockExchange) -> Result<(), Error<TradingErrorKind>> {
+    use BrokerCapability::*;
+
+    if instruction.opens_orders() && !B::STOCK_EXCHANGES.contains(&stock_exchange) {
+        return Err(Error::new(
+            format!("{} does not trade on {:?}", B::NAME, stock_exchange),
+            TradingErrorKind::UnsupportedInstruction,
+        ));
+    }
+
+    match instruction {
+        Instruction::Buy(request) => check_request::<B>(request),
+        Instruction::OneCancelsTheOther(requests) => {
+            require::<B>(OneCancelsTheOtherOrder)?;
+            requests
+                .iter()
+                .try_for_each(check_request::<B>)
+        }
+        Instruction::Bracket { entry, take_profit, stop_loss } => {
+            check_request::<B>(entry)?;
+            let direction = entry.position_type.direction();
+            check_take_profit::<B>(take_profit, direction)?;
+            check_stop_loss::<B>(stop_loss, direction)
+        }
+        Instruction::Sell { position } => check_order_type::<B>(&OrderType::MarketOrder, position.direction().opposite()),
+        Instruction::SellPartially { position, .. } => {
+            check_order_type::<B>(&OrderType::MarketOrder, position.direction().opposite())?;
+            require::<B>(PositionChange)
+        }
+        Instruction::CancelOrder { .. } => require::<B>(OrderDelete),
+        // the changed values are checked against the order once it's executed
+        Instruction::ModifyOrder { changes, .. } if changes.is_empty() => Ok(()),
+        Instruction::ModifyOrder { .. } => require::<B>(OrderChange),
+        Instruction::None => Ok(()),
+    }
+}
+
+fn check_request<B: BrokerInterface>(request: &OrderRequest<'_>) -> Result<(), Error<TradingErrorKind>> {
+    use BrokerCapability::*;
+
+    let direction = request.position_type.direction();
+    check_order_type::<B>(&request.order_type, direction)?;
+    require::<B>(match request.position_type {
+        PositionType::LongCall => LongCallPosition,
+        PositionType::LongPut => LongPutPosition,
+        PositionType::ShortCall => ShortCallPosition,
+        PositionType::ShortPut => ShortPutPosition,
+    })?;
+    check_take_profit::<B>(&request.take_profit, direction)?;
+    check_stop_loss::<B>(&request.stop_loss, direction)
+}
+
+/// checks that the exits of an active bracket can be placed for its open position
+///
+/// An active bracket doesn't come from an instruction, so it wasn't checked while planning.
+fn check_exits<B: BrokerInterface>(bracket: &Bracket, deposit: &Deposit) -> Result<(), Error<TradingErrorKind>> {
+    let position = match bracket.position() {
+        Some(position) => position,
+        None => return Ok(()),
+    };
+    if !deposit.positions().iter().any(|open| &open.id == position && open.is_open()) {
+        return Err(Error::new(
+            format!("The position `{}` does not exist", position),
+            TradingErrorKind::NoSuchPosition,
+        ));
+    }
+
+    let exit = bracket.entry().position_type().direction().opposite();
+    bracket
+        .children()
+        .iter()
+        .try_for_each(|child| check_order_type::<B>(child.order_type(), exit))
+}
+
+/// checks that the changes of an open order can be executed
+///
+/// The children of an active bracket close its position, so they trade in the opposite
+/// direction of its entry.
+fn check_changes<B: BrokerInterface>(changes: &OrderChanges, order: &Order, order_data: &OrderData) -> Result<(), Error<TradingErrorKind>> {
+    let direction = order_data.position_type().direction();
+    if let Some(order_type) = &changes.order_type {
+        let exit = matches!(order, Order::Bracket(bracket) if bracket.is_active());
+        check_order_type::<B>(order_type, if exit { direction.opposite() } else { direction })?;
+    }
+    if let Some(take_profit) = &changes.take_profit {
+        check_take_profit::<B>(take_profit, direction)?;
+    }
+    if let Some(stop_loss) = &changes.stop_loss {
+        check_stop_loss::<B>(stop_loss, direction)?;
+    }
+    Ok(())
+}
+
+/// checks that the broker can place an order of the type in the direction,
+/// long orders buy and short orders sell
+fn check_order_type<B: BrokerInterface>(order_type: &OrderType, direction: Direction) -> Result<(), Error<TradingErrorKind>> {
+    use BrokerCapability::*;
+
+    require::<B>(match (order_type, direction) {
+        (OrderType::MarketOrder, Direction::Long) => BuyMarketOrder,
+        (OrderType::LimitOrder(_), Direction::Long) => BuyLimitOrder,
+        (OrderType::StopOrder(_), Direction::Long) => BuyStopOrder,
+        (OrderType::MarketOrder, Direction::Short) => SellMarketOrder,
+        (OrderType::LimitOrder(_), Direction::Short) => SellLimitOrder,
+        (OrderType::StopOrder(_), Direction::Short) => SellStopOrder,
+    })
+}
+
+/// checks the take profit of a position in the direction, it's a limit order that closes the position
+fn check_take_profit<B: BrokerInterface>(take_profit: &TakeProfit, direction: Direction) -> Result<(), Error<TradingErrorKind>> {
+    match take_profit {
+        TakeProfit::None => Ok(()),
+        _ => {
+            require::<B>(BrokerCapability::TakeProfit)?;
+            require::<B>(match direction {
+                Direction::Long => BrokerCapability::SellLimitOrder,
+                Direction::Short => BrokerCapability::BuyLimitOrder,
+            })
+        }
+    }
+}
+
+/// checks the stop loss of a position in the direction, it's a stop order that closes the position
+fn check_stop_loss<B: BrokerInterface>(stop_loss: &StopLoss, direction: Direction) -> Result<(), Error<TradingErrorKind>> {
+    match stop_loss {
+        StopLoss::None => return Ok(()),
+        StopLoss::Trailing(_) => require::<B>(BrokerCapability::TrailingStopLoss)?,
+        _ => {}
+    }
+    require::<B>(match direction {
+        Direction::Long => BrokerCapability::SellStopOrder,
+        Direction::Short => BrokerCapability::BuyStopOrder,
+    })
+}
+
+fn require<B: BrokerInterface>(capability: BrokerCapability) -> Result<(), Error<TradingErrorKind>> {
+    if B::CAPABILITIES.contains(&capability) {
+        Ok(())
+    } else {
+        Err(Error::new(
+            format!("{} does not support {:?}", B::NAME, capability),
+            TradingErrorKind::UnsupportedInstruction,
+        ))
+    }
+}
+
+fn no_such_order(order_id: u64) -> Error<TradingErrorKind> {
+    Error::new(
+        format!("The order {} does not exist", order_id),
+        TradingErrorKind::NoSuchOrder,
+    )
+}
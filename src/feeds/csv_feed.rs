@@ -0,0 +1,150 @@
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+use std::path::Path;
+
+use chrono::{DateTime, Local, NaiveDateTime, TimeZone};
+
+use crate::{Candle, Derivative, Error, ErrorKind, FeedUpdate, MemoryFeed, PriceFeed, Price};
+
+/// A feed that reads candles from CSV files
+///
+/// Every file contains the candles of one symbol. The files are read completely when they are
+/// added, afterwards the feed behaves like a `MemoryFeed`.
+///
+/// #### Format:
+/// * an optional header line that starts with `time`, before the first candle
+/// * `time,open,high,low,close,volume`, `time,open,high,low,close` or `time,price` for ticks
+/// * the time is either RFC 3339 (`2020-05-04T09:30:00+02:00`), a local time
+///   (`2020-05-04 09:30:00`) or a unix timestamp in seconds
+/// * empty lines and lines starting with `#` are ignored
+#[derive(Clone, Debug, Default)]
+pub struct CsvFeed {
+    feed: MemoryFeed,
+}
+
+impl CsvFeed {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// reads the candles of a symbol from a file
+    pub fn with_file<P: AsRef<Path>>(mut self, symbol: &str, path: &P) -> Result<Self, Error<ErrorKind>> {
+        self.load(symbol, path)?;
+        Ok(self)
+    }
+
+    /// reads the candles of a symbol from a file and returns the amount of read candles
+    pub fn load<P: AsRef<Path>>(&mut self, symbol: &str, path: &P) -> Result<usize, Error<ErrorKind>> {
+        let file = File::open(path.as_ref())?;
+        let candles = Self::parse(BufReader::new(file)).map_err(|error| Error::new(
+            format!("{:?}: {}", path.as_ref(), error.msg()),
+            error.kind(),
+        ))?;
+
+        let amount = candles.len();
+        for candle in candles {
+            self.feed.push(symbol, candle);
+        }
+        Ok(amount)
+    }
+
+    /// parses candles in the CSV format of the feed
+    pub fn parse<R: BufRead>(reader: R) -> Result<Vec<Candle>, Error<ErrorKind>> {
+        let mut candles = Vec::new();
+        let mut first = true;
+
+        for (index, line) in reader.lines().enumerate() {
+            let line = line?;
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            // the header may follow empty lines and comments
+            let header = first && line.starts_with("time");
+            first = false;
+            if header {
+                continue;
+            }
+
+            let candle = parse_candle(line).map_err(|msg| Error::new(
+                format!("line {}: {}", index + 1, msg),
+                ErrorKind::InvalidData,
+            ))?;
+            candles.push(candle);
+        }
+
+        Ok(candles)
+    }
+
+    pub fn memory_feed(&self) -> &MemoryFeed { &self.feed }
+}
+
+impl PriceFeed for CsvFeed {
+    fn history(&self, derivative: &Derivative, from: DateTime<Local>, to: DateTime<Local>) -> Result<Vec<Candle>, Error<ErrorKind>> {
+        self.feed.history(derivative, from, to)
+    }
+
+    fn subscribe(&mut self, derivative: &Derivative) -> Result<(), Error<ErrorKind>> {
+        self.feed.subscribe(derivative)
+    }
+
+    fn poll(&mut self, time: DateTime<Local>) -> Result<Vec<FeedUpdate>, Error<ErrorKind>> {
+        self.feed.poll(time)
+    }
+}
+
+fn parse_candle(line: &str) -> Result<Candle, String> {
+    let fields = line
+        .split(',')
+        .map(str::trim)
+        .collect::<Vec<_>>();
+
+    let time = parse_time(fields[0])?;
+    match fields.len() {
+        2 => Ok(Candle::from_price(time, parse_price(fields[1])?)),
+        5 | 6 => {
+            let volume = match fields.get(5) {
+                Some(volume) => volume
+                    .parse::<u64>()
+                    .map_err(|_| format!("invalid volume `{}`", volume))?,
+                None => 0
+            };
+            Ok(Candle::new(
+                time,
+                parse_price(fields[1])?,
+                parse_price(fields[2])?,
+                parse_price(fields[3])?,
+                parse_price(fields[4])?,
+                volume,
+            ))
+        }
+        amount => Err(format!("expected 2, 5 or 6 columns, found {}", amount))
+    }
+}
+
+fn parse_time(field: &str) -> Result<DateTime<Local>, String> {
+    if let Ok(time) = DateTime::parse_from_rfc3339(field) {
+        return Ok(time.with_timezone(&Local));
+    }
+    if let Ok(time) = NaiveDateTime::parse_from_str(field, "%Y-%m-%d %H:%M:%S") {
+        return Local
+            .from_local_datetime(&time)
+            .earliest()
+            .ok_or_else(|| format!("the local time `{}` does not exist", field));
+    }
+    if let Ok(seconds) = field.parse::<i64>() {
+        return Local
+            .timestamp_opt(seconds, 0)
+            .single()
+            .ok_or_else(|| format!("invalid timestamp `{}`", field));
+    }
+
+    Err(format!("invalid time `{}`", field))
+}
+
+fn parse_price(field: &str) -> Result<Price, String> {
+    field
+        .parse::<f64>()
+        .map(Price::from)
+        .map_err(|_| format!("invalid price `{}`", field))
+}
@@ -0,0 +1,99 @@
+use std::collections::HashMap;
+
+use chrono::{DateTime, Local};
+
+use crate::{Candle, Derivative, Error, ErrorKind, FeedUpdate, PriceFeed};
+
+/// A feed that keeps all candles in memory
+///
+/// Candles can be added at any time with `MemoryFeed::push`, so the feed can also be filled
+/// while it's used.
+#[derive(Clone, Debug, Default)]
+pub struct MemoryFeed {
+    candles: HashMap<String, Vec<Candle>>,
+    /// the amount of candles that were already polled per subscribed symbol
+    subscriptions: HashMap<String, usize>,
+}
+
+impl MemoryFeed {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// creates a feed with the candles of a single symbol
+    pub fn with_candles(mut self, symbol: &str, candles: Vec<Candle>) -> Self {
+        for candle in candles {
+            self.push(symbol, candle);
+        }
+        self
+    }
+
+    /// adds a candle, candles are kept sorted by time
+    ///
+    /// A candle that is older than an already polled candle will never be polled.
+    pub fn push(&mut self, symbol: &str, candle: Candle) {
+        let candles = self.candles
+                          .entry(symbol.to_string())
+                          .or_default();
+        let index = candles.partition_point(|existing| existing.time <= candle.time);
+        candles.insert(index, candle);
+
+        // the candle is older than the polled candles, so it's skipped
+        if let Some(polled) = self.subscriptions.get_mut(symbol) {
+            if index < *polled {
+                *polled += 1;
+            }
+        }
+    }
+
+    pub fn symbols(&self) -> impl Iterator<Item=&str> {
+        self.candles
+            .keys()
+            .map(String::as_str)
+    }
+
+    pub fn candles(&self, symbol: &str) -> &[Candle] {
+        self.candles
+            .get(symbol)
+            .map_or(&[], Vec::as_slice)
+    }
+}
+
+impl PriceFeed for MemoryFeed {
+    fn history(&self, derivative: &Derivative, from: DateTime<Local>, to: DateTime<Local>) -> Result<Vec<Candle>, Error<ErrorKind>> {
+        let candles = self.candles(&derivative.symbol);
+        let start = candles.partition_point(|candle| candle.time < from);
+        let end = candles.partition_point(|candle| candle.time < to);
+
+        Ok(candles[start..end.max(start)].to_vec())
+    }
+
+    fn subscribe(&mut self, derivative: &Derivative) -> Result<(), Error<ErrorKind>> {
+        self.subscriptions
+            .entry(derivative.symbol.clone())
+            .or_insert(0);
+        Ok(())
+    }
+
+    fn poll(&mut self, time: DateTime<Local>) -> Result<Vec<FeedUpdate>, Error<ErrorKind>> {
+        let mut updates = Vec::new();
+
+        for (symbol, polled) in self.subscriptions.iter_mut() {
+            let candles = self.candles
+                              .get(symbol)
+                              .map_or(&[][..], Vec::as_slice);
+            let available = candles.partition_point(|candle| candle.time <= time);
+
+            if available > *polled {
+                updates.extend(candles[*polled..available].iter().map(|candle| FeedUpdate {
+                    symbol: symbol.clone(),
+                    candle: *candle,
+                }));
+                *polled = available;
+            }
+        }
+
+        updates.sort_by_key(|update| update.candle.time);
+        Ok(updates)
+    }
+}
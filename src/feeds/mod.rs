@@ -0,0 +1,9 @@
+pub use csv_feed::*;
+pub use memory_feed::*;
+pub use price_feed::*;
+pub use replay_feed::*;
+
+pub mod csv_feed;
+pub mod memory_feed;
+pub mod price_feed;
+pub mod replay_feed;
@@ -0,0 +1,30 @@
+use chrono::{DateTime, Local};
+
+use crate::{Candle, Derivative, Error, ErrorKind};
+
+/// A new candle of a subscribed derivative
+#[derive(Clone, Debug, PartialEq)]
+pub struct FeedUpdate {
+    pub symbol: String,
+    pub candle: Candle,
+}
+
+/// A source of prices
+///
+/// A feed provides the historical candles of a derivative and new candles of the derivatives
+/// it is subscribed to. Ticks are candles with a single price, see `Candle::from_price`.
+/// Runners, backtests and brokers that simulate a market all get their prices from a feed, so
+/// an algorithm sees the same data no matter how it's run.
+pub trait PriceFeed {
+    /// returns the candles of a derivative with `from <= time < to`, sorted by time
+    fn history(&self, derivative: &Derivative, from: DateTime<Local>, to: DateTime<Local>) -> Result<Vec<Candle>, Error<ErrorKind>>;
+
+    /// adds a derivative to the derivatives returned by `PriceFeed::poll`
+    fn subscribe(&mut self, derivative: &Derivative) -> Result<(), Error<ErrorKind>>;
+
+    /// returns the candles of subscribed derivatives that were not returned yet and that are
+    /// available at `time`, sorted by time
+    ///
+    /// The first poll after a subscription returns all candles of the derivative up to `time`.
+    fn poll(&mut self, time: DateTime<Local>) -> Result<Vec<FeedUpdate>, Error<ErrorKind>>;
+}
@@ -0,0 +1,99 @@
+use chrono::{DateTime, Duration, Local};
+
+use crate::{Candle, Derivative, Error, ErrorKind, FeedUpdate, PriceFeed};
+
+/// A feed that plays the history of another feed back
+///
+/// The replay starts at `start` in the history when the feed is polled for the first time.
+/// Afterwards the history advances `speed` times as fast as the polled time, so a speed of 60
+/// replays an hour of history per minute.
+/// Candles after the replayed time are neither polled nor returned by `PriceFeed::history`,
+/// candles before `start` are only available through `PriceFeed::history`.
+#[derive(Clone, Debug)]
+pub struct ReplayFeed<F> {
+    feed: F,
+    start: DateTime<Local>,
+    speed: f64,
+
+    started: Option<DateTime<Local>>,
+    replayed: Option<DateTime<Local>>,
+    subscriptions: Vec<Derivative>,
+}
+
+impl<F: PriceFeed> ReplayFeed<F> {
+    /// creates a replay of `feed` that starts at `start`
+    ///
+    /// Returns an error if the speed is not positive.
+    pub fn new(feed: F, start: DateTime<Local>, speed: f64) -> Result<Self, Error<ErrorKind>> {
+        if !(speed > 0.0 && speed.is_finite()) {
+            return Err(Error::new(
+                format!("The speed of a replay needs to be positive, but is {}", speed),
+                ErrorKind::InvalidParameter,
+            ));
+        }
+
+        Ok(Self {
+            feed,
+            start,
+            speed,
+            started: None,
+            replayed: None,
+            subscriptions: Vec::new(),
+        })
+    }
+
+    pub fn feed(&self) -> &F { &self.feed }
+    pub fn speed(&self) -> f64 { self.speed }
+
+    /// returns the time in the history up to which the candles were replayed
+    pub fn replayed(&self) -> Option<DateTime<Local>> { self.replayed }
+
+    /// translates a polled time into the time of the history
+    pub fn history_time(&self, time: DateTime<Local>) -> DateTime<Local> {
+        let elapsed = time - self.started.unwrap_or(time);
+        let scaled = (elapsed.num_milliseconds() as f64 * self.speed) as i64;
+        self.start + Duration::milliseconds(scaled)
+    }
+}
+
+impl<F: PriceFeed> PriceFeed for ReplayFeed<F> {
+    fn history(&self, derivative: &Derivative, from: DateTime<Local>, to: DateTime<Local>) -> Result<Vec<Candle>, Error<ErrorKind>> {
+        // the future of the replay is unknown, the history before the start is known
+        let replayed = self.replayed.unwrap_or(self.start - Duration::nanoseconds(1));
+        let to = to.min(replayed + Duration::nanoseconds(1));
+        if to <= from {
+            return Ok(Vec::new());
+        }
+
+        self.feed.history(derivative, from, to)
+    }
+
+    fn subscribe(&mut self, derivative: &Derivative) -> Result<(), Error<ErrorKind>> {
+        if !self.subscriptions.contains(derivative) {
+            self.subscriptions.push(derivative.clone());
+        }
+        Ok(())
+    }
+
+    fn poll(&mut self, time: DateTime<Local>) -> Result<Vec<FeedUpdate>, Error<ErrorKind>> {
+        self.started.get_or_insert(time);
+        let until = self.history_time(time);
+        let from = self.replayed.map_or(self.start, |replayed| replayed + Duration::nanoseconds(1));
+        if until < from {
+            return Ok(Vec::new());
+        }
+
+        let mut updates = Vec::new();
+        for derivative in &self.subscriptions {
+            let candles = self.feed.history(derivative, from, until + Duration::nanoseconds(1))?;
+            updates.extend(candles.into_iter().map(|candle| FeedUpdate {
+                symbol: derivative.symbol.clone(),
+                candle,
+            }));
+        }
+        self.replayed = Some(until);
+
+        updates.sort_by_key(|update| update.candle.time);
+        Ok(updates)
+    }
+}
@@ -1,13 +1,223 @@
-use crate::{OrderType, Position, PositionType};
+use crate::{Bracket, Derivative, Order, OrderData, OrderMoment, OrderType, OrderValidity, Position, PositionType, StockExchange, StopLoss, TakeProfit};
 
+/// An instruction given by an algorithm
+///
+/// Instructions that open new orders (`Buy`, `OneCancelsTheOther` and `Bracket`) can be
+/// translated into an `Order` with `Instruction::to_order`. The remaining instructions refer to
+/// existing orders or positions and are executed directly.
+///
+/// #### Variants:
+/// * __Buy__: Opens a new order
+/// * __Sell__: Closes a whole position
+/// * __SellPartially__: Closes some pieces of a position
+/// * __CancelOrder__: Cancels an open order by its id
+/// * __ModifyOrder__: Changes an open order by its id
+/// * __OneCancelsTheOther__: Opens a group of orders, as soon as one is filled the others are cancelled
+/// * __Bracket__: Opens an order that is protected by a take profit and a stop loss
+/// * __None__: Does nothing
+#[derive(Clone, Debug, PartialEq)]
 pub enum Instruction<'p> {
-    Buy {
-        pieces: u64,
-        order_type: OrderType,
-        position_type: PositionType,
-    },
+    Buy(OrderRequest<'p>),
     Sell {
         position: &'p Position
     },
+    SellPartially {
+        position: &'p Position,
+        pieces: u64,
+    },
+    CancelOrder {
+        order_id: u64
+    },
+    ModifyOrder {
+        order_id: u64,
+        changes: OrderChanges,
+    },
+    OneCancelsTheOther(Vec<OrderRequest<'p>>),
+    Bracket {
+        entry: OrderRequest<'p>,
+        take_profit: TakeProfit,
+        stop_loss: StopLoss,
+    },
     None,
 }
+
+impl<'p> Instruction<'p> {
+    /// returns true if the instruction opens new orders
+    ///
+    /// Opening orders is not allowed while an algorithm is shut down.
+    pub fn opens_orders(&self) -> bool {
+        use Instruction::*;
+        matches!(self, Buy(_) | OneCancelsTheOther(_) | Bracket { .. })
+    }
+
+    /// returns all order requests of the instruction
+    pub fn order_requests(&self) -> Vec<&OrderRequest<'p>> {
+        use Instruction::*;
+        match self {
+            Buy(request) => vec![request],
+            OneCancelsTheOther(requests) => requests.iter().collect(),
+            Bracket { entry, .. } => vec![entry],
+            _ => Vec::new()
+        }
+    }
+
+    /// translates the instruction into an order
+    ///
+    /// Requests without a derivative are translated for the `default_derivative`, which usually
+    /// is the derivative a single asset algorithm was initialised with.
+    /// Each `OrderData` gets a raw id from `next_raw_id`.
+    /// A bracket is translated into a `Bracket` order, its entry carries the take profit and
+    /// the stop loss.
+    /// Returns None for instructions that don't open orders.
+    pub fn to_order<F: FnMut() -> String>(
+        &self,
+        default_derivative: &Derivative,
+        stock_exchange: StockExchange,
+        mut next_raw_id: F,
+    ) -> Option<Order> {
+        match self {
+            Instruction::Buy(request) => Some(Order::Single(
+                request.to_order_data(next_raw_id(), default_derivative, stock_exchange)
+            )),
+            Instruction::OneCancelsTheOther(requests) => Some(Order::OneCancelsTheOther(
+                requests
+                    .iter()
+                    .map(|request| request.to_order_data(next_raw_id(), default_derivative, stock_exchange))
+                    .collect()
+            )),
+            Instruction::Bracket { entry, take_profit, stop_loss } => {
+                let entry = entry
+                    .clone()
+                    .with_take_profit(take_profit.clone())
+                    .with_stop_loss(stop_loss.clone());
+                Some(Order::Bracket(Bracket::new(
+                    entry.to_order_data(next_raw_id(), default_derivative, stock_exchange)
+                )))
+            }
+            _ => None
+        }
+    }
+}
+
+/// A request to open a new order
+///
+/// A request is created with the required values, the optional ones can be set with the
+/// builder methods:
+/// * __derivative__: None, which means the derivative the algorithm was initialised with
+/// * __take_profit__, __stop_loss__: None
+/// * __moment__: Instant
+/// * __validity__: OneDay
+#[derive(Clone, Debug, PartialEq)]
+pub struct OrderRequest<'p> {
+    pub derivative: Option<&'p Derivative>,
+    pub pieces: u64,
+    pub order_type: OrderType,
+    pub position_type: PositionType,
+    pub take_profit: TakeProfit,
+    pub stop_loss: StopLoss,
+    pub moment: OrderMoment,
+    pub validity: OrderValidity,
+}
+
+impl<'p> OrderRequest<'p> {
+    pub fn new(pieces: u64, order_type: OrderType, position_type: PositionType) -> Self {
+        Self {
+            derivative: None,
+            pieces,
+            order_type,
+            position_type,
+            take_profit: TakeProfit::None,
+            stop_loss: StopLoss::None,
+            moment: OrderMoment::Instant,
+            validity: OrderValidity::OneDay,
+        }
+    }
+
+    pub fn for_derivative(mut self, derivative: &'p Derivative) -> Self {
+        self.derivative = Some(derivative);
+        self
+    }
+
+    pub fn with_take_profit(mut self, take_profit: TakeProfit) -> Self {
+        self.take_profit = take_profit;
+        self
+    }
+
+    pub fn with_stop_loss(mut self, stop_loss: StopLoss) -> Self {
+        self.stop_loss = stop_loss;
+        self
+    }
+
+    pub fn at(mut self, moment: OrderMoment) -> Self {
+        self.moment = moment;
+        self
+    }
+
+    pub fn valid_for(mut self, validity: OrderValidity) -> Self {
+        self.validity = validity;
+        self
+    }
+
+    /// returns the derivative of the request or the default derivative
+    pub fn derivative_or<'d>(&self, default_derivative: &'d Derivative) -> &'d Derivative
+        where 'p: 'd {
+        self.derivative.unwrap_or(default_derivative)
+    }
+
+    /// translates the request into the data of an order
+    pub fn to_order_data(&self, raw_id: String, default_derivative: &Derivative, stock_exchange: StockExchange) -> OrderData {
+        OrderData::new(
+            raw_id,
+            self.derivative.unwrap_or(default_derivative).clone(),
+            stock_exchange,
+            self.pieces,
+            self.order_type.clone(),
+            self.position_type,
+            self.take_profit.clone(),
+            self.stop_loss.clone(),
+            self.moment.clone(),
+            self.validity,
+        )
+    }
+}
+
+/// Changes of an open order
+///
+/// Only the values that are Some will be changed.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct OrderChanges {
+    pub pieces: Option<u64>,
+    pub order_type: Option<OrderType>,
+    pub take_profit: Option<TakeProfit>,
+    pub stop_loss: Option<StopLoss>,
+    pub moment: Option<OrderMoment>,
+    pub validity: Option<OrderValidity>,
+}
+
+impl OrderChanges {
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// applies the changes to the data of an order
+    pub fn apply(&self, order_data: &mut OrderData) {
+        if let Some(pieces) = self.pieces {
+            order_data.update_pieces(pieces);
+        }
+        if let Some(order_type) = &self.order_type {
+            order_data.update_order_type(order_type.clone());
+        }
+        if let Some(take_profit) = &self.take_profit {
+            order_data.update_take_profit(take_profit.clone());
+        }
+        if let Some(stop_loss) = &self.stop_loss {
+            order_data.update_stop_loss(stop_loss.clone());
+        }
+        if let Some(moment) = &self.moment {
+            order_data.update_moment(moment.clone());
+        }
+        if let Some(validity) = self.validity {
+            order_data.update_validity(validity);
+        }
+    }
+}
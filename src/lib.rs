@@ -1,29 +1,102 @@
+//! Utilities to write, load and run trading algorithms
+//!
+//! ## Features
+//! * __log__: Writes all events to the `log` crate
+//! * __serde__: Implements `Serialize` and `Deserialize` for the domain types
+//! * __decimal__: Uses fixed point decimals instead of floats for `Price`, `Points` and
+//!   `Percent`, see `Value`
+//!
+//! ## JSON
+//! With the `serde` feature the domain types have the following, stable JSON shapes:
+//! * __Price__, __Percent__, __Points__: a number, `105.5`, also with the `decimal` feature
+//! * __Currency__, __StockExchange__: the name of the variant, `"EUR"`, `"NYSE"`
+//! * __PositionType__, __OrderValidity__ and the error kinds: the variant in snake case,
+//!   `"long_call"`, `"one_week"`
+//! * __Isin__, __Wkn__: a string, `"US0378331005"`
+//! * __OrderType__, __TakeProfit__, __StopLoss__, __OrderMoment__, __Order__, __DerivativeKind__:
+//!   an object with the variant in snake case as `type` and the content of the variant as `value`,
+//!   `{"type": "limit_order", "value": 10.5}`, `{"type": "none"}`
+//! * times: RFC 3339 strings, `"2020-05-04T09:30:00+02:00"`
+//! * __Derivative__, __TradingHours__, __Candle__, __Position__, __OrderData__, __Deposit__, __Error__:
+//!   an object with a field per struct field, weekdays are written as `"Mon"`
+//!
+//! The `id` of `OrderData` and `Deposit` is written as a string, since JavaScript can't
+//! represent every u64. It's ignored when reading, since it's the hash of the `raw_id` and
+//! computed again.
+//! ```
+//! # #[cfg(feature = "serde")] {
+//! use trading_utils::{OrderType, Price, StopLoss};
+//!
+//! let order_type = OrderType::LimitOrder(Price::from(10.5));
+//! let json = serde_json::to_string(&order_type).unwrap();
+//! assert_eq!(json, r#"{"type":"limit_order","value":10.5}"#);
+//! assert_eq!(serde_json::from_str::<OrderType>(&json).unwrap(), order_type);
+//!
+//! let stop_loss = serde_json::to_string(&StopLoss::None).unwrap();
+//! assert_eq!(stop_loss, r#"{"type":"none"}"#);
+//! # }
+//! ```
+
+pub use aligned_series::*;
 pub use algorithms::*;
 pub use banks::*;
+pub use bracket::*;
 pub use brokers::*;
+pub use candle::*;
 pub use currency::*;
 pub use derivative::*;
 pub use error::*;
+pub use event::*;
+pub use executor::*;
 pub use export::*;
+pub use feeds::*;
 pub use instruction::*;
 pub use market_values::*;
+pub use metrics::*;
 pub use order::*;
+pub use portfolio::*;
 pub use position::*;
+pub use position_sizing::*;
+pub use pricing::*;
+pub use risk::*;
+pub use session::*;
 pub use stock_exchange::*;
+pub use storage::*;
+pub use trading_hours::*;
+pub use trailing_stop::*;
 pub use transaction::*;
+pub use trading_macros::algorithm;
 
+pub mod aligned_series;
 pub mod algorithms;
 pub mod banks;
+pub mod bracket;
 pub mod brokers;
+pub mod candle;
 pub mod currency;
 pub mod derivative;
 pub mod error;
+pub mod event;
+pub mod feeds;
+pub mod executor;
 pub mod instruction;
+pub mod metrics;
 pub mod order;
+pub mod portfolio;
 pub mod position;
+pub mod position_sizing;
+pub mod pricing;
+pub mod risk;
+pub mod session;
 pub mod market_values;
 pub mod stock_exchange;
+pub mod storage;
+pub mod trading_hours;
+pub mod trailing_stop;
 pub mod transaction;
 
+#[cfg(feature = "serde")]
+mod serde_util;
+
 pub const UTILS_VERSION: &str = env!("CARGO_PKG_VERSION");
 pub const RUSTC_VERSION: &str = env!("RUSTC_VERSION");
@@ -4,20 +4,22 @@ use std::fmt::Debug;
 pub use percent::*;
 pub use points::*;
 pub use price::*;
+pub use rounding::*;
+pub use value::*;
 
 macro_rules! impl_ops {
     ($name:path, no_percent) => {
         use std::ops::*;
         use auto_ops::impl_op_ex;
 
-        impl AsRef<f64> for $name {
-            fn as_ref(&self) -> &f64 {
+        impl AsRef<crate::Value> for $name {
+            fn as_ref(&self) -> &crate::Value {
                 &self.0
             }
         }
 
         impl Deref for $name {
-            type Target = f64;
+            type Target = crate::Value;
 
             fn deref(&self) -> &Self::Target {
                 &self.0
@@ -32,38 +34,71 @@ macro_rules! impl_ops {
 
         impl From<f64> for $name {
             fn from(float: f64) -> Self {
-                $name(float)
+                $name(crate::value_from_f64(float))
             }
         }
 
         impl From<&f64> for $name {
             fn from(float: &f64) -> Self {
-                $name(*float)
+                $name(crate::value_from_f64(*float))
             }
         }
 
-        impl Into<f64> for $name {
-            fn into(self) -> f64 {
-                self.0
+        impl From<$name> for f64 {
+            fn from(value: $name) -> Self {
+                crate::value_to_f64(value.0)
             }
         }
 
-        // lhs: $name, rhs: f64
-        impl_op_ex!(+ |a: &$name, b: &f64| -> $name { $name(a.0 + b) });
-        impl_op_ex!(- |a: &$name, b: &f64| -> $name { $name(a.0 - b) });
-        impl_op_ex!(* |a: &$name, b: &f64| -> $name { $name(a.0 * b) });
-        impl_op_ex!(/ |a: &$name, b: &f64| -> $name { $name(a.0 / b) });
+        #[cfg(feature = "decimal")]
+        impl From<crate::Value> for $name {
+            fn from(value: crate::Value) -> Self {
+                $name(value)
+            }
+        }
+
+        #[cfg(feature = "decimal")]
+        impl From<$name> for crate::Value {
+            fn from(value: $name) -> Self {
+                value.0
+            }
+        }
 
-        impl_op_ex!(+= |a: &mut $name, b: &f64| { a.0 += b; });
-        impl_op_ex!(-= |a: &mut $name, b: &f64| { a.0 -= b; });
-        impl_op_ex!(*= |a: &mut $name, b: &f64| { a.0 *= b; });
-        impl_op_ex!(/= |a: &mut $name, b: &f64| { a.0 /= b; });
+        // lhs: $name, rhs: f64
+        impl_op_ex!(+ |a: &$name, b: &f64| -> $name { $name(a.0 + crate::value_from_f64(*b)) });
+        impl_op_ex!(- |a: &$name, b: &f64| -> $name { $name(a.0 - crate::value_from_f64(*b)) });
+        impl_op_ex!(* |a: &$name, b: &f64| -> $name { $name(a.0 * crate::value_from_f64(*b)) });
+        impl_op_ex!(/ |a: &$name, b: &f64| -> $name { $name(a.0 / crate::value_from_f64(*b)) });
+
+        impl_op_ex!(+= |a: &mut $name, b: &f64| { a.0 += crate::value_from_f64(*b); });
+        impl_op_ex!(-= |a: &mut $name, b: &f64| { a.0 -= crate::value_from_f64(*b); });
+        impl_op_ex!(*= |a: &mut $name, b: &f64| { a.0 *= crate::value_from_f64(*b); });
+        impl_op_ex!(/= |a: &mut $name, b: &f64| { a.0 /= crate::value_from_f64(*b); });
+
+        // lhs: $name, rhs: crate::Value, with floats this is the same as above
+        #[cfg(feature = "decimal")]
+        impl_op_ex!(+ |a: &$name, b: &crate::Value| -> $name { $name(a.0 + b) });
+        #[cfg(feature = "decimal")]
+        impl_op_ex!(- |a: &$name, b: &crate::Value| -> $name { $name(a.0 - b) });
+        #[cfg(feature = "decimal")]
+        impl_op_ex!(* |a: &$name, b: &crate::Value| -> $name { $name(a.0 * b) });
+        #[cfg(feature = "decimal")]
+        impl_op_ex!(/ |a: &$name, b: &crate::Value| -> $name { $name(a.0 / b) });
+
+        #[cfg(feature = "decimal")]
+        impl_op_ex!(+= |a: &mut $name, b: &crate::Value| { a.0 += b; });
+        #[cfg(feature = "decimal")]
+        impl_op_ex!(-= |a: &mut $name, b: &crate::Value| { a.0 -= b; });
+        #[cfg(feature = "decimal")]
+        impl_op_ex!(*= |a: &mut $name, b: &crate::Value| { a.0 *= b; });
+        #[cfg(feature = "decimal")]
+        impl_op_ex!(/= |a: &mut $name, b: &crate::Value| { a.0 /= b; });
 
         // lhs: $name, rhs: Percent
         impl_op_ex!(+ |a: &$name, b: &Percent| -> $name { $name(a.0 + a.0 * b.0) });
         impl_op_ex!(- |a: &$name, b: &Percent| -> $name { $name(a.0 - a.0 * b.0) });
         impl_op_ex!(* |a: &$name, b: &Percent| -> $name { $name(a.0 * b.0) });
-        impl_op_ex!(/ |a: &$name, b: &Percent| -> $name { $name(a.0 * b.0) });
+        impl_op_ex!(/ |a: &$name, b: &Percent| -> $name { $name(a.0 / b.0) });
 
         impl_op_ex!(+= |a: &mut $name, b: &Percent| { a.0 += a.0 * b.0; });
         impl_op_ex!(-= |a: &mut $name, b: &Percent| { a.0 -= a.0 * b.0; });
@@ -89,8 +124,10 @@ macro_rules! impl_ops {
 pub mod percent;
 pub mod points;
 pub mod price;
+pub mod rounding;
+pub mod value;
 
-type Momentum = f64;
+type Momentum = Value;
 
 macro_rules! get_cross_over {
     ($instances:ident) => {
@@ -135,16 +172,41 @@ macro_rules! get_cross_over {
 }
 
 pub trait MarketValue:
-AsRef<f64> + Deref<Target=f64> + DerefMut<Target=f64> + From<f64> + Into<f64> +
+AsRef<Value> + Deref<Target=Value> + DerefMut<Target=Value> + From<Value> + Into<Value> + From<f64> + Into<f64> +
 Add<Self> + AddAssign<Self> + Sub<Self> + SubAssign<Self> + Mul<Self> + MulAssign<Self> + Div<Self> + DivAssign<Self> +
 Add<Percent> + AddAssign<Percent> + Sub<Percent> + SubAssign<Percent> + Mul<Percent> + MulAssign<Percent> + Div<Percent> + DivAssign<Percent> +
 Add<f64> + AddAssign<f64> + Sub<f64> + SubAssign<f64> + Mul<f64> + MulAssign<f64> + Div<f64> + DivAssign<f64> +
+Add<Value> + AddAssign<Value> + Sub<Value> + SubAssign<Value> + Mul<Value> + MulAssign<Value> + Div<Value> + DivAssign<Value> +
 PartialEq + PartialOrd +
 Debug + Clone + Copy {
     fn new(value: f64) -> Self;
 
-    fn value(&self) -> &f64 { self.deref() }
-    fn value_mut(&mut self) -> &mut f64 { self.deref_mut() }
+    fn value(&self) -> &Value { self.deref() }
+    fn value_mut(&mut self) -> &mut Value { self.deref_mut() }
+
+    /// returns the value as float, decimals can lose precision
+    fn as_f64(&self) -> f64 { value_to_f64(*self.value()) }
+
+    /// rounds the value to the given number of decimal places
+    /// ```
+    /// # use trading_utils::{MarketValue, Price, RoundingMode};
+    /// assert_eq!(Price::from(2.345).round_dp(2, RoundingMode::HalfUp), Price::from(2.35));
+    /// assert_eq!(Price::from(2.345).round_dp(2, RoundingMode::Down), Price::from(2.34));
+    /// ```
+    fn round_dp(&self, decimals: u32, mode: RoundingMode) -> Self {
+        Self::from(rounding::round_dp(*self.value(), decimals, mode))
+    }
+
+    /// rounds the value to a multiple of the tick, a tick of zero leaves the value as it is
+    /// ```
+    /// # use trading_utils::{MarketValue, Price, RoundingMode};
+    /// let tick = Price::from(0.05);
+    /// assert_eq!(Price::from(10.23).round_to_tick(tick, RoundingMode::HalfUp), Price::from(10.25));
+    /// assert_eq!(Price::from(10.23).round_to_tick(tick, RoundingMode::Floor), Price::from(10.2));
+    /// ```
+    fn round_to_tick(&self, tick: Self, mode: RoundingMode) -> Self {
+        Self::from(rounding::round_to_tick(*self.value(), *tick.value(), mode))
+    }
 
     fn zero() -> Self { Self::new(0.0) }
     fn one() -> Self { Self::new(1.0) }
@@ -187,7 +249,7 @@ Debug + Clone + Copy {
                     prev += curr;
                     prev
                 });
-        Self::new(*sum / instances.len() as f64)
+        Self::from(*sum / value_from_usize(instances.len()))
     }
 
     fn simple_moving_average(instances: &[Self], interval: usize) -> Vec<Self> {
@@ -208,7 +270,7 @@ Debug + Clone + Copy {
 
     fn momentum(instances: &[Self]) -> Momentum {
         if instances.len() <= 1 {
-            0.0
+            Momentum::default()
         } else {
             let first = *instances[0];
             let last = *instances[instances.len() - 1];
@@ -219,7 +281,7 @@ Debug + Clone + Copy {
 
     fn average_momentum(instances: &[Self]) -> Momentum {
         if instances.len() <= 1 {
-            return 0.0;
+            return Momentum::default();
         }
         let mut momentums = Vec::with_capacity(instances.len() - 1);
 
@@ -237,7 +299,7 @@ Debug + Clone + Copy {
                 momentums[0],
                 |prev, curr| prev + *curr,
             );
-        sum / momentums.len() as f64
+        sum / value_from_usize(momentums.len())
     }
 
     fn moving_momentum(instances: &[Self], interval: usize) -> Vec<Momentum> {
@@ -323,7 +385,7 @@ Debug + Clone + Copy {
         let mut cross_overs = Vec::new();
         if instances1.len() <= 1 || instances2.len() <= 1 { return cross_overs; }
 
-        while let Some(i) = Self::first_cross_over(&instances1, &instances2) {
+        while let Some(i) = Self::first_cross_over(instances1, instances2) {
             cross_overs.push(i);
 
             instances1 = &instances1[i..];
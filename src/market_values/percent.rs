@@ -1,39 +1,40 @@
 use std::fmt::Debug;
 
-use crate::MarketValue;
+use crate::{MarketValue, Value, value_from_f64};
 
-#[derive(Copy, Clone, Debug, PartialOrd, PartialEq)]
-pub struct Percent(pub(crate) f64);
+#[derive(Copy, Clone, Debug, Default, PartialOrd, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(transparent))]
+pub struct Percent(pub(crate) Value);
 
 impl Percent {
     pub fn from_decimal(decimal: f64) -> Self {
-        Self(decimal / 100.0)
+        Self(value_from_f64(decimal) / Value::from(100))
     }
 
     pub fn growth<P: MarketValue>(old: P, new: P) -> Self {
-        Self((*new / *old) - 1.0)
+        Self((*new / *old) - Value::from(1))
     }
 }
 
 impl MarketValue for Percent {
     fn new(value: f64) -> Self {
-        Self(value)
+        Self(value_from_f64(value))
     }
 
     fn one() -> Self {
-        Self(0.01)
+        Self::new(0.01)
     }
 
     fn minus_one() -> Self {
-        Self(-0.01)
+        Self::new(-0.01)
     }
 
     fn one_hundred() -> Self {
-        Self(1.0)
+        Self::new(1.0)
     }
 
     fn minus_one_hundred() -> Self {
-        Self(1.0)
+        Self::new(1.0)
     }
 }
 
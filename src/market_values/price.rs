@@ -1,6 +1,6 @@
 use std::fmt::Debug;
 
-use crate::{Currency, MarketValue, Percent, Points, RelativePoints};
+use crate::{Currency, MarketValue, Percent, Points, RelativePoints, Value, value_from_f64};
 
 pub type PriceWithCurrency = (Price, Currency);
 pub type RelativePrice = Price;
@@ -17,38 +17,39 @@ pub type RelativePrice = Price;
 /// ## Percent calculations
 /// When working with market_values and percentages it's important to be aware how different operations
 /// are implemented. If you need a custom behaviour you can always dereference both the price and
-/// percentage to a float.
+/// percentage to a `Value`.
 ///
 /// #### Addition
 /// When you add a percentage to a price the price will be increased by the persentage of itself.
 /// ```
 ///# use trading_utils::{Price, Percent};
-/// let addition = Price(100.0) + Percent(0.05);
-/// assert_eq!(addition, Price(105.0));
+/// let addition = Price::from(100.0) + Percent::from(0.05);
+/// assert_eq!(addition, Price::from(105.0));
 ///```
 /// #### Subtraction
 /// When you subtract a percentage of a price the price will be decreased by the percentage of itself.
 /// ```
 /// # use trading_utils::{Price, Percent};
-/// let subtraction = Price(100.0) - Percent(0.05);
-/// assert_eq!(subtraction, Price(95.0));
+/// let subtraction = Price::from(100.0) - Percent::from(0.05);
+/// assert_eq!(subtraction, Price::from(95.0));
 /// ```
 /// #### Multiplication
 /// When you multiplie a price and a percentage the price will be the percentage of itself.
 /// ```
 /// # use trading_utils::{Price, Percent};
-/// let multiplication = Price(100.0) * Percent(0.05);
-/// assert_eq!(multiplication, Price(5.0));
+/// let multiplication = Price::from(100.0) * Percent::from(0.05);
+/// assert_eq!(multiplication, Price::from(5.0));
 /// ```
 /// #### Division
 /// When you devide a price by a percentage the price will be the price devided by the percentage.
 /// ```
 /// # use trading_utils::{Price, Percent};
-/// let division = Price(100.0) / Percent(0.05);
-/// assert_eq!(division, Price(2_000.0));
+/// let division = Price::from(100.0) / Percent::from(0.05);
+/// assert_eq!(division, Price::from(2_000.0));
 /// ```
-#[derive(Copy, Clone, Debug, PartialOrd, PartialEq)]
-pub struct Price(pub(crate) f64);
+#[derive(Copy, Clone, Debug, Default, PartialOrd, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(transparent))]
+pub struct Price(pub(crate) Value);
 
 impl Price {
     pub fn from_relative_price(base_price: Price, relative_price: RelativePrice) -> Self {
@@ -67,7 +68,7 @@ impl Price {
 
 impl MarketValue for Price {
     fn new(value: f64) -> Self {
-        Self(value)
+        Self(value_from_f64(value))
     }
 }
 
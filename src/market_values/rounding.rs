@@ -0,0 +1,139 @@
+use crate::{MarketValue, Value};
+
+/// How a market value is rounded
+///
+/// #### Variants:
+/// * __HalfUp__: to the nearest number, midpoints away from zero, `2.5 -> 3`, `-2.5 -> -3`
+/// * __HalfDown__: to the nearest number, midpoints towards zero, `2.5 -> 2`, `-2.5 -> -2`
+/// * __HalfEven__: to the nearest number, midpoints to the even neighbour (banker's rounding),
+///   `2.5 -> 2`, `3.5 -> 4`
+/// * __Up__: away from zero, `2.1 -> 3`, `-2.1 -> -3`
+/// * __Down__: towards zero, `2.9 -> 2`, `-2.9 -> -2`
+/// * __Ceiling__: towards positive infinity, `2.1 -> 3`, `-2.9 -> -2`
+/// * __Floor__: towards negative infinity, `2.9 -> 2`, `-2.1 -> -3`
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(rename_all = "snake_case"))]
+pub enum RoundingMode {
+    #[default]
+    HalfUp,
+    HalfDown,
+    HalfEven,
+    Up,
+    Down,
+    Ceiling,
+    Floor,
+}
+
+/// A number of decimal places and the rounding mode to get there
+///
+/// ```
+/// # use trading_utils::{Precision, Price, RoundingMode};
+/// let cents = Precision::new(2, RoundingMode::HalfEven);
+/// assert_eq!(cents.apply(Price::from(10.125)), Price::from(10.12));
+/// assert_eq!(cents.apply(Price::from(10.135)), Price::from(10.14));
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Precision {
+    pub decimals: u32,
+    pub rounding: RoundingMode,
+}
+
+impl Precision {
+    pub fn new(decimals: u32, rounding: RoundingMode) -> Self {
+        Self { decimals, rounding }
+    }
+
+    pub fn apply<V: MarketValue>(&self, value: V) -> V {
+        value.round_dp(self.decimals, self.rounding)
+    }
+}
+
+/// rounds the value to the given number of decimal places
+#[cfg(not(feature = "decimal"))]
+pub(crate) fn round_dp(value: Value, decimals: u32, mode: RoundingMode) -> Value {
+    if !value.is_finite() {
+        return value;
+    }
+    let scale = 10f64.powi(decimals as i32);
+
+    round_integer(value * scale, mode) / scale
+}
+
+/// rounds the value to the given number of decimal places
+#[cfg(feature = "decimal")]
+pub(crate) fn round_dp(value: Value, decimals: u32, mode: RoundingMode) -> Value {
+    value.round_dp_with_strategy(decimals, strategy(mode))
+}
+
+/// rounds the value to a multiple of the tick, a tick of zero leaves the value as it is
+#[cfg(not(feature = "decimal"))]
+pub(crate) fn round_to_tick(value: Value, tick: Value, mode: RoundingMode) -> Value {
+    if tick == 0.0 || !value.is_finite() || !tick.is_finite() {
+        return value;
+    }
+    let ticks = round_integer(value / tick, mode);
+
+    // ticks like 0.01 aren't exact floats, dividing by 100 gives the closer result
+    let inverse = 1.0 / tick;
+    if is_close(inverse, inverse.round()) {
+        ticks / inverse.round()
+    } else {
+        ticks * tick
+    }
+}
+
+/// rounds the value to a multiple of the tick, a tick of zero leaves the value as it is
+#[cfg(feature = "decimal")]
+pub(crate) fn round_to_tick(value: Value, tick: Value, mode: RoundingMode) -> Value {
+    if tick.is_zero() {
+        return value;
+    }
+
+    (value / tick).round_dp_with_strategy(0, strategy(mode)) * tick
+}
+
+#[cfg(feature = "decimal")]
+fn strategy(mode: RoundingMode) -> rust_decimal::RoundingStrategy {
+    use rust_decimal::RoundingStrategy;
+
+    match mode {
+        RoundingMode::HalfUp => RoundingStrategy::MidpointAwayFromZero,
+        RoundingMode::HalfDown => RoundingStrategy::MidpointTowardZero,
+        RoundingMode::HalfEven => RoundingStrategy::MidpointNearestEven,
+        RoundingMode::Up => RoundingStrategy::AwayFromZero,
+        RoundingMode::Down => RoundingStrategy::ToZero,
+        RoundingMode::Ceiling => RoundingStrategy::ToPositiveInfinity,
+        RoundingMode::Floor => RoundingStrategy::ToNegativeInfinity,
+    }
+}
+
+/// Rounds a float to an integer.
+/// `1.005 * 100.0` is `100.49999999999999`, so values that are within the float error of an
+/// integer or a midpoint are treated as if they were exactly on it.
+#[cfg(not(feature = "decimal"))]
+fn round_integer(value: f64, mode: RoundingMode) -> f64 {
+    let nearest = value.round();
+    if is_close(value, nearest) {
+        return nearest;
+    }
+    let truncated = value.trunc();
+    let midpoint = truncated + 0.5 * value.signum();
+    let value = if is_close(value, midpoint) { midpoint } else { value };
+
+    match mode {
+        RoundingMode::HalfUp => value.round(),
+        RoundingMode::HalfDown if value == midpoint => truncated,
+        RoundingMode::HalfDown => value.round(),
+        RoundingMode::HalfEven => value.round_ties_even(),
+        RoundingMode::Up => truncated + value.signum(),
+        RoundingMode::Down => truncated,
+        RoundingMode::Ceiling => value.ceil(),
+        RoundingMode::Floor => value.floor(),
+    }
+}
+
+#[cfg(not(feature = "decimal"))]
+fn is_close(a: f64, b: f64) -> bool {
+    (a - b).abs() <= 1e-9 * a.abs().max(1.0)
+}
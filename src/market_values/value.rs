@@ -0,0 +1,63 @@
+/// The number behind `Price`, `Points` and `Percent`
+///
+/// By default market values are floats. With the `decimal` feature they are fixed point decimals
+/// (`rust_decimal::Decimal`), so `0.1 + 0.2` is exactly `0.3`.
+/// Code that only uses the `MarketValue` functions and operators works with both.
+///
+/// Please note that a decimal division by zero panics instead of returning infinity.
+#[cfg(not(feature = "decimal"))]
+pub type Value = f64;
+
+/// The number behind `Price`, `Points` and `Percent`
+///
+/// By default market values are floats. With the `decimal` feature they are fixed point decimals
+/// (`rust_decimal::Decimal`), so `0.1 + 0.2` is exactly `0.3`.
+/// Code that only uses the `MarketValue` functions and operators works with both.
+///
+/// Please note that a decimal division by zero panics instead of returning infinity.
+#[cfg(feature = "decimal")]
+pub type Value = rust_decimal::Decimal;
+
+/// converts a float into a `Value`
+///
+/// Decimals get the shortest representation of the float, so `0.1` becomes exactly `0.1`.
+/// Floats that can't be represented as decimal (NaN, infinity and values beyond ±7.9e28)
+/// become zero.
+#[cfg(not(feature = "decimal"))]
+pub fn value_from_f64(float: f64) -> Value {
+    float
+}
+
+/// converts a float into a `Value`
+///
+/// Decimals get the shortest representation of the float, so `0.1` becomes exactly `0.1`.
+/// Floats that can't be represented as decimal (NaN, infinity and values beyond ±7.9e28)
+/// become zero.
+#[cfg(feature = "decimal")]
+pub fn value_from_f64(float: f64) -> Value {
+    use rust_decimal::prelude::FromPrimitive;
+
+    Value::from_f64(float).unwrap_or_default()
+}
+
+/// converts a `Value` into a float, decimals can lose precision
+#[cfg(not(feature = "decimal"))]
+pub fn value_to_f64(value: Value) -> f64 {
+    value
+}
+
+/// converts a `Value` into a float, decimals can lose precision
+#[cfg(feature = "decimal")]
+pub fn value_to_f64(value: Value) -> f64 {
+    use rust_decimal::prelude::ToPrimitive;
+
+    value.to_f64().unwrap_or_default()
+}
+
+/// converts a count into a `Value`, for example to build averages
+pub(crate) fn value_from_usize(count: usize) -> Value {
+    #[cfg(not(feature = "decimal"))]
+    return count as f64;
+    #[cfg(feature = "decimal")]
+    return Value::from(count);
+}
@@ -0,0 +1,98 @@
+use chrono::{DateTime, Duration, Local};
+
+use crate::{MarketValue, Percent, Price};
+
+/// A decline of the equity from a peak
+///
+/// #### Fields:
+/// * __depth__: The decline from the peak to the trough relative to the peak, `0.2` for 20%
+/// * __peak__: When the equity was at its peak
+/// * __trough__: When the equity was at its lowest
+/// * __recovery__: When the equity was back at the peak, `None` if it didn't recover
+/// * __duration__: The time from the peak to the recovery or the end of the series
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Drawdown {
+    pub depth: Percent,
+    pub peak: DateTime<Local>,
+    pub trough: DateTime<Local>,
+    pub recovery: Option<DateTime<Local>>,
+    pub duration: Duration,
+}
+
+impl Drawdown {
+    /// returns all drawdowns of an equity series in chronological order
+    /// ```
+    /// # use chrono::{Duration, Local};
+    /// # use trading_utils::{Drawdown, Percent, Price};
+    /// let start = Local::now();
+    /// let equity: Vec<_> = [100.0, 120.0, 90.0, 130.0, 117.0]
+    ///     .iter()
+    ///     .enumerate()
+    ///     .map(|(day, equity)| (start + Duration::days(day as i64), Price::from(*equity)))
+    ///     .collect();
+    ///
+    /// let drawdowns = Drawdown::all(&equity);
+    /// assert_eq!(drawdowns.len(), 2);
+    /// assert_eq!(drawdowns[0].depth, Percent::from(0.25));
+    /// assert_eq!(drawdowns[0].duration, Duration::days(2));
+    /// assert_eq!(drawdowns[1].recovery, None);
+    /// ```
+    pub fn all(equity: &[(DateTime<Local>, Price)]) -> Vec<Drawdown> {
+        let mut drawdowns = Vec::new();
+        let (mut peak_time, mut peak) = match equity.first() {
+            Some((time, value)) => (*time, value.as_f64()),
+            None => return drawdowns,
+        };
+        let mut current: Option<Drawdown> = None;
+
+        for (time, value) in equity.iter().skip(1) {
+            let value = value.as_f64();
+
+            if value >= peak {
+                if let Some(mut drawdown) = current.take() {
+                    drawdown.recovery = Some(*time);
+                    drawdown.duration = *time - drawdown.peak;
+                    drawdowns.push(drawdown);
+                }
+                peak_time = *time;
+                peak = value;
+                continue;
+            }
+
+            let depth = if peak > 0.0 { (peak - value) / peak } else { 0.0 };
+            let drawdown = current.get_or_insert(Drawdown {
+                depth: Percent::zero(),
+                peak: peak_time,
+                trough: *time,
+                recovery: None,
+                duration: Duration::zero(),
+            });
+            if depth > drawdown.depth.as_f64() {
+                drawdown.depth = Percent::from(depth);
+                drawdown.trough = *time;
+            }
+            drawdown.duration = *time - drawdown.peak;
+        }
+
+        drawdowns.extend(current);
+        drawdowns
+    }
+
+    /// returns the deepest drawdown of an equity series
+    pub fn max(equity: &[(DateTime<Local>, Price)]) -> Option<Drawdown> {
+        Self::all(equity)
+            .into_iter()
+            .fold(None, |max: Option<Drawdown>, drawdown| match max {
+                Some(max) if max.depth >= drawdown.depth => Some(max),
+                _ => Some(drawdown),
+            })
+    }
+
+    /// returns the longest drawdown of an equity series
+    pub fn longest(equity: &[(DateTime<Local>, Price)]) -> Option<Drawdown> {
+        Self::all(equity)
+            .into_iter()
+            .max_by_key(|drawdown| drawdown.duration)
+    }
+}
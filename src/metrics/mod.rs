@@ -0,0 +1,7 @@
+pub use drawdown::*;
+pub use performance_metrics::*;
+pub use trade_metrics::*;
+
+pub mod drawdown;
+pub mod performance_metrics;
+pub mod trade_metrics;
@@ -0,0 +1,131 @@
+use chrono::{DateTime, Duration, Local};
+
+use crate::{Drawdown, Error, ErrorKind, MarketValue, Percent, Price};
+
+const SECONDS_PER_YEAR: f64 = 365.25 * 24.0 * 60.0 * 60.0;
+
+/// The performance of an equity curve
+///
+/// The equity is sampled at the given times, the returns are the changes from one sample to the
+/// next. They are annualized with the average time between two samples, so daily samples give
+/// about 365 periods per year.
+///
+/// #### Fields:
+/// * __total_return__: The change from the first to the last equity
+/// * __annualized_return__: The compound annual growth rate (CAGR)
+/// * __volatility__: The annualized standard deviation of the returns
+/// * __sharpe__: The annualized excess return per volatility
+/// * __sortino__: The annualized excess return per downside deviation, only the returns below
+///   the risk free rate count as risk
+/// * __calmar__: The annualized return per maximal drawdown
+/// * __max_drawdown__: The deepest drawdown, `None` if the equity never declined
+/// * __longest_drawdown__: The longest time the equity was below a previous peak
+/// * __periods_per_year__: The samples per year used to annualize
+///
+/// Ratios with a denominator of zero are zero.
+/// ```
+/// # use chrono::{Duration, Local};
+/// # use trading_utils::{MarketValue, Percent, PerformanceMetrics, Price};
+/// let start = Local::now();
+/// let equity: Vec<_> = [100.0, 110.0, 99.0, 121.0]
+///     .iter()
+///     .enumerate()
+///     .map(|(year, equity)| (start + Duration::days(365 * year as i64), Price::from(*equity)))
+///     .collect();
+///
+/// let metrics = PerformanceMetrics::from_equity(&equity, Percent::zero()).unwrap();
+/// assert!((metrics.total_return.as_f64() - 0.21).abs() < 1e-9);
+/// assert!((metrics.annualized_return.as_f64() - 0.0656).abs() < 1e-3);
+/// assert!((metrics.max_drawdown.unwrap().depth.as_f64() - 0.1).abs() < 1e-9);
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct PerformanceMetrics {
+    pub total_return: Percent,
+    pub annualized_return: Percent,
+    pub volatility: Percent,
+    pub sharpe: f64,
+    pub sortino: f64,
+    pub calmar: f64,
+    pub max_drawdown: Option<Drawdown>,
+    pub longest_drawdown: Duration,
+    pub periods_per_year: f64,
+}
+
+impl PerformanceMetrics {
+    /// computes the metrics of an equity series
+    ///
+    /// The risk free rate is per year. Returns an error if there are less than two samples, if
+    /// the samples aren't ordered by time or if the first equity isn't positive.
+    pub fn from_equity(equity: &[(DateTime<Local>, Price)], risk_free_rate: Percent) -> Result<Self, Error<ErrorKind>> {
+        let invalid = |msg: &str| Err(Error::new(msg.to_string(), ErrorKind::InvalidParameter));
+
+        if equity.len() < 2 {
+            return invalid("The performance needs at least two samples of the equity");
+        }
+        if equity.windows(2).any(|pair| pair[1].0 <= pair[0].0) {
+            return invalid("The samples of the equity need to be ordered by time");
+        }
+        let (first_time, first) = (equity[0].0, equity[0].1.as_f64());
+        let (last_time, last) = (equity[equity.len() - 1].0, equity[equity.len() - 1].1.as_f64());
+        if first <= 0.0 {
+            return invalid("The first equity needs to be positive");
+        }
+
+        let years = (last_time - first_time).num_seconds() as f64 / SECONDS_PER_YEAR;
+        let periods_per_year = if years > 0.0 { (equity.len() - 1) as f64 / years } else { 0.0 };
+
+        let returns: Vec<f64> = equity
+            .windows(2)
+            .map(|pair| {
+                let before = pair[0].1.as_f64();
+                if before == 0.0 { 0.0 } else { pair[1].1.as_f64() / before - 1.0 }
+            })
+            .collect();
+        let risk_free = risk_free_rate.as_f64() / periods_per_year.max(1.0);
+        let mean = returns.iter().sum::<f64>() / returns.len() as f64;
+        let deviation = standard_deviation(&returns, mean);
+        let downside_deviation = (returns
+            .iter()
+            .map(|r| (r - risk_free).min(0.0).powi(2))
+            .sum::<f64>() / returns.len() as f64)
+            .sqrt();
+
+        let total_return = last / first - 1.0;
+        let annualized_return = if years > 0.0 && last > 0.0 {
+            (last / first).powf(1.0 / years) - 1.0
+        } else {
+            total_return
+        };
+        let annualization = periods_per_year.sqrt();
+        let max_drawdown = Drawdown::max(equity);
+
+        Ok(Self {
+            total_return: Percent::from(total_return),
+            annualized_return: Percent::from(annualized_return),
+            volatility: Percent::from(deviation * annualization),
+            sharpe: ratio(mean - risk_free, deviation) * annualization,
+            sortino: ratio(mean - risk_free, downside_deviation) * annualization,
+            calmar: ratio(annualized_return, max_drawdown.map_or(0.0, |drawdown| drawdown.depth.as_f64())),
+            max_drawdown,
+            longest_drawdown: Drawdown::longest(equity).map_or_else(Duration::zero, |drawdown| drawdown.duration),
+            periods_per_year,
+        })
+    }
+}
+
+/// the sample standard deviation
+pub(crate) fn standard_deviation(values: &[f64], mean: f64) -> f64 {
+    if values.len() < 2 {
+        return 0.0;
+    }
+    let variance = values
+        .iter()
+        .map(|value| (value - mean).powi(2))
+        .sum::<f64>() / (values.len() - 1) as f64;
+    variance.sqrt()
+}
+
+/// the ratio or zero, if the denominator is zero
+pub(crate) fn ratio(numerator: f64, denominator: f64) -> f64 {
+    if denominator == 0.0 { 0.0 } else { numerator / denominator }
+}
@@ -0,0 +1,110 @@
+use chrono::{DateTime, Local};
+
+use crate::{MarketValue, Percent, Position, Price};
+use crate::metrics::performance_metrics::ratio;
+
+/// The statistics of closed positions
+///
+/// The profit or loss of a position is its realized profit or loss minus its fees. Positions
+/// that are still open are ignored. All positions should be in the same currency.
+///
+/// #### Fields:
+/// * __trades__, __wins__, __losses__: The closed positions, the ones with a profit and the ones
+///   with a loss. Positions that broke even are neither.
+/// * __win_rate__: The share of the wins in the trades
+/// * __gross_profit__, __gross_loss__: The sum of the profits and of the losses, the loss is
+///   positive
+/// * __average_win__, __average_loss__: The average profit of the wins and the average loss of
+///   the losses, the loss is positive
+/// * __win_loss_ratio__: The average win per average loss
+/// * __profit_factor__: The gross profit per gross loss
+/// * __expectancy__: The average profit or loss per trade
+/// * __exposure_time__: The share of the period in which at least one position was open
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TradeMetrics {
+    pub trades: usize,
+    pub wins: usize,
+    pub losses: usize,
+    pub win_rate: Percent,
+    pub gross_profit: Price,
+    pub gross_loss: Price,
+    pub average_win: Price,
+    pub average_loss: Price,
+    pub win_loss_ratio: f64,
+    pub profit_factor: f64,
+    pub expectancy: Price,
+    pub exposure_time: Percent,
+}
+
+impl TradeMetrics {
+    /// computes the metrics of the closed positions
+    ///
+    /// The exposure time is relative to the given period, by default the time from the first
+    /// opened to the last closed position.
+    pub fn from_positions(positions: &[Position], period: Option<(DateTime<Local>, DateTime<Local>)>) -> Self {
+        let closed: Vec<(&Position, DateTime<Local>, f64)> = positions
+            .iter()
+            .filter_map(|position| {
+                let closed = position.closed()?;
+                Some((position, closed, (position.realized() - position.fees()).as_f64()))
+            })
+            .collect();
+        if closed.is_empty() {
+            return Self::default();
+        }
+
+        let profits: Vec<f64> = closed.iter().map(|(_, _, pnl)| *pnl).filter(|pnl| *pnl > 0.0).collect();
+        let losses: Vec<f64> = closed.iter().map(|(_, _, pnl)| -*pnl).filter(|loss| *loss > 0.0).collect();
+        let gross_profit: f64 = profits.iter().sum();
+        let gross_loss: f64 = losses.iter().sum();
+        let average_win = ratio(gross_profit, profits.len() as f64);
+        let average_loss = ratio(gross_loss, losses.len() as f64);
+
+        let mut intervals: Vec<(DateTime<Local>, DateTime<Local>)> = closed
+            .iter()
+            .map(|(position, closed, _)| (position.bought, *closed))
+            .collect();
+        intervals.sort_by_key(|(opened, _)| *opened);
+        let (start, end) = period.unwrap_or_else(|| (
+            intervals[0].0,
+            intervals.iter().map(|(_, closed)| *closed).max().unwrap_or(intervals[0].1),
+        ));
+
+        Self {
+            trades: closed.len(),
+            wins: profits.len(),
+            losses: losses.len(),
+            win_rate: Percent::from(profits.len() as f64 / closed.len() as f64),
+            gross_profit: Price::from(gross_profit),
+            gross_loss: Price::from(gross_loss),
+            average_win: Price::from(average_win),
+            average_loss: Price::from(average_loss),
+            win_loss_ratio: ratio(average_win, average_loss),
+            profit_factor: ratio(gross_profit, gross_loss),
+            expectancy: Price::from((gross_profit - gross_loss) / closed.len() as f64),
+            exposure_time: Percent::from(ratio(
+                exposed_seconds(&intervals, start, end),
+                (end - start).num_seconds() as f64,
+            )),
+        }
+    }
+}
+
+/// the seconds within the period that are covered by at least one interval,
+/// the intervals are ordered by their start
+fn exposed_seconds(intervals: &[(DateTime<Local>, DateTime<Local>)], start: DateTime<Local>, end: DateTime<Local>) -> f64 {
+    let mut exposed = 0;
+    let mut covered_until = start;
+
+    for (opened, closed) in intervals {
+        let opened = (*opened).max(covered_until);
+        let closed = (*closed).min(end);
+        if closed > opened {
+            exposed += (closed - opened).num_seconds();
+            covered_until = closed;
+        }
+    }
+
+    exposed as f64
+}
@@ -3,16 +3,18 @@ use std::hash::{Hash, Hasher};
 
 use chrono::{DateTime, Duration, Local};
 
-use crate::{PositionType, Price, RelativePrice, StockExchange};
+use crate::{Bracket, Derivative, PositionType, Price, RelativePrice, StockExchange};
 
 /// for a documentation of the order types:  https://www.investopedia.com/investing/basics-trading-stock-know-your-orders/
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(tag = "type", content = "value", rename_all = "snake_case"))]
 pub enum Order {
     Single(OrderData),
     OneCancelsTheOther(Vec<OrderData>),
     AllOrNone(Vec<OrderData>),
     ImmediateOrCancel(OrderData),
     FillOrKill(Vec<OrderData>),
+    Bracket(Bracket),
 }
 
 impl Order {
@@ -33,6 +35,25 @@ impl Order {
                 .iter()
                 .find(|order_data| order_data.id == id)
                 .is_some(),
+            Bracket(bracket) => bracket
+                .data()
+                .iter()
+                .any(|order_data| order_data.id == id),
+        }
+    }
+
+    /// returns the data of all orders
+    ///
+    /// That's the entry of a bracket, or its take profit and stop loss once it's active.
+    pub fn data(&self) -> &[OrderData] {
+        use Order::*;
+        match self {
+            Single(order_data) => std::slice::from_ref(order_data),
+            OneCancelsTheOther(data) => data,
+            AllOrNone(data) => data,
+            ImmediateOrCancel(order_data) => std::slice::from_ref(order_data),
+            FillOrKill(data) => data,
+            Bracket(bracket) => bracket.data(),
         }
     }
 
@@ -62,6 +83,10 @@ impl Order {
             FillOrKill(data) => data
                 .iter()
                 .find(|order_data| order_data.id == id),
+            Bracket(bracket) => bracket
+                .data()
+                .iter()
+                .find(|order_data| order_data.id == id),
         }
     }
 
@@ -91,6 +116,19 @@ impl Order {
             FillOrKill(data) => data
                 .iter_mut()
                 .find(|order_data| order_data.id == id),
+            Bracket(bracket) => bracket
+                .data_mut()
+                .iter_mut()
+                .find(|order_data| order_data.id == id),
+        }
+    }
+
+    /// returns true if the order opens positions,
+    /// the take profit and the stop loss of an active bracket close one
+    pub fn opens_positions(&self) -> bool {
+        match self {
+            Order::Bracket(bracket) => !bracket.is_active(),
+            _ => true,
         }
     }
 }
@@ -99,16 +137,21 @@ impl Order {
 ///
 /// #### Fields:
 /// * __id__: A unique id that makes it easy to identify an order. Note: Since many brokers provide
-/// strings instead of u64 the id is always the hash of the provided raw_id.
+///   strings instead of u64 the id is always the hash of the provided raw_id.
 /// * __raw_id__: A unique id that makes it easy to identify an order. This id is usually provided
-/// by the broker
+///   by the broker
+/// * __derivative__: The derivative that is traded by the order.
 /// * __stock_exchange__: The stock exchange on which the order will be executed.
+///
 /// todo
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(from = "OrderDataFields"))]
 pub struct OrderData {
+    #[cfg_attr(feature = "serde", serde(serialize_with = "crate::serde_util::serialize_id"))]
     id: u64,
     raw_id: String,
 
+    derivative: Derivative,
     stock_exchange: StockExchange,
     pieces: u64,
 
@@ -125,6 +168,7 @@ pub struct OrderData {
 impl OrderData {
     pub fn id(&self) -> u64 { self.id }
     pub fn raw_id(&self) -> &String { &self.raw_id }
+    pub fn derivative(&self) -> &Derivative { &self.derivative }
     pub fn stock_exchange(&self) -> StockExchange { self.stock_exchange }
     pub fn pieces(&self) -> u64 { self.pieces }
     pub fn order_type(&self) -> &OrderType { &self.order_type }
@@ -134,13 +178,17 @@ impl OrderData {
     pub fn moment(&self) -> &OrderMoment { &self.moment }
     pub fn validity(&self) -> &OrderValidity { &self.validity }
 
+    pub fn update_pieces(&mut self, pieces: u64) { self.pieces = pieces }
+    pub fn update_order_type(&mut self, order_type: OrderType) { self.order_type = order_type }
     pub fn update_take_profit(&mut self, take_profit: TakeProfit) { self.take_profit = take_profit }
     pub fn update_stop_loss(&mut self, stop_loss: StopLoss) { self.stop_loss = stop_loss }
     pub fn update_moment(&mut self, order_moment: OrderMoment) { self.moment = order_moment }
     pub fn update_validity(&mut self, order_validity: OrderValidity) { self.validity = order_validity }
 
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         raw_id: String,
+        derivative: Derivative,
         stock_exchange: StockExchange,
         pieces: u64,
         order_type: OrderType,
@@ -157,6 +205,7 @@ impl OrderData {
         Self {
             id,
             raw_id,
+            derivative,
             stock_exchange,
             pieces,
             order_type,
@@ -169,8 +218,45 @@ impl OrderData {
     }
 }
 
+/// The serialized fields of `OrderData`
+///
+/// The id is not read, since the hash of the raw id isn't stable between versions of rustc.
+/// It's computed again by `OrderData::new`.
+#[cfg(feature = "serde")]
+#[derive(serde::Deserialize)]
+struct OrderDataFields {
+    raw_id: String,
+    derivative: Derivative,
+    stock_exchange: StockExchange,
+    pieces: u64,
+    order_type: OrderType,
+    position_type: PositionType,
+    take_profit: TakeProfit,
+    stop_loss: StopLoss,
+    moment: OrderMoment,
+    validity: OrderValidity,
+}
+
+#[cfg(feature = "serde")]
+impl From<OrderDataFields> for OrderData {
+    fn from(fields: OrderDataFields) -> Self {
+        OrderData::new(
+            fields.raw_id,
+            fields.derivative,
+            fields.stock_exchange,
+            fields.pieces,
+            fields.order_type,
+            fields.position_type,
+            fields.take_profit,
+            fields.stop_loss,
+            fields.moment,
+            fields.validity,
+        )
+    }
+}
 
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(tag = "type", content = "value", rename_all = "snake_case"))]
 pub enum OrderType {
     MarketOrder,
     LimitOrder(Price),
@@ -178,13 +264,27 @@ pub enum OrderType {
 }
 
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(tag = "type", content = "value", rename_all = "snake_case"))]
 pub enum TakeProfit {
     Absolute(Price),
     Relative(RelativePrice),
     None,
 }
 
+impl TakeProfit {
+    /// returns the price of the take profit, relative take profits are relative to the base
+    /// price, usually the fill price
+    pub fn level(&self, base_price: Price) -> Option<Price> {
+        match self {
+            TakeProfit::Absolute(price) => Some(*price),
+            TakeProfit::Relative(relative_price) => Some(Price::from_relative_price(base_price, *relative_price)),
+            TakeProfit::None => None,
+        }
+    }
+}
+
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(tag = "type", content = "value", rename_all = "snake_case"))]
 pub enum StopLoss {
     Absolute(Price),
     Relative(RelativePrice),
@@ -192,7 +292,21 @@ pub enum StopLoss {
     None,
 }
 
+impl StopLoss {
+    /// returns the price of the stop loss, relative and trailing stop losses are relative to the
+    /// base price, usually the fill price
+    pub fn level(&self, base_price: Price) -> Option<Price> {
+        match self {
+            StopLoss::Absolute(price) => Some(*price),
+            StopLoss::Relative(relative_price) | StopLoss::Trailing(relative_price) =>
+                Some(Price::from_relative_price(base_price, *relative_price)),
+            StopLoss::None => None,
+        }
+    }
+}
+
 #[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(tag = "type", content = "value", rename_all = "snake_case"))]
 pub enum OrderMoment {
     Instant,
     Planed(DateTime<Local>),
@@ -213,6 +327,7 @@ impl OrderMoment {
 }
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(rename_all = "snake_case"))]
 pub enum OrderValidity {
     OneDay,
     OneWeek,
@@ -229,12 +344,20 @@ impl OrderValidity {
             OneWeek => Duration::weeks(1),
             OneMonth => Duration::days(30),
             OneYear => Duration::days(365),
-            Forever => Duration::max_value()
+            Forever => Duration::MAX
+        }
+    }
+
+    /// the time an order that was placed at the start expires, None if it never expires
+    pub fn expiry(&self, start: DateTime<Local>) -> Option<DateTime<Local>> {
+        match self {
+            OrderValidity::Forever => None,
+            validity => start.checked_add_signed(validity.as_duration()),
         }
     }
 
+    /// returns true if an order that was placed at the start didn't expire yet
     pub fn is_valid(&self, start: &DateTime<Local>) -> bool {
-        let duration = self.as_duration();
-        *start + duration < Local::now()
+        self.expiry(*start).is_none_or(|expiry| Local::now() < expiry)
     }
 }
@@ -0,0 +1,60 @@
+use crate::{Derivative, Percent, Price, StockExchange};
+
+/// All open positions of a derivative across the deposits of a `Portfolio`
+///
+/// #### Fields:
+/// * __derivative__: The held derivative
+/// * __exchange__: The stock exchange it's listed on or was bought at
+/// * __long_pieces__, __short_pieces__: The open pieces of long and short positions
+/// * __price__: The price the positions are valued at, in the currency of the derivative
+/// * __net__: The market value of the long minus the short positions, in the base currency
+/// * __gross__: The market value of the long plus the short positions, in the base currency
+/// * __unrealized__: The unrealized profit or loss, in the base currency
+/// * __deposits__: The ids of the deposits that hold the positions
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Holding {
+    pub derivative: Derivative,
+    pub exchange: Option<StockExchange>,
+    pub long_pieces: u64,
+    pub short_pieces: u64,
+    pub price: Price,
+    pub net: Price,
+    pub gross: Price,
+    pub unrealized: Price,
+    pub deposits: Vec<u64>,
+}
+
+/// The exposure of a portfolio to a derivative, a currency or a stock exchange
+///
+/// #### Fields:
+/// * __net__: The market value of the long minus the short positions, in the base currency
+/// * __gross__: The market value of the long plus the short positions, in the base currency
+/// * __weight__: The gross exposure relative to the equity of the portfolio
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Exposure {
+    pub net: Price,
+    pub gross: Price,
+    pub weight: Percent,
+}
+
+/// How concentrated the gross exposure of a portfolio is
+///
+/// All metrics use the shares of the holdings in the gross exposure, which add up to 100%.
+///
+/// #### Fields:
+/// * __largest__: The share of the largest holding
+/// * __top_five__: The share of the five largest holdings
+/// * __herfindahl__: The Herfindahl-Hirschman index, the sum of the squared shares. It's 1 for a
+///   single holding and `1 / n` for `n` equally weighted holdings
+/// * __effective_holdings__: `1 / herfindahl`, the number of equally weighted holdings with the
+///   same concentration
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Concentration {
+    pub largest: Percent,
+    pub top_five: Percent,
+    pub herfindahl: f64,
+    pub effective_holdings: f64,
+}
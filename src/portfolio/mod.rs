@@ -0,0 +1,330 @@
+use std::cmp::Ordering;
+use std::collections::HashMap;
+
+pub use holding::*;
+
+use crate::{Currency, Deposit, Direction, Error, ErrorKind, ExchangeRates, MarketValue, Order, OrderType, Percent, Position, Price, StockExchange, Transaction};
+
+pub mod holding;
+
+/// A consolidated view of several deposits
+///
+/// The portfolio is computed from the transactions, orders and positions of the deposits, so
+/// it's computed again whenever they change.
+/// * __cash__: The sum of the transactions of every deposit. Deposits without transactions
+///   count with their balance.
+/// * __reserved__: The value of the open orders that buy, which is no longer available
+/// * __holdings__: The open positions grouped by the symbol of their derivative. They are valued
+///   at the given prices, positions without a price at their entry price, see `unpriced`.
+///
+/// All values are converted to the base currency of the exchange rates.
+/// ```
+/// # use std::collections::HashMap;
+/// # use chrono::Local;
+/// # use trading_utils::*;
+/// let mut deposit = Deposit::empty("deposit".to_string(), Currency::EUR);
+/// deposit.update_balance(Price::from(1_000.0));
+///
+/// let share = Derivative::new("SAP".to_string(), DerivativeKind::Stock, Currency::EUR);
+/// let order = Order::Single(OrderData::new(
+///     "order-1".to_string(), share.clone(), StockExchange::LSExchange, 10,
+///     OrderType::LimitOrder(Price::from(100.0)), PositionType::LongCall,
+///     TakeProfit::None, StopLoss::None, OrderMoment::Instant, OrderValidity::OneDay,
+/// ));
+/// deposit.update_positions(vec![Position::from_order(order, Local::now(), Price::from(100.0)).unwrap()]);
+///
+/// let prices: HashMap<String, Price> = vec![("SAP".to_string(), Price::from(150.0))].into_iter().collect();
+/// let portfolio = Portfolio::compute(&[deposit], &prices, &ExchangeRates::new(Currency::EUR)).unwrap();
+///
+/// assert_eq!(portfolio.equity(), Price::from(2_500.0));
+/// assert_eq!(portfolio.unrealized(), Price::from(500.0));
+/// assert_eq!(portfolio.cash_weight(), Percent::from(0.4));
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Portfolio {
+    base_currency: Currency,
+    cash: Price,
+    cash_by_currency: Vec<(Currency, Price)>,
+    reserved: Price,
+    holdings: Vec<Holding>,
+    unpriced: Vec<String>,
+}
+
+impl Portfolio {
+    /// aggregates the deposits
+    ///
+    /// The prices are the current prices per symbol in the currency of the derivative.
+    /// Returns an error if an exchange rate is missing.
+    pub fn compute<'a>(
+        deposits: impl IntoIterator<Item=&'a Deposit>,
+        prices: &HashMap<String, Price>,
+        rates: &ExchangeRates,
+    ) -> Result<Self, Error<ErrorKind>> {
+        let mut portfolio = Self {
+            base_currency: rates.base(),
+            cash: Price::zero(),
+            cash_by_currency: Vec::new(),
+            reserved: Price::zero(),
+            holdings: Vec::new(),
+            unpriced: Vec::new(),
+        };
+
+        for deposit in deposits {
+            let balances = if deposit.transactions().is_empty() {
+                vec![(deposit.currency(), deposit.balance())]
+            } else {
+                Transaction::cash_balances(deposit.transactions())
+            };
+            for (currency, balance) in balances {
+                portfolio.add_cash(currency, balance, rates)?;
+            }
+
+            for order in deposit.orders() {
+                portfolio.reserved += reserved(order, prices, rates)?;
+            }
+
+            for position in deposit.positions() {
+                if position.is_open() && position.pieces() > 0 {
+                    portfolio.add_position(deposit.id(), position, prices, rates)?;
+                }
+            }
+        }
+
+        portfolio
+            .holdings
+            .sort_by(|a, b| by_gross(&a.gross, &b.gross));
+
+        Ok(portfolio)
+    }
+
+    pub fn base_currency(&self) -> Currency { self.base_currency }
+    /// the cash of all deposits in the base currency
+    pub fn cash(&self) -> Price { self.cash }
+    /// the cash of all deposits per currency, not converted
+    pub fn cash_by_currency(&self) -> &[(Currency, Price)] { &self.cash_by_currency }
+    /// the value of the open orders that buy
+    pub fn reserved(&self) -> Price { self.reserved }
+    /// the holdings ordered by their gross exposure, the largest first
+    pub fn holdings(&self) -> &[Holding] { &self.holdings }
+    /// the symbols that were valued at the entry price, since there was no price for them
+    pub fn unpriced(&self) -> &[String] { &self.unpriced }
+
+    /// the cash that isn't reserved by open orders
+    pub fn available_cash(&self) -> Price {
+        self.cash - self.reserved
+    }
+
+    /// the net market value of all holdings
+    pub fn invested(&self) -> Price {
+        self.holdings
+            .iter()
+            .fold(Price::zero(), |sum, holding| sum + holding.net)
+    }
+
+    /// the gross market value of all holdings
+    pub fn gross_exposure(&self) -> Price {
+        self.holdings
+            .iter()
+            .fold(Price::zero(), |sum, holding| sum + holding.gross)
+    }
+
+    /// the cash plus the net market value of all holdings
+    pub fn equity(&self) -> Price {
+        self.cash + self.invested()
+    }
+
+    /// the unrealized profit or loss of all holdings
+    pub fn unrealized(&self) -> Price {
+        self.holdings
+            .iter()
+            .fold(Price::zero(), |sum, holding| sum + holding.unrealized)
+    }
+
+    /// the share of the cash in the equity
+    pub fn cash_weight(&self) -> Percent {
+        self.weight(self.cash)
+    }
+
+    /// the cash per currency relative to the equity
+    pub fn cash_weights(&self, rates: &ExchangeRates) -> Result<Vec<(Currency, Percent)>, Error<ErrorKind>> {
+        self.cash_by_currency
+            .iter()
+            .map(|(currency, cash)| Ok((*currency, self.weight(rates.to_base(*cash, *currency)?))))
+            .collect()
+    }
+
+    /// the exposure per symbol, the largest first
+    pub fn exposure_by_derivative(&self) -> Vec<(String, Exposure)> {
+        self.exposures(|holding| holding.derivative.symbol.clone())
+    }
+
+    /// the exposure per currency of the derivatives, the largest first
+    pub fn exposure_by_currency(&self) -> Vec<(Currency, Exposure)> {
+        self.exposures(|holding| holding.derivative.currency)
+    }
+
+    /// the exposure per stock exchange, the largest first
+    pub fn exposure_by_exchange(&self) -> Vec<(Option<StockExchange>, Exposure)> {
+        self.exposures(|holding| holding.exchange)
+    }
+
+    /// how concentrated the gross exposure is
+    pub fn concentration(&self) -> Concentration {
+        let gross = self.gross_exposure().as_f64();
+        if gross <= 0.0 {
+            return Concentration::default();
+        }
+        let shares: Vec<f64> = self.holdings
+            .iter()
+            .map(|holding| holding.gross.as_f64() / gross)
+            .collect();
+        let herfindahl: f64 = shares.iter().map(|share| share * share).sum();
+
+        Concentration {
+            largest: Percent::from(shares.first().copied().unwrap_or(0.0)),
+            top_five: Percent::from(shares.iter().take(5).sum::<f64>()),
+            herfindahl,
+            effective_holdings: 1.0 / herfindahl,
+        }
+    }
+
+    fn exposures<K: PartialEq>(&self, key: impl Fn(&Holding) -> K) -> Vec<(K, Exposure)> {
+        let mut exposures: Vec<(K, Exposure)> = Vec::new();
+
+        for holding in &self.holdings {
+            let key = key(holding);
+            let index = match exposures.iter().position(|(known, _)| *known == key) {
+                Some(index) => index,
+                None => {
+                    exposures.push((key, Exposure::default()));
+                    exposures.len() - 1
+                }
+            };
+            let exposure = &mut exposures[index].1;
+            exposure.net += holding.net;
+            exposure.gross += holding.gross;
+        }
+
+        for (_, exposure) in exposures.iter_mut() {
+            exposure.weight = self.weight(exposure.gross);
+        }
+        exposures.sort_by(|(_, a), (_, b)| by_gross(&a.gross, &b.gross));
+
+        exposures
+    }
+
+    fn weight(&self, value: Price) -> Percent {
+        let equity = self.equity().as_f64();
+        if equity <= 0.0 {
+            Percent::zero()
+        } else {
+            Percent::from(value.as_f64() / equity)
+        }
+    }
+
+    fn add_cash(&mut self, currency: Currency, amount: Price, rates: &ExchangeRates) -> Result<(), Error<ErrorKind>> {
+        self.cash += rates.to_base(amount, currency)?;
+        match self.cash_by_currency.iter_mut().find(|(known, _)| *known == currency) {
+            Some((_, cash)) => *cash += amount,
+            None => self.cash_by_currency.push((currency, amount)),
+        }
+        Ok(())
+    }
+
+    fn add_position(&mut self, deposit: u64, position: &Position, prices: &HashMap<String, Price>, rates: &ExchangeRates) -> Result<(), Error<ErrorKind>> {
+        let derivative = position.derivative();
+        let price = match prices.get(&derivative.symbol) {
+            Some(price) => *price,
+            None => {
+                if !self.unpriced.contains(&derivative.symbol) {
+                    self.unpriced.push(derivative.symbol.clone());
+                }
+                position.entry_price()
+            }
+        };
+        let market_value = rates.to_base(position.market_value(price), derivative.currency)?;
+        let unrealized = rates.to_base(position.unrealized(price), derivative.currency)?;
+
+        let index = match self.holdings.iter().position(|holding| holding.derivative.symbol == derivative.symbol) {
+            Some(index) => index,
+            None => {
+                self.holdings.push(Holding {
+                    derivative: derivative.clone(),
+                    exchange: derivative.exchange.or_else(|| {
+                        position.order
+                                .data()
+                                .first()
+                                .map(|order_data| order_data.stock_exchange())
+                    }),
+                    long_pieces: 0,
+                    short_pieces: 0,
+                    price,
+                    net: Price::zero(),
+                    gross: Price::zero(),
+                    unrealized: Price::zero(),
+                    deposits: Vec::new(),
+                });
+                self.holdings.len() - 1
+            }
+        };
+        let holding = &mut self.holdings[index];
+
+        match position.direction() {
+            Direction::Long => {
+                holding.long_pieces += position.pieces();
+                holding.net += market_value;
+            }
+            Direction::Short => {
+                holding.short_pieces += position.pieces();
+                holding.net -= market_value;
+            }
+        }
+        holding.gross += Price::from(market_value.abs());
+        holding.unrealized += unrealized;
+        if !holding.deposits.contains(&deposit) {
+            holding.deposits.push(deposit);
+        }
+
+        Ok(())
+    }
+}
+
+/// The value of the order data that buy, in the base currency.
+/// Only the largest order of a one cancels the other order can be filled.
+/// Market orders are valued at the current price, if there's one.
+fn reserved(order: &Order, prices: &HashMap<String, Price>, rates: &ExchangeRates) -> Result<Price, Error<ErrorKind>> {
+    if !order.opens_positions() {
+        return Ok(Price::zero());
+    }
+    let mut values = Vec::new();
+
+    for order_data in order.data() {
+        if order_data.position_type().direction() != Direction::Long {
+            continue;
+        }
+        let derivative = order_data.derivative();
+        let price = match order_data.order_type() {
+            OrderType::LimitOrder(price) | OrderType::StopOrder(price) => Some(*price),
+            OrderType::MarketOrder => prices.get(&derivative.symbol).copied(),
+        };
+        if let Some(price) = price {
+            values.push(rates.to_base(derivative.notional(price, order_data.pieces()), derivative.currency)?);
+        }
+    }
+
+    Ok(match order {
+        Order::OneCancelsTheOther(_) => values
+            .into_iter()
+            .max_by(|a, b| a.partial_cmp(b).unwrap_or(Ordering::Equal))
+            .unwrap_or_else(Price::zero),
+        _ => values
+            .into_iter()
+            .fold(Price::zero(), |sum, value| sum + value),
+    })
+}
+
+/// orders by gross exposure, the largest first
+fn by_gross(a: &Price, b: &Price) -> Ordering {
+    b.partial_cmp(a).unwrap_or(Ordering::Equal)
+}
@@ -1,23 +1,294 @@
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+
 use chrono::{DateTime, Local};
 
-use crate::Order;
+use crate::{Derivative, Error, MarketValue, Order, Price, TradingErrorKind};
 
+/// A position in a derivative
+///
+/// A position is opened by a fill of an order. Further fills of the same direction scale in and
+/// move the average entry price, opposite fills scale out and realize profits or losses at the
+/// average entry price. The position is closed when no pieces are left.
+///
+/// All profits and losses are in the currency of the derivative and include its multiplier.
+/// Long positions profit from rising prices of the derivative, short positions from falling
+/// ones, see `PositionType::direction`.
+///
+/// #### Fields:
+/// * __id__: The id of the position, usually provided by the broker
+/// * __bought__: The time of the first fill
+/// * __order__: The order that opened the position
+///
+/// ```
+/// # use chrono::Local;
+/// # use trading_utils::*;
+/// let apple = Derivative::new("AAPL".to_string(), DerivativeKind::Stock, Currency::USD);
+/// let now = Local::now();
+/// let order = Order::Single(OrderData::new(
+///     "order-1".to_string(), apple.clone(), StockExchange::NASDAQ, 10,
+///     OrderType::LimitOrder(Price::from(100.0)), PositionType::LongCall,
+///     TakeProfit::None, StopLoss::None, OrderMoment::Instant, OrderValidity::OneDay,
+/// ));
+///
+/// let mut position = Position::from_order(order, now, Price::from(100.0)).unwrap();
+/// position.scale_in(Fill::new(now, 10, Price::from(110.0)).with_fee(Price::from(1.0))).unwrap();
+/// assert_eq!(position.entry_price(), Price::from(105.0));
+///
+/// let realized = position.scale_out(Fill::new(now, 5, Price::from(115.0))).unwrap();
+/// assert_eq!(realized, Price::from(50.0));
+/// assert_eq!(position.unrealized(Price::from(115.0)), Price::from(150.0));
+/// assert_eq!(position.pnl(Price::from(115.0)), Price::from(199.0));
+///
+/// position.close(now, Price::from(100.0), Price::zero()).unwrap();
+/// assert!(!position.is_open());
+/// assert_eq!(position.realized(), Price::from(-25.0));
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Position {
     pub id: String,
     pub bought: DateTime<Local>,
     pub order: Order,
+
+    derivative: Derivative,
+    position_type: PositionType,
+    pieces: u64,
+    entry_price: Price,
+    realized: Price,
+    fees: Price,
+    fills: Vec<Fill>,
+    closed: Option<DateTime<Local>>,
 }
 
-impl From<Order> for Position {
-    fn from(_: Order) -> Self {
-        unimplemented!()
+impl Position {
+    /// opens a position with its first fill
+    pub fn new(id: String, derivative: Derivative, position_type: PositionType, order: Order, fill: Fill) -> Self {
+        Self {
+            id,
+            bought: fill.time,
+            order,
+            derivative,
+            position_type,
+            pieces: fill.pieces,
+            entry_price: fill.price,
+            realized: Price::zero(),
+            fees: fill.fee,
+            fills: vec![fill],
+            closed: None,
+        }
+    }
+
+    /// opens a position from an order that was filled at the given time and price
+    ///
+    /// The id of the position is the raw id of the filled order data. All order data count as
+    /// filled, except for `OneCancelsTheOther` orders where only the first one does, and
+    /// brackets where only the entry does.
+    /// Returns an error if the order has no order data.
+    pub fn from_order(order: Order, time: DateTime<Local>, price: Price) -> Result<Self, Error<TradingErrorKind>> {
+        let filled = match &order {
+            Order::Bracket(bracket) => std::slice::from_ref(bracket.entry()),
+            Order::OneCancelsTheOther(data) => &data[..data.len().min(1)],
+            order => order.data(),
+        };
+        let first = match filled.first() {
+            Some(first) => first,
+            None => return Err(Error::new(
+                "A position can only be opened by an order with order data".to_string(),
+                TradingErrorKind::InvalidOrder,
+            )),
+        };
+
+        let pieces = filled
+            .iter()
+            .map(|order_data| order_data.pieces())
+            .sum();
+        let (id, derivative, position_type) = (first.raw_id().clone(), first.derivative().clone(), first.position_type());
+
+        Ok(Self::new(id, derivative, position_type, order, Fill::new(time, pieces, price)))
+    }
+
+    /// the id used by brokers to identify the position
+    ///
+    /// Since many brokers provide strings instead of u64 this is the hash of the provided id.
+    pub fn hashed_id(&self) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        self.id.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    pub fn derivative(&self) -> &Derivative { &self.derivative }
+    pub fn position_type(&self) -> PositionType { self.position_type }
+    pub fn direction(&self) -> Direction { self.position_type.direction() }
+    /// the open pieces
+    pub fn pieces(&self) -> u64 { self.pieces }
+    /// the average price of the open pieces
+    pub fn entry_price(&self) -> Price { self.entry_price }
+    /// the profit or loss of the closed pieces, without fees
+    pub fn realized(&self) -> Price { self.realized }
+    pub fn fees(&self) -> Price { self.fees }
+    pub fn fills(&self) -> &[Fill] { &self.fills }
+    /// the time the last pieces were closed
+    pub fn closed(&self) -> Option<DateTime<Local>> { self.closed }
+    pub fn is_open(&self) -> bool { self.closed.is_none() }
+
+    /// adds pieces to the position, the entry price becomes the average of all open pieces
+    pub fn scale_in(&mut self, fill: Fill) -> Result<(), Error<TradingErrorKind>> {
+        self.check_open()?;
+
+        let pieces = self.pieces + fill.pieces;
+        if pieces > 0 {
+            self.entry_price = (self.entry_price * self.pieces as f64 + fill.price * fill.pieces as f64) / pieces as f64;
+        }
+        self.pieces = pieces;
+        self.fees += fill.fee;
+        self.fills.push(fill);
+
+        Ok(())
+    }
+
+    /// removes pieces from the position and returns the realized profit or loss of them
+    ///
+    /// The position is closed when no pieces are left.
+    pub fn scale_out(&mut self, fill: Fill) -> Result<Price, Error<TradingErrorKind>> {
+        self.check_open()?;
+        if fill.pieces > self.pieces {
+            return Err(Error::new(
+                format!("Can't close {} pieces of the position `{}`, only {} are open", fill.pieces, self.id, self.pieces),
+                TradingErrorKind::InvalidOrder,
+            ));
+        }
+
+        let realized = self.profit(fill.price, fill.pieces);
+        self.realized += realized;
+        self.pieces -= fill.pieces;
+        self.fees += fill.fee;
+        if self.pieces == 0 {
+            self.closed = Some(fill.time);
+        }
+        self.fills.push(fill);
+
+        Ok(realized)
+    }
+
+    /// closes all open pieces and returns their realized profit or loss
+    pub fn close(&mut self, time: DateTime<Local>, price: Price, fee: Price) -> Result<Price, Error<TradingErrorKind>> {
+        self.scale_out(Fill::new(time, self.pieces, price).with_fee(fee))
+    }
+
+    /// the profit or loss of the open pieces at the given price, without fees
+    pub fn unrealized(&self, price: Price) -> Price {
+        self.profit(price, self.pieces)
+    }
+
+    /// the realized and unrealized profit or loss minus all fees
+    pub fn pnl(&self, price: Price) -> Price {
+        self.realized + self.unrealized(price) - self.fees
+    }
+
+    /// the value of the open pieces at the given price
+    pub fn market_value(&self, price: Price) -> Price {
+        self.derivative.notional(price, self.pieces)
+    }
+
+    /// the value of the open pieces at the entry price
+    pub fn cost_basis(&self) -> Price {
+        self.derivative.notional(self.entry_price, self.pieces)
+    }
+
+    fn profit(&self, price: Price, pieces: u64) -> Price {
+        let difference = match self.direction() {
+            Direction::Long => price - self.entry_price,
+            Direction::Short => self.entry_price - price,
+        };
+        self.derivative.notional(difference, pieces)
+    }
+
+    fn check_open(&self) -> Result<(), Error<TradingErrorKind>> {
+        if self.is_open() {
+            Ok(())
+        } else {
+            Err(Error::new(
+                format!("The position `{}` is already closed", self.id),
+                TradingErrorKind::NoSuchPosition,
+            ))
+        }
+    }
+}
+
+/// A single execution of an order
+///
+/// #### Fields:
+/// * __time__: When the order was executed
+/// * __pieces__: How many pieces were executed
+/// * __price__: The price per piece
+/// * __fee__: The fees the broker charged for the execution
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Fill {
+    pub time: DateTime<Local>,
+    pub pieces: u64,
+    pub price: Price,
+    pub fee: Price,
+}
+
+impl Fill {
+    pub fn new(time: DateTime<Local>, pieces: u64, price: Price) -> Self {
+        Self {
+            time,
+            pieces,
+            price,
+            fee: Price::zero(),
+        }
+    }
+
+    pub fn with_fee(mut self, fee: Price) -> Self {
+        self.fee = fee;
+        self
     }
 }
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(rename_all = "snake_case"))]
 pub enum PositionType {
     LongCall,
     LongPut,
     ShortCall,
     ShortPut,
 }
+
+impl PositionType {
+    /// Long positions bought the derivative, short positions sold it.
+    pub fn direction(&self) -> Direction {
+        match self {
+            PositionType::LongCall | PositionType::LongPut => Direction::Long,
+            PositionType::ShortCall | PositionType::ShortPut => Direction::Short,
+        }
+    }
+}
+
+/// Whether a position profits from rising (long) or falling (short) prices
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(rename_all = "snake_case"))]
+pub enum Direction {
+    Long,
+    Short,
+}
+
+impl Direction {
+    /// 1 for long and -1 for short positions
+    pub fn sign(&self) -> f64 {
+        match self {
+            Direction::Long => 1.0,
+            Direction::Short => -1.0,
+        }
+    }
+
+    /// the direction of the orders that close a position of this direction
+    pub fn opposite(&self) -> Direction {
+        match self {
+            Direction::Long => Direction::Short,
+            Direction::Short => Direction::Long,
+        }
+    }
+}
@@ -0,0 +1,167 @@
+use crate::{Derivative, Error, ErrorKind, MarketValue, Percent, Price, StopLoss};
+
+/// How many pieces of a derivative an order buys
+///
+/// The pieces are computed from the balance of a deposit, the entry price and the stop loss of
+/// the order. They are rounded down to a multiple of the lot size of the derivative and are
+/// zero if the order wouldn't reach the minimal order value.
+/// The sizings that risk a fraction of the balance buy at most the pieces the balance can pay
+/// for at the entry price, so a tight stop doesn't lever the deposit. `FixedNotional` buys for
+/// its notional, even if that's more than the balance.
+///
+/// #### Variants:
+/// * __FixedFractional__: Risks a fraction of the balance, the distance from the entry to the
+///   stop loss is the risk of a piece
+/// * __FixedNotional__: Buys for a fixed value, the stop loss is ignored
+/// * __Kelly__: Risks the scaled Kelly fraction of the balance, see `kelly_fraction`. A scale of
+///   `0.5` is the common "half Kelly"
+/// * __VolatilityTarget__: Risks a fraction of the balance with a stop at a multiple of the
+///   average true range, see `Candle::average_true_range`. The stop loss is ignored
+/// ```
+/// # use trading_utils::*;
+/// let share = Derivative::new("SAP".to_string(), DerivativeKind::Stock, Currency::EUR)
+///     .with_lot_size(5);
+/// let sizing = PositionSizing::FixedFractional { risk: Percent::from(0.01) };
+///
+/// // 1% of 10 000 are 100, with a stop 4 below the entry that's 25 pieces, 25 is a multiple of 5
+/// let stop_loss = StopLoss::Absolute(Price::from(96.0));
+/// assert_eq!(sizing.pieces(Price::from(10_000.0), &share, Price::from(100.0), &stop_loss).unwrap(), 25);
+///
+/// // a relative stop loss is the distance to the entry
+/// let stop_loss = StopLoss::Relative(Price::from(-3.0));
+/// assert_eq!(sizing.pieces(Price::from(10_000.0), &share, Price::from(100.0), &stop_loss).unwrap(), 30);
+///
+/// // a stop 0.5 below the entry would risk 100 with 200 pieces, but the balance pays for 100
+/// let stop_loss = StopLoss::Relative(Price::from(-0.5));
+/// assert_eq!(sizing.pieces(Price::from(10_000.0), &share, Price::from(100.0), &stop_loss).unwrap(), 100);
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(tag = "type", content = "value", rename_all = "snake_case"))]
+pub enum PositionSizing {
+    FixedFractional { risk: Percent },
+    FixedNotional { notional: Price },
+    Kelly { win_rate: Percent, win_loss_ratio: f64, scale: Percent },
+    VolatilityTarget { risk: Percent, atr: Price, multiple: f64 },
+}
+
+impl PositionSizing {
+    /// returns the pieces of an order at the entry price
+    ///
+    /// Returns an error if the entry price isn't positive, the balance is negative or the
+    /// stop loss is needed but `None` or at the entry price.
+    pub fn pieces(&self, balance: Price, derivative: &Derivative, entry: Price, stop_loss: &StopLoss) -> Result<u64, Error<ErrorKind>> {
+        if entry <= Price::zero() {
+            return invalid(format!("The entry price of `{}` needs to be positive", derivative.symbol));
+        }
+        if balance < Price::zero() {
+            return invalid(format!("The balance can't be negative to size an order of `{}`", derivative.symbol));
+        }
+
+        let price_per_piece = derivative.notional(entry, 1).as_f64();
+        let affordable = balance.as_f64() / price_per_piece;
+
+        use PositionSizing::*;
+        let pieces = match self {
+            FixedFractional { risk } =>
+                risked_pieces(balance, *risk, derivative, stop_distance(derivative, entry, stop_loss)?).min(affordable),
+            FixedNotional { notional } =>
+                notional.as_f64() / price_per_piece,
+            Kelly { win_rate, win_loss_ratio, scale } => {
+                let risk = Self::kelly_fraction(*win_rate, *win_loss_ratio) * scale.as_f64();
+                risked_pieces(balance, risk, derivative, stop_distance(derivative, entry, stop_loss)?).min(affordable)
+            }
+            VolatilityTarget { risk, atr, multiple } => {
+                let distance = *atr * multiple.abs();
+                if distance <= Price::zero() {
+                    return invalid(format!("The volatility of `{}` needs to be positive", derivative.symbol));
+                }
+                risked_pieces(balance, *risk, derivative, distance).min(affordable)
+            }
+        };
+
+        Ok(to_pieces(pieces, derivative, entry))
+    }
+
+    /// returns the pieces of every derivative of a basket, so each gets the same share of the
+    /// balance
+    ///
+    /// The basket contains the derivatives and their entry prices. Since the pieces are rounded
+    /// down, some of the balance is usually left.
+    /// ```
+    /// # use trading_utils::*;
+    /// let basket = vec![
+    ///     (Derivative::new("SAP".to_string(), DerivativeKind::Stock, Currency::EUR), Price::from(120.0)),
+    ///     (Derivative::new("BMW".to_string(), DerivativeKind::Stock, Currency::EUR), Price::from(80.0)),
+    /// ];
+    ///
+    /// let pieces = PositionSizing::equal_weight(Price::from(10_000.0), &basket).unwrap();
+    /// assert_eq!(pieces, vec![41, 62]);
+    /// ```
+    pub fn equal_weight(balance: Price, basket: &[(Derivative, Price)]) -> Result<Vec<u64>, Error<ErrorKind>> {
+        if basket.is_empty() {
+            return Ok(Vec::new());
+        }
+        let sizing = PositionSizing::FixedNotional { notional: balance / basket.len() as f64 };
+
+        basket
+            .iter()
+            .map(|(derivative, entry)| sizing.pieces(balance, derivative, *entry, &StopLoss::None))
+            .collect()
+    }
+
+    /// returns the fraction of the balance the Kelly criterion risks per trade,
+    /// `win_rate - (1 - win_rate) / win_loss_ratio`
+    ///
+    /// The win loss ratio is the average win per average loss, see `TradeMetrics`.
+    /// Strategies without an edge get zero.
+    /// ```
+    /// # use trading_utils::{MarketValue, Percent, PositionSizing};
+    /// let fraction = PositionSizing::kelly_fraction(Percent::from(0.6), 1.0);
+    /// assert!((fraction.as_f64() - 0.2).abs() < 1e-9);
+    ///
+    /// assert_eq!(PositionSizing::kelly_fraction(Percent::from(0.4), 1.0), Percent::from(0.0));
+    /// ```
+    pub fn kelly_fraction(win_rate: Percent, win_loss_ratio: f64) -> Percent {
+        if win_loss_ratio <= 0.0 {
+            return Percent::zero();
+        }
+        let win_rate = win_rate.as_f64().clamp(0.0, 1.0);
+        Percent::from((win_rate - (1.0 - win_rate) / win_loss_ratio).max(0.0))
+    }
+}
+
+/// the distance from the entry price to the stop loss
+fn stop_distance(derivative: &Derivative, entry: Price, stop_loss: &StopLoss) -> Result<Price, Error<ErrorKind>> {
+    let distance = match stop_loss {
+        StopLoss::Absolute(stop) => Price::from((entry - *stop).abs()),
+        StopLoss::Relative(distance) | StopLoss::Trailing(distance) => Price::from(distance.abs()),
+        StopLoss::None => return invalid(format!("An order of `{}` needs a stop loss to be sized by its risk", derivative.symbol)),
+    };
+    if distance <= Price::zero() {
+        return invalid(format!("The stop loss of `{}` can't be at the entry price", derivative.symbol));
+    }
+    Ok(distance)
+}
+
+/// the pieces that lose the risked fraction of the balance, if the price moves by the distance
+fn risked_pieces(balance: Price, risk: Percent, derivative: &Derivative, distance: Price) -> f64 {
+    (balance * risk).as_f64() / derivative.notional(distance, 1).as_f64()
+}
+
+/// rounds the pieces down to a multiple of the lot size, zero if they are below the minimal
+/// order value
+fn to_pieces(pieces: f64, derivative: &Derivative, entry: Price) -> u64 {
+    let lot_size = derivative.lot_size.max(1);
+    // tolerates the float error of a division that should be exact
+    let lots = (pieces / lot_size as f64 + 1e-9).floor();
+    let pieces = if lots.is_finite() && lots > 0.0 { lots as u64 * lot_size } else { 0 };
+
+    match derivative.min_order_value {
+        Some(min_order_value) if derivative.notional(entry, pieces) < min_order_value => 0,
+        _ => pieces,
+    }
+}
+
+fn invalid<T>(msg: String) -> Result<T, Error<ErrorKind>> {
+    Err(Error::new(msg, ErrorKind::InvalidParameter))
+}
@@ -0,0 +1,146 @@
+use crate::{ExerciseStyle, Greeks, MarketValue, OptionParameters, OptionPricingModel, OptionRight, Percent, Price};
+use crate::pricing::{central_difference, DAYS_PER_YEAR, MIN_VOLATILITY};
+
+/// The default number of steps of a `Binomial` tree
+pub const DEFAULT_BINOMIAL_STEPS: usize = 200;
+
+/// The binomial tree of Cox, Ross and Rubinstein
+///
+/// Values European and American options. The more steps the more exact the price,
+/// but the runtime grows quadratic.
+/// ```
+/// # use trading_utils::*;
+/// let put = OptionParameters::new(
+///     OptionRight::Put,
+///     ExerciseStyle::American,
+///     Price::from(100.0),
+///     Price::from(100.0),
+///     1.0,
+///     Percent::from(0.05),
+///     Percent::from(0.2),
+/// );
+/// let european = OptionParameters { style: ExerciseStyle::European, ..put };
+///
+/// // the early exercise makes the american put more valuable
+/// assert!(Binomial::default().price(&put) > BlackScholes.price(&european));
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Binomial {
+    steps: usize,
+}
+
+impl Default for Binomial {
+    fn default() -> Self {
+        Self::new(DEFAULT_BINOMIAL_STEPS)
+    }
+}
+
+/// the rolled back tree, with the nodes of the first two steps for the greeks
+struct Tree {
+    value: f64,
+    first_step: [f64; 2],
+    second_step: [f64; 3],
+    up: f64,
+    dt: f64,
+}
+
+impl Binomial {
+    /// creates a tree with the given steps, at least three
+    ///
+    /// The gamma is read from the second step, which needs to be rolled back from a later one.
+    pub fn new(steps: usize) -> Self {
+        Self { steps: steps.max(3) }
+    }
+
+    pub fn steps(&self) -> usize { self.steps }
+
+    fn roll_back(&self, option: &OptionParameters) -> Tree {
+        let spot = option.spot.as_f64();
+        let strike = option.strike.as_f64();
+        let volatility = option.volatility.as_f64().max(MIN_VOLATILITY);
+        let rate = option.rate.as_f64();
+        let dividend_yield = option.dividend_yield.as_f64();
+
+        let dt = option.years / self.steps as f64;
+        let up = (volatility * dt.sqrt()).exp();
+        let down = 1.0 / up;
+        let probability = (((rate - dividend_yield) * dt).exp() - down) / (up - down);
+        let discount = (-rate * dt).exp();
+
+        let payoff = |price: f64| match option.right {
+            OptionRight::Call => (price - strike).max(0.0),
+            OptionRight::Put => (strike - price).max(0.0),
+        };
+        let node_price = |step: usize, ups: usize| spot * up.powi(ups as i32) * down.powi((step - ups) as i32);
+
+        let mut values: Vec<f64> = (0..=self.steps)
+            .map(|ups| payoff(node_price(self.steps, ups)))
+            .collect();
+        let mut first_step = [0.0; 2];
+        let mut second_step = [0.0; 3];
+
+        for step in (0..self.steps).rev() {
+            for ups in 0..=step {
+                let held = discount * (probability * values[ups + 1] + (1.0 - probability) * values[ups]);
+                values[ups] = match option.style {
+                    ExerciseStyle::European => held,
+                    ExerciseStyle::American => held.max(payoff(node_price(step, ups))),
+                };
+            }
+            match step {
+                2 => second_step.copy_from_slice(&values[..3]),
+                1 => first_step.copy_from_slice(&values[..2]),
+                _ => {}
+            }
+        }
+
+        Tree {
+            value: values[0],
+            first_step,
+            second_step,
+            up,
+            dt,
+        }
+    }
+}
+
+impl OptionPricingModel for Binomial {
+    fn price(&self, option: &OptionParameters) -> Price {
+        if option.years <= 0.0 {
+            return option.intrinsic_value();
+        }
+
+        Price::from(self.roll_back(option).value)
+    }
+
+    /// The delta, gamma and theta are read from the first steps of the tree,
+    /// the vega and rho are finite differences.
+    fn greeks(&self, option: &OptionParameters) -> Greeks {
+        if option.years <= 0.0 {
+            return Greeks::default();
+        }
+        let tree = self.roll_back(option);
+        let spot = option.spot.as_f64();
+        let (up, down) = (tree.up, 1.0 / tree.up);
+
+        let delta = (tree.first_step[1] - tree.first_step[0]) / (spot * up - spot * down);
+        let upper_delta = (tree.second_step[2] - tree.second_step[1]) / (spot * up * up - spot);
+        let lower_delta = (tree.second_step[1] - tree.second_step[0]) / (spot - spot * down * down);
+        let gamma = (upper_delta - lower_delta) / (0.5 * (spot * up * up - spot * down * down));
+        let theta = (tree.second_step[1] - tree.value) / (2.0 * tree.dt);
+
+        let price = |option: &OptionParameters| self.roll_back(option).value;
+
+        Greeks {
+            delta,
+            gamma,
+            vega: Price::from(central_difference(option.volatility.as_f64(), 0.01, MIN_VOLATILITY, |volatility| {
+                price(&option.with_volatility(Percent::from(volatility)))
+            })),
+            theta: Price::from(theta / DAYS_PER_YEAR),
+            rho: Price::from(central_difference(option.rate.as_f64(), 0.01, f64::MIN, |rate| {
+                price(&option.with_rate(Percent::from(rate)))
+            })),
+        }
+    }
+}
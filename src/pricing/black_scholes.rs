@@ -0,0 +1,108 @@
+use crate::{Greeks, MarketValue, OptionParameters, OptionPricingModel, OptionRight, Price};
+use crate::pricing::normal::{cdf, pdf};
+use crate::pricing::DAYS_PER_YEAR;
+
+/// The Black-Scholes-Merton model
+///
+/// Values European options in closed form, with a continuous dividend yield.
+/// Early exercise is ignored, so American options are valued as if they were European.
+/// That's exact for American calls without dividends, for American puts use `Binomial`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct BlackScholes;
+
+/// the terms that the price and all greeks share
+struct Terms {
+    spot: f64,
+    strike: f64,
+    years: f64,
+    rate: f64,
+    volatility: f64,
+    dividend_yield: f64,
+    d1: f64,
+    d2: f64,
+    spot_discount: f64,
+    strike_discount: f64,
+}
+
+impl Terms {
+    fn new(option: &OptionParameters) -> Self {
+        let spot = option.spot.as_f64();
+        let strike = option.strike.as_f64();
+        let years = option.years;
+        let rate = option.rate.as_f64();
+        let volatility = option.volatility.as_f64();
+        let dividend_yield = option.dividend_yield.as_f64();
+
+        let deviation = (volatility * years.sqrt()).max(1e-12);
+        let d1 = ((spot / strike).ln() + (rate - dividend_yield + volatility * volatility / 2.0) * years) / deviation;
+
+        Self {
+            spot,
+            strike,
+            years,
+            rate,
+            volatility,
+            dividend_yield,
+            d1,
+            d2: d1 - deviation,
+            spot_discount: (-dividend_yield * years).exp(),
+            strike_discount: (-rate * years).exp(),
+        }
+    }
+}
+
+impl OptionPricingModel for BlackScholes {
+    fn price(&self, option: &OptionParameters) -> Price {
+        if option.years <= 0.0 {
+            return option.intrinsic_value();
+        }
+        let t = Terms::new(option);
+
+        let price = match option.right {
+            OptionRight::Call => t.spot * t.spot_discount * cdf(t.d1) - t.strike * t.strike_discount * cdf(t.d2),
+            OptionRight::Put => t.strike * t.strike_discount * cdf(-t.d2) - t.spot * t.spot_discount * cdf(-t.d1),
+        };
+
+        Price::from(price.max(0.0))
+    }
+
+    fn greeks(&self, option: &OptionParameters) -> Greeks {
+        if option.years <= 0.0 {
+            let in_the_money = option.intrinsic_value() > Price::zero();
+            return Greeks {
+                delta: match (option.right, in_the_money) {
+                    (_, false) => 0.0,
+                    (OptionRight::Call, true) => 1.0,
+                    (OptionRight::Put, true) => -1.0,
+                },
+                ..Greeks::default()
+            };
+        }
+        let t = Terms::new(option);
+        let density = pdf(t.d1);
+        let time_decay = -t.spot * t.spot_discount * density * t.volatility / (2.0 * t.years.sqrt());
+
+        let (delta, theta, rho) = match option.right {
+            OptionRight::Call => (
+                t.spot_discount * cdf(t.d1),
+                time_decay - t.rate * t.strike * t.strike_discount * cdf(t.d2)
+                    + t.dividend_yield * t.spot * t.spot_discount * cdf(t.d1),
+                t.strike * t.years * t.strike_discount * cdf(t.d2),
+            ),
+            OptionRight::Put => (
+                t.spot_discount * (cdf(t.d1) - 1.0),
+                time_decay + t.rate * t.strike * t.strike_discount * cdf(-t.d2)
+                    - t.dividend_yield * t.spot * t.spot_discount * cdf(-t.d1),
+                -t.strike * t.years * t.strike_discount * cdf(-t.d2),
+            ),
+        };
+
+        Greeks {
+            delta,
+            gamma: t.spot_discount * density / (t.spot * t.volatility * t.years.sqrt()).max(1e-12),
+            vega: Price::from(t.spot * t.spot_discount * density * t.years.sqrt() / 100.0),
+            theta: Price::from(theta / DAYS_PER_YEAR),
+            rho: Price::from(rho / 100.0),
+        }
+    }
+}
@@ -0,0 +1,62 @@
+use std::ops::{Add, Mul};
+
+use crate::{PositionType, Price};
+
+/// The sensitivities of an option price
+///
+/// #### Fields:
+/// * __delta__: The change of the price per change of the spot
+/// * __gamma__: The change of the delta per change of the spot
+/// * __vega__: The change of the price per percentage point of volatility
+/// * __theta__: The change of the price per calendar day that passes
+/// * __rho__: The change of the price per percentage point of the interest rate
+///
+/// The greeks are per piece of the underlying, use `for_position` for the greeks of a position.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Greeks {
+    pub delta: f64,
+    pub gamma: f64,
+    pub vega: Price,
+    pub theta: Price,
+    pub rho: Price,
+}
+
+impl Greeks {
+    /// returns the greeks of a position of the given pieces
+    ///
+    /// The greeks are multiplied by the pieces and the multiplier of the option,
+    /// short positions get the opposite sign.
+    pub fn for_position(&self, position_type: PositionType, pieces: u64, multiplier: f64) -> Self {
+        *self * (position_type.direction().sign() * pieces as f64 * multiplier)
+    }
+}
+
+impl Mul<f64> for Greeks {
+    type Output = Greeks;
+
+    fn mul(self, factor: f64) -> Self::Output {
+        Greeks {
+            delta: self.delta * factor,
+            gamma: self.gamma * factor,
+            vega: self.vega * factor,
+            theta: self.theta * factor,
+            rho: self.rho * factor,
+        }
+    }
+}
+
+impl Add for Greeks {
+    type Output = Greeks;
+
+    /// adds the greeks of two positions
+    fn add(self, other: Greeks) -> Self::Output {
+        Greeks {
+            delta: self.delta + other.delta,
+            gamma: self.gamma + other.gamma,
+            vega: self.vega + other.vega,
+            theta: self.theta + other.theta,
+            rho: self.rho + other.rho,
+        }
+    }
+}
@@ -0,0 +1,128 @@
+use crate::{Error, ErrorKind, MarketValue, Percent, Price};
+
+pub use binomial::*;
+pub use black_scholes::*;
+pub use greeks::*;
+pub use option_parameters::*;
+
+pub mod binomial;
+pub mod black_scholes;
+pub mod greeks;
+pub mod option_parameters;
+mod normal;
+
+const MIN_VOLATILITY: f64 = 1e-4;
+const MAX_VOLATILITY: f64 = 5.0;
+const MAX_ITERATIONS: usize = 100;
+
+/// A model that values options
+///
+/// The crate provides the closed form `BlackScholes` model for European options and the
+/// `Binomial` tree that also values American options.
+/// ```
+/// # use trading_utils::*;
+/// let call = OptionParameters::new(
+///     OptionRight::Call,
+///     ExerciseStyle::European,
+///     Price::from(100.0),
+///     Price::from(100.0),
+///     1.0,
+///     Percent::from(0.05),
+///     Percent::from(0.2),
+/// );
+///
+/// let price = BlackScholes.price(&call);
+/// assert!((price.as_f64() - 10.4506).abs() < 1e-4);
+///
+/// let volatility = BlackScholes.implied_volatility(&call, price).unwrap();
+/// assert!((volatility.as_f64() - 0.2).abs() < 1e-6);
+/// ```
+pub trait OptionPricingModel {
+    /// returns the fair price of one piece of the underlying
+    fn price(&self, option: &OptionParameters) -> Price;
+
+    /// returns the greeks of the option
+    ///
+    /// By default they are computed with finite differences of `price`.
+    fn greeks(&self, option: &OptionParameters) -> Greeks {
+        let price = |option: &OptionParameters| self.price(option).as_f64();
+        let current = price(option);
+
+        let spot = option.spot.as_f64();
+        let spot_step = (spot * 0.01).max(1e-4);
+        let up = price(&option.with_spot(Price::from(spot + spot_step)));
+        let down = price(&option.with_spot(Price::from((spot - spot_step).max(0.0))));
+
+        let day = 1.0 / DAYS_PER_YEAR;
+        let tomorrow = price(&option.with_years((option.years - day).max(0.0)));
+
+        Greeks {
+            delta: (up - down) / (2.0 * spot_step),
+            gamma: (up - 2.0 * current + down) / (spot_step * spot_step),
+            vega: Price::from(central_difference(option.volatility.as_f64(), 0.01, MIN_VOLATILITY, |volatility| {
+                price(&option.with_volatility(Percent::from(volatility)))
+            })),
+            theta: Price::from(tomorrow - current),
+            rho: Price::from(central_difference(option.rate.as_f64(), 0.01, f64::MIN, |rate| {
+                price(&option.with_rate(Percent::from(rate)))
+            })),
+        }
+    }
+
+    /// returns the volatility at which the model price is the market price
+    ///
+    /// Returns an error if no volatility between 0.01% and 500% matches the market price,
+    /// for example if the market price is below the intrinsic value.
+    fn implied_volatility(&self, option: &OptionParameters, market_price: Price) -> Result<Percent, Error<ErrorKind>> {
+        let target = market_price.as_f64();
+        let difference = |volatility: f64| self.price(&option.with_volatility(Percent::from(volatility))).as_f64() - target;
+
+        let (mut low, mut high) = (MIN_VOLATILITY, MAX_VOLATILITY);
+        let (low_difference, high_difference) = (difference(low), difference(high));
+        if low_difference > 0.0 || high_difference < 0.0 {
+            return Err(Error::new(
+                format!(
+                    "No volatility matches the market price {}, the model prices are between {} and {}",
+                    target, low_difference + target, high_difference + target
+                ),
+                ErrorKind::InvalidParameter,
+            ));
+        }
+
+        // Newton steps with the vega, bisection whenever a step leaves the bracket
+        let mut volatility = option.volatility.as_f64().clamp(low, high);
+        for _ in 0..MAX_ITERATIONS {
+            let current = difference(volatility);
+            if current.abs() < 1e-10 {
+                break;
+            }
+            if current > 0.0 { high = volatility } else { low = volatility }
+
+            let vega = self
+                .greeks(&option.with_volatility(Percent::from(volatility)))
+                .vega
+                .as_f64() * 100.0;
+            let newton = volatility - current / vega;
+            volatility = if vega > 1e-12 && newton > low && newton < high {
+                newton
+            } else {
+                (low + high) / 2.0
+            };
+
+            if high - low < 1e-12 {
+                break;
+            }
+        }
+
+        Ok(Percent::from(volatility))
+    }
+}
+
+/// the change of `f` per `step`, one sided if `value - step` would be below `min`
+fn central_difference(value: f64, step: f64, min: f64, f: impl Fn(f64) -> f64) -> f64 {
+    if value - step < min {
+        f(value + step) - f(value)
+    } else {
+        (f(value + step) - f(value - step)) / 2.0
+    }
+}
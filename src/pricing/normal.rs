@@ -0,0 +1,50 @@
+//! The standard normal distribution
+
+const ONE_OVER_SQRT_TWO_PI: f64 = 0.398_942_280_401_432_7;
+
+/// the probability density function
+pub(crate) fn pdf(x: f64) -> f64 {
+    ONE_OVER_SQRT_TWO_PI * (-0.5 * x * x).exp()
+}
+
+/// The cumulative distribution function.
+/// Uses the double precision algorithm of Hart (1968) as described by West (2005),
+/// the error is below 1e-14.
+pub(crate) fn cdf(x: f64) -> f64 {
+    let x_abs = x.abs();
+
+    let tail = if x_abs > 37.0 {
+        0.0
+    } else {
+        let exponential = (-x_abs * x_abs / 2.0).exp();
+
+        if x_abs < 7.071_067_811_865_47 {
+            let mut numerator = 3.526_249_659_989_11e-2 * x_abs + 0.700_383_064_443_688;
+            numerator = numerator * x_abs + 6.373_962_203_531_65;
+            numerator = numerator * x_abs + 33.912_866_078_383;
+            numerator = numerator * x_abs + 112.079_291_497_871;
+            numerator = numerator * x_abs + 221.213_596_169_931;
+            numerator = numerator * x_abs + 220.206_867_912_376;
+
+            let mut denominator = 8.838_834_764_831_84e-2 * x_abs + 1.755_667_163_182_64;
+            denominator = denominator * x_abs + 16.064_177_579_207;
+            denominator = denominator * x_abs + 86.780_732_202_946_1;
+            denominator = denominator * x_abs + 296.564_248_779_674;
+            denominator = denominator * x_abs + 637.333_633_378_831;
+            denominator = denominator * x_abs + 793.826_512_519_948;
+            denominator = denominator * x_abs + 440.413_735_824_752;
+
+            exponential * numerator / denominator
+        } else {
+            let mut fraction = x_abs + 0.65;
+            fraction = x_abs + 4.0 / fraction;
+            fraction = x_abs + 3.0 / fraction;
+            fraction = x_abs + 2.0 / fraction;
+            fraction = x_abs + 1.0 / fraction;
+
+            exponential / fraction / 2.506_628_274_631
+        }
+    };
+
+    if x > 0.0 { 1.0 - tail } else { tail }
+}
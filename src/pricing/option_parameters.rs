@@ -0,0 +1,110 @@
+use chrono::{DateTime, Local};
+
+use crate::{Derivative, DerivativeKind, Error, ErrorKind, ExerciseStyle, MarketValue, OptionRight, Percent, Price};
+
+/// The days of a year used to convert times into years
+pub const DAYS_PER_YEAR: f64 = 365.0;
+
+/// Everything an option pricing model needs to know about an option and its market
+///
+/// #### Fields:
+/// * __right__: Call or put
+/// * __style__: European or American
+/// * __spot__: The current price of the underlying
+/// * __strike__: The strike of the option
+/// * __years__: The time until the expiry in years
+/// * __rate__: The continuously compounded risk free interest rate per year,
+///   `Percent::from(0.05)` for 5%
+/// * __volatility__: The annualized volatility of the underlying
+/// * __dividend_yield__: The continuously compounded dividend yield of the underlying per year
+///   (default 0)
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct OptionParameters {
+    pub right: OptionRight,
+    pub style: ExerciseStyle,
+    pub spot: Price,
+    pub strike: Price,
+    pub years: f64,
+    pub rate: Percent,
+    pub volatility: Percent,
+    pub dividend_yield: Percent,
+}
+
+impl OptionParameters {
+    pub fn new(right: OptionRight, style: ExerciseStyle, spot: Price, strike: Price, years: f64, rate: Percent, volatility: Percent) -> Self {
+        Self {
+            right,
+            style,
+            spot,
+            strike,
+            years,
+            rate,
+            volatility,
+            dividend_yield: Percent::zero(),
+        }
+    }
+
+    /// takes the right, style, strike and expiry of an option or warrant
+    ///
+    /// Returns an error if the derivative is no option or warrant or if the strike or the expiry
+    /// is missing. An expiry in the past is treated as expiring now.
+    pub fn from_derivative(derivative: &Derivative, time: DateTime<Local>, spot: Price, rate: Percent, volatility: Percent) -> Result<Self, Error<ErrorKind>> {
+        let (right, style) = match derivative.kind {
+            DerivativeKind::Option { right, style } | DerivativeKind::Warrant { right, style } => (right, style),
+            kind => return Err(Error::new(
+                format!("`{}` is a {:?}, only options and warrants can be priced", derivative.symbol, kind),
+                ErrorKind::InvalidParameter,
+            )),
+        };
+        let missing = |what: &str| Error::new(
+            format!("`{}` has no {}", derivative.symbol, what),
+            ErrorKind::InvalidParameter,
+        );
+        let strike = derivative.strike.ok_or_else(|| missing("strike"))?;
+        let expiry = derivative.expiry.ok_or_else(|| missing("expiry"))?;
+
+        Ok(Self::new(right, style, spot, strike, years_between(time, expiry), rate, volatility))
+    }
+
+    pub fn with_dividend_yield(mut self, dividend_yield: Percent) -> Self {
+        self.dividend_yield = dividend_yield;
+        self
+    }
+
+    pub fn with_spot(mut self, spot: Price) -> Self {
+        self.spot = spot;
+        self
+    }
+
+    pub fn with_years(mut self, years: f64) -> Self {
+        self.years = years;
+        self
+    }
+
+    pub fn with_rate(mut self, rate: Percent) -> Self {
+        self.rate = rate;
+        self
+    }
+
+    pub fn with_volatility(mut self, volatility: Percent) -> Self {
+        self.volatility = volatility;
+        self
+    }
+
+    /// the value of exercising the option right now
+    pub fn intrinsic_value(&self) -> Price {
+        let (spot, strike) = (self.spot.as_f64(), self.strike.as_f64());
+
+        Price::from(match self.right {
+            OptionRight::Call => (spot - strike).max(0.0),
+            OptionRight::Put => (strike - spot).max(0.0),
+        })
+    }
+}
+
+/// returns the time between both dates in years, at least zero
+pub fn years_between(from: DateTime<Local>, to: DateTime<Local>) -> f64 {
+    let seconds = (to - from).num_seconds().max(0) as f64;
+    seconds / (DAYS_PER_YEAR * 24.0 * 60.0 * 60.0)
+}
@@ -0,0 +1,31 @@
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// Stops a `RiskManager` from opening any new orders, also from another thread
+///
+/// A KillSwitch is a handle, so all clones of a switch control the same risk managers.
+/// While it's engaged every action that submits or changes an order that opens positions is
+/// rejected. Positions can still be closed, the exits of active brackets changed and orders
+/// cancelled.
+#[derive(Clone, Debug, Default)]
+pub struct KillSwitch {
+    engaged: Arc<AtomicBool>,
+}
+
+impl KillSwitch {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn engage(&self) {
+        self.engaged.store(true, Ordering::SeqCst);
+    }
+
+    pub fn release(&self) {
+        self.engaged.store(false, Ordering::SeqCst);
+    }
+
+    pub fn is_engaged(&self) -> bool {
+        self.engaged.load(Ordering::SeqCst)
+    }
+}
@@ -0,0 +1,355 @@
+use std::collections::HashMap;
+
+use chrono::{DateTime, Local, NaiveDate};
+
+pub use kill_switch::*;
+pub use risk_event::*;
+pub use risk_limits::*;
+
+use crate::{Deposit, Derivative, Error, ErrorKind, EventBus, ExchangeRates, MarketValue, Order, OrderType, Percent, PlannedAction, Portfolio, Price, TradingErrorKind};
+
+pub mod kill_switch;
+pub mod risk_event;
+pub mod risk_limits;
+
+/// Vets the actions of an `Executor` before they reach the broker
+///
+/// Only actions that submit or change orders that open positions are checked. Closing
+/// positions, changing the take profit or the stop loss of an active bracket and cancelling
+/// orders always reduce the risk, so they pass even while the kill switch is engaged. The checks only use the `Deposit`, so they work the same
+/// with every broker. A violation rejects the action with `TradingErrorKind::RiskLimit` and
+/// emits a `RiskEvent`.
+///
+/// Positions and market orders are valued at the last known price of their symbol, see
+/// `update_price`. Limit and stop orders are valued at their price.
+/// The daily loss is measured from the equity at the first `update` of a day, so it isn't
+/// checked before `update` was called. `Executor::trade` and the `Runner` call both with every
+/// step, see `Executor::update_candle` and `Executor::update`.
+/// ```
+/// # use trading_utils::*;
+/// let mut deposit = Deposit::empty("deposit".to_string(), Currency::EUR);
+/// deposit.update_balance(Price::from(1_000.0));
+/// let share = Derivative::new("SAP".to_string(), DerivativeKind::Stock, Currency::EUR);
+///
+/// let mut risk_manager = RiskManager::new(RiskLimits::new().with_max_leverage(1.0));
+/// let order = |pieces| PlannedAction::Submit(Order::Single(OrderData::new(
+///     "order-1".to_string(), share.clone(), StockExchange::LSExchange, pieces,
+///     OrderType::LimitOrder(Price::from(100.0)), PositionType::LongCall,
+///     TakeProfit::None, StopLoss::None, OrderMoment::Instant, OrderValidity::OneDay,
+/// )));
+///
+/// assert!(risk_manager.check(&order(10), &deposit).is_ok());
+/// assert_eq!(risk_manager.check(&order(11), &deposit).unwrap_err().kind(), TradingErrorKind::RiskLimit);
+///
+/// risk_manager.kill_switch().engage();
+/// assert!(risk_manager.check(&order(1), &deposit).is_err());
+/// ```
+pub struct RiskManager {
+    limits: RiskLimits,
+    kill_switch: KillSwitch,
+    rates: Option<ExchangeRates>,
+    prices: HashMap<String, Price>,
+    day: Option<(NaiveDate, Price)>,
+    daily_loss_reached: bool,
+    events: EventBus<RiskEvent>,
+}
+
+impl RiskManager {
+    pub fn new(limits: RiskLimits) -> Self {
+        Self {
+            limits,
+            kill_switch: KillSwitch::new(),
+            rates: None,
+            prices: HashMap::new(),
+            day: None,
+            daily_loss_reached: false,
+            events: EventBus::new(),
+        }
+    }
+
+    /// uses an existing kill switch
+    pub fn with_kill_switch(mut self, kill_switch: &KillSwitch) -> Self {
+        self.kill_switch = kill_switch.clone();
+        self
+    }
+
+    /// converts the values of derivatives in other currencies than the deposit,
+    /// the base currency has to be the currency of the deposit
+    pub fn with_exchange_rates(mut self, rates: ExchangeRates) -> Self {
+        self.rates = Some(rates);
+        self
+    }
+
+    /// uses an existing bus for the risk events
+    pub fn with_events(mut self, events: &EventBus<RiskEvent>) -> Self {
+        self.events = events.clone();
+        self
+    }
+
+    pub fn limits(&self) -> &RiskLimits { &self.limits }
+    pub fn kill_switch(&self) -> &KillSwitch { &self.kill_switch }
+    pub fn events(&self) -> &EventBus<RiskEvent> { &self.events }
+    /// the last known prices per symbol
+    pub fn prices(&self) -> &HashMap<String, Price> { &self.prices }
+
+    pub fn update_limits(&mut self, limits: RiskLimits) { self.limits = limits }
+
+    /// sets the last known price of a symbol
+    pub fn update_price(&mut self, symbol: &str, price: Price) {
+        self.prices.insert(symbol.to_string(), price);
+    }
+
+    /// starts a new day, if the time is on another day than the last update,
+    /// and checks the daily loss
+    pub fn update(&mut self, time: DateTime<Local>, deposit: &Deposit) {
+        let date = time.date_naive();
+        if self.day.is_none_or(|(day, _)| day != date) {
+            if let Ok(equity) = self.equity(deposit) {
+                self.day = Some((date, equity));
+                self.daily_loss_reached = false;
+                self.events.emit(RiskEvent::DayStarted { date, equity });
+            }
+        }
+
+        let _ = self.check_daily_loss(deposit);
+    }
+
+    /// the equity of the deposit, its cash plus the net market value of its positions
+    pub fn equity(&self, deposit: &Deposit) -> Result<Price, Error<TradingErrorKind>> {
+        Ok(self.portfolio(deposit)?.equity())
+    }
+
+    /// the loss since the start of the day relative to the equity at the start,
+    /// `None` before the first `update`
+    pub fn daily_loss(&self, deposit: &Deposit) -> Result<Option<Percent>, Error<TradingErrorKind>> {
+        let start = match self.day {
+            Some((_, start)) if start.as_f64() > 0.0 => start,
+            _ => return Ok(None),
+        };
+        let equity = self.equity(deposit)?;
+        Ok(Some(Percent::from((start - equity).as_f64() / start.as_f64())))
+    }
+
+    /// checks an action against the limits
+    ///
+    /// Actions that change an order are checked as if the changed order replaced the open one.
+    pub fn check(&mut self, action: &PlannedAction, deposit: &Deposit) -> Result<(), Error<TradingErrorKind>> {
+        let (order, replaced) = match action {
+            PlannedAction::Submit(order) => (order.clone(), None),
+            PlannedAction::Modify { order_id, changes } => {
+                let mut order = match deposit.orders().iter().find(|order| order.has_id(*order_id)) {
+                    Some(order) => order.clone(),
+                    // the executor rejects changes of unknown orders
+                    None => return Ok(()),
+                };
+                if let Some(order_data) = order.find_order_mut(*order_id) {
+                    changes.apply(order_data);
+                }
+                (order, Some(*order_id))
+            }
+            PlannedAction::Close { .. } | PlannedAction::Cancel { .. } => return Ok(()),
+        };
+        if !order.opens_positions() {
+            // changes of the take profit or the stop loss of an active bracket
+            return Ok(());
+        }
+
+        match self.violation(&order, replaced, deposit)? {
+            Some((rule, msg)) => {
+                self.events.emit(RiskEvent::Rejected { rule, msg: msg.clone() });
+                Err(Error::new(msg, TradingErrorKind::RiskLimit))
+            }
+            None => Ok(()),
+        }
+    }
+
+    /// returns the first rule the order violates
+    fn violation(&mut self, order: &Order, replaced: Option<u64>, deposit: &Deposit) -> Result<Option<(RiskRule, String)>, Error<TradingErrorKind>> {
+        if self.kill_switch.is_engaged() {
+            return Ok(Some((RiskRule::KillSwitch, "The kill switch is engaged, no orders are opened".to_string())));
+        }
+
+        if let Some(order_data) = order.data().iter().find(|order_data| self.limits.is_banned(order_data.derivative())) {
+            return Ok(Some((
+                RiskRule::BannedInstrument,
+                format!("The derivative `{}` is banned", order_data.derivative().symbol),
+            )));
+        }
+
+        if let Some(max) = self.limits.max_open_orders {
+            if replaced.is_none() && deposit.orders().len() >= max {
+                return Ok(Some((
+                    RiskRule::OpenOrders,
+                    format!("The deposit already has {} open orders, the limit is {}", deposit.orders().len(), max),
+                )));
+            }
+        }
+
+        // the open orders without the one that is replaced by the changed order,
+        // active brackets only close positions
+        let open_orders: Vec<&Order> = deposit
+            .orders()
+            .iter()
+            .filter(|open| open.opens_positions() && replaced.is_none_or(|id| !open.has_id(id)))
+            .collect();
+
+        for order_data in order.data() {
+            let derivative = order_data.derivative();
+            let pieces = deposit
+                .positions()
+                .iter()
+                .filter(|position| position.is_open() && position.derivative().symbol == derivative.symbol)
+                .map(|position| position.pieces())
+                .chain(open_orders.iter().map(|open| order_pieces(open, &derivative.symbol)))
+                .sum::<u64>() + order_pieces(order, &derivative.symbol);
+
+            if let Some(max) = self.limits.max_position_size {
+                if pieces > max {
+                    return Ok(Some((
+                        RiskRule::PositionSize,
+                        format!("The derivative `{}` would have {} pieces, the limit is {}", derivative.symbol, pieces, max),
+                    )));
+                }
+            }
+
+            if let Some(max) = self.limits.max_notional {
+                let price = match self.prices.get(&derivative.symbol) {
+                    Some(price) => *price,
+                    None => match order_data.order_type() {
+                        OrderType::LimitOrder(price) | OrderType::StopOrder(price) => *price,
+                        OrderType::MarketOrder => return Ok(Some(no_price(RiskRule::Notional, derivative))),
+                    },
+                };
+                let notional = derivative.notional(price, pieces);
+                if notional > max {
+                    return Ok(Some((
+                        RiskRule::Notional,
+                        format!(
+                            "The derivative `{}` would have a value of {:.2}, the limit is {:.2}",
+                            derivative.symbol, notional.as_f64(), max.as_f64()
+                        ),
+                    )));
+                }
+            }
+        }
+
+        if let Some(limit) = self.limits.max_daily_loss {
+            if let Some(loss) = self.check_daily_loss(deposit)? {
+                if loss >= limit {
+                    return Ok(Some((
+                        RiskRule::DailyLoss,
+                        format!(
+                            "The deposit lost {:.2}% today, the limit is {:.2}%",
+                            loss.as_f64() * 100.0, limit.as_f64() * 100.0
+                        ),
+                    )));
+                }
+            }
+        }
+
+        if let Some(max) = self.limits.max_leverage {
+            let portfolio = self.portfolio(deposit)?;
+            let mut exposure = portfolio.gross_exposure();
+            for open in open_orders.iter().copied().chain(Some(order)) {
+                match self.order_notional(open)? {
+                    Some(notional) => exposure += notional,
+                    None => return Ok(Some(no_price(RiskRule::Leverage, open.data()[0].derivative()))),
+                }
+            }
+
+            let equity = portfolio.equity();
+            if equity.as_f64() <= 0.0 {
+                return Ok(Some((RiskRule::Leverage, "The deposit has no positive equity".to_string())));
+            }
+            let leverage = exposure.as_f64() / equity.as_f64();
+            if leverage > max {
+                return Ok(Some((
+                    RiskRule::Leverage,
+                    format!("The leverage would be {:.2}, the limit is {:.2}", leverage, max),
+                )));
+            }
+        }
+
+        Ok(None)
+    }
+
+    /// returns the daily loss and emits an event the first time it reaches its limit on a day
+    fn check_daily_loss(&mut self, deposit: &Deposit) -> Result<Option<Percent>, Error<TradingErrorKind>> {
+        let limit = match self.limits.max_daily_loss {
+            Some(limit) => limit,
+            None => return Ok(None),
+        };
+        let loss = self.daily_loss(deposit)?;
+
+        if let Some(loss) = loss {
+            if loss >= limit && !self.daily_loss_reached {
+                self.daily_loss_reached = true;
+                self.events.emit(RiskEvent::DailyLossLimitReached { loss, limit });
+            }
+        }
+        Ok(loss)
+    }
+
+    fn portfolio(&self, deposit: &Deposit) -> Result<Portfolio, Error<TradingErrorKind>> {
+        let base = ExchangeRates::new(deposit.currency());
+        Portfolio::compute(Some(deposit), &self.prices, self.rates.as_ref().unwrap_or(&base))
+            .map_err(valuation_failed)
+    }
+
+    /// the gross value of an order in the currency of the deposit, `None` if a leg has no price
+    ///
+    /// One cancels the other orders count with their largest leg.
+    fn order_notional(&self, order: &Order) -> Result<Option<Price>, Error<TradingErrorKind>> {
+        let mut values = Vec::new();
+
+        for order_data in order.data() {
+            let derivative = order_data.derivative();
+            let price = match order_data.order_type() {
+                OrderType::LimitOrder(price) | OrderType::StopOrder(price) => *price,
+                OrderType::MarketOrder => match self.prices.get(&derivative.symbol) {
+                    Some(price) => *price,
+                    None => return Ok(None),
+                },
+            };
+            let notional = derivative.notional(price, order_data.pieces());
+            values.push(match &self.rates {
+                Some(rates) => rates
+                    .to_base(notional, derivative.currency)
+                    .map_err(valuation_failed)?,
+                None => notional,
+            });
+        }
+
+        Ok(Some(match order {
+            Order::OneCancelsTheOther(_) => values
+                .into_iter()
+                .fold(Price::zero(), |max, value| if value > max { value } else { max }),
+            _ => values
+                .into_iter()
+                .fold(Price::zero(), |sum, value| sum + value),
+        }))
+    }
+}
+
+/// the pieces of the order data of an order that trade the symbol,
+/// one cancels the other orders count with their largest leg
+fn order_pieces(order: &Order, symbol: &str) -> u64 {
+    let pieces = order
+        .data()
+        .iter()
+        .filter(|order_data| order_data.derivative().symbol == symbol)
+        .map(|order_data| order_data.pieces());
+
+    match order {
+        Order::OneCancelsTheOther(_) => pieces.max().unwrap_or(0),
+        _ => pieces.sum(),
+    }
+}
+
+fn no_price(rule: RiskRule, derivative: &Derivative) -> (RiskRule, String) {
+    (rule, format!("There is no price to value the derivative `{}`", derivative.symbol))
+}
+
+fn valuation_failed(error: Error<ErrorKind>) -> Error<TradingErrorKind> {
+    Error::new(format!("The deposit could not be valued: {}", error.msg()), TradingErrorKind::RiskLimit)
+}
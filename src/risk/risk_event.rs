@@ -0,0 +1,42 @@
+use std::fmt;
+use std::fmt::Formatter;
+
+use chrono::NaiveDate;
+
+use crate::{Event, EventLevel, MarketValue, Percent, Price, RiskRule};
+
+/// The events emitted by a `RiskManager`
+#[derive(Clone, Debug)]
+pub enum RiskEvent {
+    /// an action was rejected, since it would violate a rule
+    Rejected { rule: RiskRule, msg: String },
+    /// a new day started, the daily loss is measured from this equity
+    DayStarted { date: NaiveDate, equity: Price },
+    /// the loss of the day reached its limit, no orders are opened until the next day
+    DailyLossLimitReached { loss: Percent, limit: Percent },
+}
+
+impl Event for RiskEvent {
+    fn level(&self) -> EventLevel {
+        use RiskEvent::*;
+        match self {
+            Rejected { .. } => EventLevel::Warn,
+            DayStarted { .. } => EventLevel::Debug,
+            DailyLossLimitReached { .. } => EventLevel::Error,
+        }
+    }
+}
+
+impl fmt::Display for RiskEvent {
+    fn fmt(&self, formatter: &mut Formatter<'_>) -> fmt::Result {
+        use RiskEvent::*;
+        match self {
+            Rejected { rule, msg } =>
+                write!(formatter, "rejected by the {:?} rule: {}", rule, msg),
+            DayStarted { date, equity } =>
+                write!(formatter, "started {} with an equity of {:.2}", date, equity.as_f64()),
+            DailyLossLimitReached { loss, limit } =>
+                write!(formatter, "lost {:.2}% today, the limit is {:.2}%", loss.as_f64() * 100.0, limit.as_f64() * 100.0),
+        }
+    }
+}
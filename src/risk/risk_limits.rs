@@ -0,0 +1,92 @@
+use crate::{Derivative, Percent, Price};
+
+/// The limits a `RiskManager` enforces before an order is submitted
+///
+/// Every limit is optional, `None` doesn't limit anything.
+///
+/// #### Fields:
+/// * __max_position_size__: The maximal pieces of a derivative in open positions and orders
+/// * __max_notional__: The maximal value of a derivative in open positions and orders, in the
+///   currency of the derivative
+/// * __max_open_orders__: The maximal number of open orders of the deposit
+/// * __max_daily_loss__: The maximal loss of the equity since the start of the day, `0.05` for 5%
+/// * __max_leverage__: The maximal gross exposure of the positions and orders per equity
+/// * __banned__: The symbols and ISINs of derivatives that must not be traded
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct RiskLimits {
+    pub max_position_size: Option<u64>,
+    pub max_notional: Option<Price>,
+    pub max_open_orders: Option<usize>,
+    pub max_daily_loss: Option<Percent>,
+    pub max_leverage: Option<f64>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub banned: Vec<String>,
+}
+
+impl RiskLimits {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_max_position_size(mut self, pieces: u64) -> Self {
+        self.max_position_size = Some(pieces);
+        self
+    }
+
+    pub fn with_max_notional(mut self, notional: Price) -> Self {
+        self.max_notional = Some(notional);
+        self
+    }
+
+    pub fn with_max_open_orders(mut self, orders: usize) -> Self {
+        self.max_open_orders = Some(orders);
+        self
+    }
+
+    pub fn with_max_daily_loss(mut self, loss: Percent) -> Self {
+        self.max_daily_loss = Some(loss);
+        self
+    }
+
+    pub fn with_max_leverage(mut self, leverage: f64) -> Self {
+        self.max_leverage = Some(leverage);
+        self
+    }
+
+    /// bans a derivative by its symbol or ISIN
+    pub fn with_banned(mut self, identifier: &str) -> Self {
+        self.banned.push(identifier.to_string());
+        self
+    }
+
+    /// returns true if the symbol or the ISIN of the derivative is banned
+    pub fn is_banned(&self, derivative: &Derivative) -> bool {
+        self.banned.iter().any(|banned| {
+            *banned == derivative.symbol
+                || derivative.isin.as_ref().is_some_and(|isin| *banned == isin.to_string())
+        })
+    }
+}
+
+/// The rule of a `RiskManager` that rejected an action
+///
+/// #### Variants:
+/// * __KillSwitch__: The `KillSwitch` is engaged
+/// * __BannedInstrument__: The derivative is banned
+/// * __OpenOrders__: The deposit has too many open orders
+/// * __PositionSize__: There would be too many pieces of the derivative
+/// * __Notional__: The value of the derivative would be too high
+/// * __DailyLoss__: The loss of the day reached its limit
+/// * __Leverage__: The gross exposure per equity would be too high
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(rename_all = "snake_case"))]
+pub enum RiskRule {
+    KillSwitch,
+    BannedInstrument,
+    OpenOrders,
+    PositionSize,
+    Notional,
+    DailyLoss,
+    Leverage,
+}
@@ -0,0 +1,6 @@
+use serde::Serializer;
+
+/// writes an id as a string, since JavaScript can't represent every u64 as a number
+pub(crate) fn serialize_id<S: Serializer>(id: &u64, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.collect_str(id)
+}
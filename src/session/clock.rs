@@ -0,0 +1,62 @@
+use chrono::{DateTime, Duration, Local};
+
+/// The source of the current time of a trading session
+///
+/// Runners never ask the system for the time directly, so sessions can be simulated or tested
+/// with a `ManualClock`.
+pub trait Clock {
+    fn now(&self) -> DateTime<Local>;
+
+    /// blocks until the duration passed
+    fn sleep(&mut self, duration: Duration);
+}
+
+/// The real time of the system
+#[derive(Copy, Clone, Debug, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> DateTime<Local> {
+        Local::now()
+    }
+
+    fn sleep(&mut self, duration: Duration) {
+        if let Ok(duration) = duration.to_std() {
+            std::thread::sleep(duration);
+        }
+    }
+}
+
+/// A clock that only moves when it's told to
+///
+/// Sleeping advances the clock immediately, so a whole session runs without waiting.
+#[derive(Copy, Clone, Debug)]
+pub struct ManualClock {
+    now: DateTime<Local>,
+}
+
+impl ManualClock {
+    pub fn new(start: DateTime<Local>) -> Self {
+        Self {
+            now: start
+        }
+    }
+
+    pub fn advance(&mut self, duration: Duration) {
+        self.now += duration;
+    }
+
+    pub fn set(&mut self, time: DateTime<Local>) {
+        self.now = time;
+    }
+}
+
+impl Clock for ManualClock {
+    fn now(&self) -> DateTime<Local> {
+        self.now
+    }
+
+    fn sleep(&mut self, duration: Duration) {
+        self.advance(duration);
+    }
+}
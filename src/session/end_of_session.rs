@@ -0,0 +1,99 @@
+use std::fmt;
+use std::fmt::Formatter;
+
+use crate::{Error, ExecutionReport, Position, StopLoss, TradingErrorKind};
+
+/// What happens to the positions that are still open after `shutdown` returned
+///
+/// The policy is applied by the `Runner` to every remaining position of the deposit.
+///
+/// #### Variants:
+/// * __KeepOpen__: The positions stay open, the user takes care of them (default)
+/// * __Liquidate__: All positions are sold at market
+/// * __ProtectiveStop__: Every position gets a stop order at the stop loss, so the positions stay
+///   open but the losses are limited. Relative stop losses are relative to the last close.
+/// * __HandOver__: The positions stay open for another algorithm, which continues the session
+///   with `Runner::hand_over`
+#[derive(Clone, Debug, Default, PartialEq)]
+pub enum EndOfSession {
+    #[default]
+    KeepOpen,
+    Liquidate,
+    ProtectiveStop(StopLoss),
+    HandOver,
+}
+
+impl fmt::Display for EndOfSession {
+    fn fmt(&self, formatter: &mut Formatter<'_>) -> fmt::Result {
+        use EndOfSession::*;
+        match self {
+            KeepOpen => write!(formatter, "keep open"),
+            Liquidate => write!(formatter, "liquidate"),
+            ProtectiveStop(stop_loss) => write!(formatter, "protective stop ({:?})", stop_loss),
+            HandOver => write!(formatter, "hand over"),
+        }
+    }
+}
+
+/// What the end of session policy did with a single position
+#[derive(Clone, Debug)]
+pub enum PositionOutcome {
+    KeptOpen,
+    Liquidated,
+    Protected,
+    HandedOver,
+    /// the policy could not be applied, the position is still open
+    Failed(Error<TradingErrorKind>),
+}
+
+impl PositionOutcome {
+    pub fn is_failed(&self) -> bool {
+        matches!(self, PositionOutcome::Failed(_))
+    }
+}
+
+/// The report of an applied end of session policy
+///
+/// #### Fields:
+/// * __policy__: The applied policy
+/// * __positions__: Every position that was open after `shutdown` and what was done with it
+/// * __execution__: The execution report of the instructions that were needed
+#[derive(Clone, Debug, Default)]
+pub struct EndOfSessionReport {
+    pub policy: EndOfSession,
+    pub positions: Vec<(Position, PositionOutcome)>,
+    pub execution: ExecutionReport,
+}
+
+impl EndOfSessionReport {
+    /// returns true if the policy was applied to all positions
+    pub fn is_success(&self) -> bool {
+        !self.positions
+             .iter()
+             .any(|(_, outcome)| outcome.is_failed())
+    }
+
+    /// returns the positions that are still open after the policy was applied
+    pub fn open_positions(&self) -> impl Iterator<Item=&Position> {
+        self.positions
+            .iter()
+            .filter(|(_, outcome)| !matches!(outcome, PositionOutcome::Liquidated))
+            .map(|(position, _)| position)
+    }
+}
+
+impl fmt::Display for EndOfSessionReport {
+    fn fmt(&self, formatter: &mut Formatter<'_>) -> fmt::Result {
+        writeln!(formatter, "end of session: {}", self.policy)?;
+        for (position, outcome) in &self.positions {
+            match outcome {
+                PositionOutcome::KeptOpen => writeln!(formatter, "\t{}: kept open", position.id)?,
+                PositionOutcome::Liquidated => writeln!(formatter, "\t{}: liquidated", position.id)?,
+                PositionOutcome::Protected => writeln!(formatter, "\t{}: protected", position.id)?,
+                PositionOutcome::HandedOver => writeln!(formatter, "\t{}: handed over", position.id)?,
+                PositionOutcome::Failed(error) => writeln!(formatter, "\t{}: failed ({})", position.id, error.msg())?,
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,11 @@
+pub use clock::*;
+pub use end_of_session::*;
+pub use runner::*;
+pub use session_event::*;
+pub use stop_handle::*;
+
+pub mod clock;
+pub mod end_of_session;
+pub mod runner;
+pub mod session_event;
+pub mod stop_handle;
@@ -0,0 +1,613 @@
+use chrono::{DateTime, Duration, Local};
+
+use crate::{AlignedSeries, AlgorithmInstance, AlgorithmInterface, Bracket, BrokerInterface, Candle, Clock, Deposit, Derivative, EndOfSession, EndOfSessionReport, Error, EventBus, ExecutionPhase, ExecutionPlan, ExecutionReport, Executor, FeedUpdate, Instruction, MarketContext, MultiAssetContext, Order, OrderData, OrderEvent, OrderMoment, OrderType, OrderValidity, PlannedAction, Position, PositionOutcome, PriceFeed, SeriesAligner, SessionEvent, StockExchange, StopHandle, StopLoss, SystemClock, TakeProfit, TradingErrorKind, TrailingStopEngine};
+
+/// The longest time the runner sleeps at once, so a stop request is noticed quickly
+const MAX_SLEEP_MILLIS: i64 = 100;
+
+/// What happened during a trading session
+///
+/// #### Fields:
+/// * __steps__: The amount of time steps with new prices
+/// * __warm_up_steps__: The amount of steps in which the algorithm only collected prices
+/// * __trading__: The merged execution reports of all trading steps
+/// * __shutdown__: The execution report of `shutdown`
+/// * __end_of_session__: What the end of session policy did with the remaining positions
+/// * __error__: The error that ended the session early
+#[derive(Clone, Debug, Default)]
+pub struct RunReport {
+    pub steps: u64,
+    pub warm_up_steps: u64,
+    pub trading: ExecutionReport,
+    pub shutdown: ExecutionReport,
+    pub end_of_session: EndOfSessionReport,
+    pub error: Option<Error<TradingErrorKind>>,
+}
+
+/// Runs an algorithm in its time steps
+///
+/// The runner follows the lifecycle of the `AlgorithmInterface`:
+/// * `init` is called once with the derivative and the time steps
+/// * in every time step the runner polls the `PriceFeed` for new candles
+/// * while there are less than `min_data_length` candles `collect_prices` is called
+/// * afterwards `trade` is called with a window of at most `max_data_length` candles
+///   (0 means unlimited) and the instructions are executed by the `Executor`
+/// * the new candles and the time of every step are passed to the `Executor`, so its
+///   `RiskManager` values the positions at the last prices and checks the daily loss. An
+///   executor with a `MatchingEngine` fills the open orders with the new candles before the
+///   algorithm trades, so a session can be simulated with any broker, see
+///   `Executor::with_matching_engine`
+/// * the stops of positions with a `StopLoss::Trailing` follow the new candles with a
+///   `TrailingStopEngine`, the executor moves the stops of brackets and closes the other
+///   positions once their stop was crossed, see `Runner::with_trailing_stops`
+/// * when the session is stopped `shutdown` is called and the `EndOfSession` policy is applied
+///   to the remaining positions, the `EndOfSessionReport` tells what was done with each of them
+///
+/// If `AlgorithmInterface::as_multi_asset` returns Some, the runner drives the
+/// `MultiAssetAlgorithmInterface` instead. It subscribes to the derivative and to the derivatives
+/// of `Runner::with_derivatives` and aligns their candles by timestamp with a `SeriesAligner`.
+/// The data lengths count the aligned timestamps, a step only has new prices once a timestamp is
+/// complete for all derivatives.
+///
+/// The session ends when the `StopHandle` is used, the end time is reached, or the algorithm
+/// returned an error. If `trade` takes longer than a time step its instructions are dropped and
+/// the session ends as well.
+pub struct Runner<A, B, P, C = SystemClock> {
+    algorithm: A,
+    broker: B,
+    feed: P,
+    clock: C,
+
+    deposit: Deposit,
+    derivative: Derivative,
+    derivatives: Vec<Derivative>,
+    time_steps: Duration,
+    end: Option<DateTime<Local>>,
+
+    min_data_length: u64,
+    max_data_length: u64,
+    candles: Vec<Candle>,
+    aligner: SeriesAligner,
+
+    executor: Executor,
+    trailing_stops: TrailingStopEngine,
+    end_of_session: EndOfSession,
+    stop: StopHandle,
+    events: EventBus<SessionEvent>,
+}
+
+impl<A: AlgorithmInterface, B: BrokerInterface, P: PriceFeed> Runner<A, B, P> {
+    /// creates a runner that uses the system time
+    ///
+    /// Orders are placed on the first stock exchange of the broker, use `Runner::with_executor`
+    /// to change it.
+    pub fn new(algorithm: A, broker: B, feed: P, deposit: Deposit, derivative: Derivative, time_steps: Duration) -> Self {
+        let stock_exchange = B::STOCK_EXCHANGES
+            .first()
+            .copied()
+            .unwrap_or(StockExchange::NYSE);
+
+        Self {
+            algorithm,
+            broker,
+            feed,
+            clock: SystemClock,
+            deposit,
+            derivatives: vec![derivative.clone()],
+            aligner: SeriesAligner::new(&[&derivative.symbol]),
+            derivative,
+            time_steps,
+            end: None,
+            min_data_length: 0,
+            max_data_length: 0,
+            candles: Vec::new(),
+            executor: Executor::new(stock_exchange),
+            trailing_stops: TrailingStopEngine::new(),
+            end_of_session: EndOfSession::default(),
+            stop: StopHandle::new(),
+            events: EventBus::new(),
+        }
+    }
+}
+
+impl<B: BrokerInterface, P: PriceFeed> Runner<AlgorithmInstance, B, P> {
+    /// creates a runner for a loaded algorithm that uses its data lengths
+    pub fn from_instance(instance: AlgorithmInstance, broker: B, feed: P, deposit: Deposit, derivative: Derivative, time_steps: Duration) -> Self {
+        let (min_data_length, max_data_length) = (instance.min_data_length(), instance.max_data_length());
+        Self::new(instance, broker, feed, deposit, derivative, time_steps)
+            .with_data_length(min_data_length, max_data_length)
+    }
+}
+
+impl<A: AlgorithmInterface, B: BrokerInterface, P: PriceFeed, C: Clock> Runner<A, B, P, C> {
+    /// replaces the clock, for example with a `ManualClock` for simulations and tests
+    pub fn with_clock<T: Clock>(self, clock: T) -> Runner<A, B, P, T> {
+        Runner {
+            algorithm: self.algorithm,
+            broker: self.broker,
+            feed: self.feed,
+            clock,
+            deposit: self.deposit,
+            derivative: self.derivative,
+            derivatives: self.derivatives,
+            time_steps: self.time_steps,
+            end: self.end,
+            min_data_length: self.min_data_length,
+            max_data_length: self.max_data_length,
+            candles: self.candles,
+            aligner: self.aligner,
+            executor: self.executor,
+            trailing_stops: self.trailing_stops,
+            end_of_session: self.end_of_session,
+            stop: self.stop,
+            events: self.events,
+        }
+    }
+
+    /// continues the session with another algorithm
+    ///
+    /// The successor gets the deposit, including the positions that were kept open by
+    /// `EndOfSession::HandOver`, and the collected candles, so it only needs to warm up if it
+    /// requires more data. The clock, the price feed, the executor and the event bus are kept,
+    /// the data lengths, the end time and the end of session policy can be changed with the
+    /// builder methods before `Runner::run` is called again.
+    pub fn hand_over<N: AlgorithmInterface>(self, algorithm: N) -> Runner<N, B, P, C> {
+        Runner {
+            algorithm,
+            broker: self.broker,
+            feed: self.feed,
+            clock: self.clock,
+            deposit: self.deposit,
+            derivative: self.derivative,
+            derivatives: self.derivatives,
+            time_steps: self.time_steps,
+            end: self.end,
+            min_data_length: self.min_data_length,
+            max_data_length: self.max_data_length,
+            candles: self.candles,
+            aligner: self.aligner,
+            executor: self.executor,
+            trailing_stops: self.trailing_stops,
+            end_of_session: EndOfSession::default(),
+            stop: StopHandle::new(),
+            events: self.events,
+        }
+    }
+
+    pub fn with_data_length(mut self, min_data_length: u64, max_data_length: u64) -> Self {
+        self.min_data_length = min_data_length;
+        self.max_data_length = max_data_length;
+        self
+    }
+
+    /// adds the derivatives a multi asset algorithm trades besides the derivative of the runner
+    pub fn with_derivatives(mut self, derivatives: Vec<Derivative>) -> Self {
+        self.derivatives.extend(derivatives);
+        let symbols = self.derivatives
+            .iter()
+            .map(|derivative| derivative.symbol.as_str())
+            .collect::<Vec<_>>();
+        self.aligner = SeriesAligner::new(&symbols);
+        self
+    }
+
+    /// fills the window with older candles, for example from `DataStore::read_last`
+    ///
+    /// The candles count towards `min_data_length`, so the algorithm can trade right away.
+    pub fn with_history(mut self, candles: Vec<Candle>) -> Self {
+        for candle in candles {
+            self.push_candle(candle);
+        }
+        self
+    }
+
+    /// stops the session after the last time step before `end`
+    pub fn with_end(mut self, end: DateTime<Local>) -> Self {
+        self.end = Some(end);
+        self
+    }
+
+    pub fn with_end_of_session(mut self, end_of_session: EndOfSession) -> Self {
+        self.end_of_session = end_of_session;
+        self
+    }
+
+    pub fn with_executor(mut self, executor: Executor) -> Self {
+        self.executor = executor;
+        self
+    }
+
+    /// trails the stops with the engine, for example one that emits its events on an existing bus
+    /// or that tracks positions without a `StopLoss::Trailing`
+    pub fn with_trailing_stops(mut self, trailing_stops: TrailingStopEngine) -> Self {
+        self.trailing_stops = trailing_stops;
+        self
+    }
+
+    /// uses an existing bus for the session events
+    pub fn with_events(mut self, events: &EventBus<SessionEvent>) -> Self {
+        self.events = events.clone();
+        self
+    }
+
+    pub fn algorithm(&self) -> &A { &self.algorithm }
+    pub fn broker(&self) -> &B { &self.broker }
+    pub fn clock(&self) -> &C { &self.clock }
+    pub fn deposit(&self) -> &Deposit { &self.deposit }
+    pub fn derivative(&self) -> &Derivative { &self.derivative }
+    /// the derivative of the runner and the derivatives of `Runner::with_derivatives`
+    pub fn derivatives(&self) -> &[Derivative] { &self.derivatives }
+    pub fn time_steps(&self) -> Duration { self.time_steps }
+    pub fn candles(&self) -> &[Candle] { &self.candles }
+    /// the aligned candles of a multi asset algorithm
+    pub fn aligned_series(&self) -> &AlignedSeries { self.aligner.series() }
+    pub fn executor(&self) -> &Executor { &self.executor }
+    pub fn trailing_stops(&self) -> &TrailingStopEngine { &self.trailing_stops }
+    pub fn events(&self) -> &EventBus<SessionEvent> { &self.events }
+
+    /// returns a handle that stops the session gracefully
+    pub fn stop_handle(&self) -> StopHandle {
+        self.stop.clone()
+    }
+
+    /// runs the session until it is stopped
+    ///
+    /// An error is only returned if a derivative can't be subscribed or `init` failed, errors
+    /// during the session end it early and are part of the report.
+    pub fn run(&mut self) -> Result<RunReport, Error<TradingErrorKind>> {
+        for derivative in &self.derivatives {
+            self.feed.subscribe(derivative).map_err(|error| Error::new(
+                format!("Could not subscribe to `{}`: {}", derivative.symbol, error.msg()),
+                TradingErrorKind::PriceFeed,
+            ))?;
+        }
+        match self.algorithm.as_multi_asset() {
+            Some(algorithm) => algorithm.init(&self.derivatives, self.time_steps)?,
+            None => self.algorithm.init(&self.derivative, self.time_steps)?,
+        }
+        self.events.emit(SessionEvent::Started {
+            derivative: self.derivative.symbol.clone(),
+            time_steps: self.time_steps,
+        });
+
+        let mut report = RunReport::default();
+        let mut next_step = self.clock.now();
+
+        loop {
+            if self.end.is_some_and(|end| next_step > end) || !self.wait_until(next_step) {
+                break;
+            }
+
+            let time = self.clock.now();
+            if let Err(error) = self.step(time, &mut report) {
+                self.events.emit(SessionEvent::AlgorithmFailed { error: error.clone() });
+                report.error = Some(error);
+                break;
+            }
+
+            next_step += self.time_steps;
+        }
+
+        self.finish(&mut report);
+        Ok(report)
+    }
+
+    /// waits until the time of the next step, returns false if the session was stopped
+    fn wait_until(&mut self, time: DateTime<Local>) -> bool {
+        loop {
+            if self.stop.is_stopped() {
+                return false;
+            }
+
+            let remaining = time - self.clock.now();
+            if remaining <= Duration::zero() {
+                return true;
+            }
+            self.clock.sleep(remaining.min(Duration::milliseconds(MAX_SLEEP_MILLIS)));
+        }
+    }
+
+    fn step(&mut self, time: DateTime<Local>, report: &mut RunReport) -> Result<(), Error<TradingErrorKind>> {
+        let updates = match self.feed.poll(time) {
+            Ok(updates) => updates,
+            Err(error) => {
+                self.events.emit(SessionEvent::FeedFailed { error });
+                return Ok(());
+            }
+        };
+        let mut new_candles = Vec::new();
+        for update in &updates {
+            let new_candle = self.executor
+                .last_candle(&update.symbol)
+                .is_none_or(|last| update.candle.time > last.time);
+            if !new_candle {
+                continue;
+            }
+            self.executor.update_candle(&update.symbol, &update.candle);
+
+            let matched = self.executor.match_orders(&self.broker, &mut self.deposit, &mut self.algorithm, &self.derivative, &update.symbol, &update.candle);
+            report.trading.merge(matched);
+            new_candles.push((update.symbol.clone(), update.candle));
+        }
+        let multi_asset = self.algorithm.as_multi_asset().is_some();
+        let new_prices = if multi_asset {
+            self.push_aligned(updates)
+        } else {
+            self.push_updates(updates)
+        };
+        for (symbol, candle) in new_candles {
+            self.trail_stops(&symbol, candle, report);
+        }
+        if !new_prices {
+            self.events.emit(SessionEvent::NoData { time });
+            return Ok(());
+        }
+        report.steps += 1;
+        self.executor.update(time, &self.deposit);
+
+        let collected = if multi_asset { self.aligner.series().len() } else { self.candles.len() } as u64;
+        if collected < self.min_data_length {
+            report.warm_up_steps += 1;
+            self.events.emit(SessionEvent::WarmingUp {
+                collected,
+                required: self.min_data_length,
+            });
+            return match self.algorithm.as_multi_asset() {
+                Some(algorithm) => algorithm.collect_prices(self.aligner.series()),
+                None => self.algorithm.collect_prices(&Candle::closes(&self.candles)),
+            };
+        }
+
+        let started = self.clock.now();
+        let plan = {
+            let instructions = match self.algorithm.as_multi_asset() {
+                Some(algorithm) => {
+                    let context = MultiAssetContext::new(time, &self.derivatives, self.aligner.series())
+                        .with_deposit(&self.deposit);
+                    algorithm.trade(&context)?
+                }
+                None => {
+                    let context = MarketContext::new(time, &self.derivative, &self.candles)
+                        .with_deposit(&self.deposit)
+                        .with_stock_exchange(self.executor.stock_exchange());
+                    self.algorithm.trade(&context)?
+                }
+            };
+
+            let took = self.clock.now() - started;
+            if took > self.time_steps {
+                return Err(Error::new(
+                    format!(
+                        "The algorithm took {}ms, but the time steps are {}ms",
+                        took.num_milliseconds(), self.time_steps.num_milliseconds()
+                    ),
+                    TradingErrorKind::StepTimeout,
+                ));
+            }
+
+            self.executor.plan::<B>(instructions, &self.derivative, ExecutionPhase::Trading)
+        };
+
+        let step_report = self.executor.execute(
+            &self.broker,
+            &mut self.deposit,
+            &mut self.algorithm,
+            &self.derivative,
+            plan,
+            ExecutionPhase::Trading,
+        );
+        self.events.emit(SessionEvent::Traded {
+            time,
+            submitted: step_report.submitted.len(),
+            errors: step_report.errors.len(),
+        });
+        report.trading.merge(step_report);
+
+        Ok(())
+    }
+
+    /// follows a new candle with the trailing stops of the symbol and executes their exits
+    ///
+    /// The earlier candles of the window are used by trails with the average true range.
+    fn trail_stops(&mut self, symbol: &str, candle: Candle, report: &mut RunReport) {
+        let window = match self.algorithm.as_multi_asset() {
+            Some(_) => self.aligner.series().candles(symbol),
+            None => Some(&self.candles[..]).filter(|_| symbol == self.derivative.symbol),
+        };
+        let candles = window
+            .filter(|candles| candles.last().map(|last| last.time) == Some(candle.time))
+            .unwrap_or(std::slice::from_ref(&candle));
+
+        let exits = self.trailing_stops.update(&self.deposit, symbol, candles);
+        if exits.is_empty() {
+            return;
+        }
+        let execution = self.executor.execute(
+            &self.broker,
+            &mut self.deposit,
+            &mut self.algorithm,
+            &self.derivative,
+            ExecutionPlan::new(exits),
+            ExecutionPhase::Trading,
+        );
+        report.trading.merge(execution);
+    }
+
+    /// adds the candles of the derivative, returns true if there was a new one
+    fn push_updates(&mut self, updates: Vec<FeedUpdate>) -> bool {
+        let collected = self.candles.last().map(|candle| candle.time);
+        for update in updates {
+            if update.symbol == self.derivative.symbol {
+                self.push_candle(update.candle);
+            }
+        }
+        self.candles.last().map(|candle| candle.time) != collected
+    }
+
+    /// aligns the candles of all derivatives, returns true if a timestamp was completed
+    fn push_aligned(&mut self, updates: Vec<FeedUpdate>) -> bool {
+        let mut completed = false;
+        for update in updates {
+            completed |= self.aligner.push(&update.symbol, update.candle);
+        }
+        self.aligner
+            .series_mut()
+            .truncate_front(self.max_data_length as usize);
+        completed
+    }
+
+    fn push_candle(&mut self, candle: Candle) {
+        // a feed can return candles that are already part of the history
+        if self.candles.last().is_some_and(|last| candle.time <= last.time) {
+            return;
+        }
+        self.candles.push(candle);
+
+        let max_data_length = self.max_data_length as usize;
+        if max_data_length != 0 && self.candles.len() > max_data_length {
+            let remove = self.candles.len() - max_data_length;
+            self.candles.drain(..remove);
+        }
+    }
+
+    fn finish(&mut self, report: &mut RunReport) {
+        self.events.emit(SessionEvent::ShuttingDown { time: self.clock.now() });
+
+        match self.shutdown() {
+            Ok(shutdown) => report.shutdown = shutdown,
+            Err(error) => {
+                self.events.emit(SessionEvent::AlgorithmFailed { error: error.clone() });
+                report.error.get_or_insert(error);
+            }
+        }
+
+        report.end_of_session = self.apply_end_of_session();
+        self.events.emit(SessionEvent::Finished { steps: report.steps });
+    }
+
+    /// calls `shutdown` of the algorithm and executes its instructions
+    fn shutdown(&mut self) -> Result<ExecutionReport, Error<TradingErrorKind>> {
+        let plan = match self.algorithm.as_multi_asset() {
+            Some(algorithm) => {
+                let context = MultiAssetContext::new(self.clock.now(), &self.derivatives, self.aligner.series())
+                    .with_deposit(&self.deposit);
+                let instructions = algorithm.shutdown(&context)?;
+                self.executor.plan::<B>(instructions, &self.derivative, ExecutionPhase::Shutdown)
+            }
+            None => {
+                let prices = Candle::closes(&self.candles);
+                return self.executor.shutdown(&self.broker, &mut self.deposit, &mut self.algorithm, &self.derivative, &prices);
+            }
+        };
+
+        Ok(self.executor.execute(&self.broker, &mut self.deposit, &mut self.algorithm, &self.derivative, plan, ExecutionPhase::Shutdown))
+    }
+
+    fn apply_end_of_session(&mut self) -> EndOfSessionReport {
+        let policy = self.end_of_session.clone();
+        let mut report = EndOfSessionReport {
+            policy: policy.clone(),
+            ..EndOfSessionReport::default()
+        };
+
+        for position in self.deposit.positions().clone() {
+            let (plan, outcome) = match &policy {
+                EndOfSession::KeepOpen => {
+                    report.positions.push((position, PositionOutcome::KeptOpen));
+                    continue;
+                }
+                EndOfSession::HandOver => {
+                    report.positions.push((position, PositionOutcome::HandedOver));
+                    continue;
+                }
+                EndOfSession::Liquidate => {
+                    let instruction = Instruction::Sell { position: &position };
+                    let plan = self.executor.plan::<B>(std::slice::from_ref(&instruction), &self.derivative, ExecutionPhase::Shutdown);
+                    (plan, PositionOutcome::Liquidated)
+                }
+                EndOfSession::ProtectiveStop(stop_loss) => match self.protection(&position, stop_loss) {
+                    Ok(exits) => {
+                        let plan = ExecutionPlan::new(vec![PlannedAction::Submit(Order::Bracket(exits))]);
+                        (plan, PositionOutcome::Protected)
+                    }
+                    Err(error) => {
+                        report.positions.push((position, PositionOutcome::Failed(error)));
+                        continue;
+                    }
+                },
+            };
+
+            let execution = self.executor.execute(
+                &self.broker,
+                &mut self.deposit,
+                &mut self.algorithm,
+                &self.derivative,
+                plan,
+                ExecutionPhase::Shutdown,
+            );
+
+            let outcome = match failure(&execution) {
+                Some(error) => PositionOutcome::Failed(error),
+                None => outcome,
+            };
+            report.positions.push((position, outcome));
+            report.execution.merge(execution);
+        }
+
+        self.events.emit(SessionEvent::EndOfSessionApplied {
+            policy,
+            positions: report.positions.len(),
+        });
+        report
+    }
+
+    /// an active `Bracket` for the position whose only exit is a stop order at the stop loss
+    ///
+    /// The exit closes all pieces of the position, the executor places it like the stop loss of
+    /// any other bracket. Relative stop losses are relative to the last close of the derivative
+    /// of the position.
+    fn protection(&self, position: &Position, stop_loss: &StopLoss) -> Result<Bracket, Error<TradingErrorKind>> {
+        let symbol = &position.derivative().symbol;
+        let close = self.executor
+            .last_candle(symbol)
+            .or_else(|| self.candles.last().filter(|_| *symbol == self.derivative.symbol))
+            .map(|candle| candle.close)
+            .ok_or_else(|| Error::new(
+                format!("The position `{}` can't be protected without a price of `{}`", position.id, symbol),
+                TradingErrorKind::InvalidOrder,
+            ))?;
+        let level = stop_loss.level(close).ok_or_else(|| Error::new(
+            format!("The position `{}` can't be protected without a stop loss", position.id),
+            TradingErrorKind::InvalidOrder,
+        ))?;
+
+        let mut bracket = Bracket::new(OrderData::new(
+            format!("{}-protective", position.id),
+            position.derivative().clone(),
+            self.executor.stock_exchange(),
+            position.pieces(),
+            OrderType::StopOrder(level),
+            position.position_type(),
+            TakeProfit::None,
+            stop_loss.clone(),
+            OrderMoment::Instant,
+            OrderValidity::Forever,
+        ));
+        bracket.activate(position, close);
+        Ok(bracket)
+    }
+}
+
+/// returns the first error of an execution, including errors of the broker
+fn failure(execution: &ExecutionReport) -> Option<Error<TradingErrorKind>> {
+    if let Some(error) = execution.errors.first() {
+        return Some(error.clone());
+    }
+
+    execution.events
+             .iter()
+             .find_map(|event| match event {
+                 OrderEvent::BrokerError { error } | OrderEvent::Rejected { error, .. } => Some(error.clone().into()),
+                 _ => None
+             })
+}
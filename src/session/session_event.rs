@@ -0,0 +1,68 @@
+use std::fmt;
+use std::fmt::Formatter;
+
+use chrono::{DateTime, Duration, Local};
+
+use crate::{EndOfSession, Error, ErrorKind, Event, EventLevel, TradingErrorKind};
+
+/// The events emitted while a trading session is running
+#[derive(Clone, Debug)]
+pub enum SessionEvent {
+    /// the algorithm was initialised and the session started
+    Started { derivative: String, time_steps: Duration },
+    /// the algorithm got prices, but not enough to trade yet
+    WarmingUp { collected: u64, required: u64 },
+    /// the algorithm traded
+    Traded { time: DateTime<Local>, submitted: usize, errors: usize },
+    /// the price feed had no new candle
+    NoData { time: DateTime<Local> },
+    /// the price feed returned an error, the step was skipped
+    FeedFailed { error: Error<ErrorKind> },
+    /// the algorithm returned an error or took too long, the session will be shut down
+    AlgorithmFailed { error: Error<TradingErrorKind> },
+    /// `shutdown` of the algorithm will be called
+    ShuttingDown { time: DateTime<Local> },
+    /// the end of session policy was applied to the remaining positions
+    EndOfSessionApplied { policy: EndOfSession, positions: usize },
+    /// the session is over
+    Finished { steps: u64 },
+}
+
+impl Event for SessionEvent {
+    fn level(&self) -> EventLevel {
+        use SessionEvent::*;
+        match self {
+            Started { .. } | ShuttingDown { .. } | EndOfSessionApplied { .. } | Finished { .. } => EventLevel::Info,
+            WarmingUp { .. } | Traded { .. } => EventLevel::Debug,
+            NoData { .. } => EventLevel::Trace,
+            FeedFailed { .. } => EventLevel::Warn,
+            AlgorithmFailed { .. } => EventLevel::Error,
+        }
+    }
+}
+
+impl fmt::Display for SessionEvent {
+    fn fmt(&self, formatter: &mut Formatter<'_>) -> fmt::Result {
+        use SessionEvent::*;
+        match self {
+            Started { derivative, time_steps } =>
+                write!(formatter, "started trading `{}` every {}s", derivative, time_steps.num_seconds()),
+            WarmingUp { collected, required } =>
+                write!(formatter, "warming up ({}/{} prices)", collected, required),
+            Traded { time, submitted, errors } =>
+                write!(formatter, "traded at {}: {} orders submitted, {} errors", time, submitted, errors),
+            NoData { time } =>
+                write!(formatter, "no new prices at {}", time),
+            FeedFailed { error } =>
+                write!(formatter, "the price feed failed: {}", error.msg()),
+            AlgorithmFailed { error } =>
+                write!(formatter, "the algorithm failed: {}", error.msg()),
+            ShuttingDown { time } =>
+                write!(formatter, "shutting down at {}", time),
+            EndOfSessionApplied { policy, positions } =>
+                write!(formatter, "applied `{}` to {} remaining positions", policy, positions),
+            Finished { steps } =>
+                write!(formatter, "finished after {} steps", steps),
+        }
+    }
+}
@@ -1,6 +1,67 @@
+use chrono::NaiveTime;
+
+use crate::{MarketValue, Price, RoundingMode, TradingHours};
+
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum StockExchange {
     NASDAQ,
     NYSE,
     LSExchange,
 }
+
+impl StockExchange {
+    /// returns the smallest price step at the given price
+    ///
+    /// * __NASDAQ__, __NYSE__: 0.01 from 1.00 on, 0.0001 below (Reg NMS Rule 612)
+    /// * __LSExchange__: a simplified version of the MiFID II table of the most liquid shares,
+    ///   from 0.0001 below 1 up to 1 from 5000 on
+    pub fn tick_size(&self, price: Price) -> Price {
+        use StockExchange::*;
+
+        let price = price.as_f64().abs();
+        let tick = match self {
+            NASDAQ | NYSE => if price < 1.0 { 0.0001 } else { 0.01 },
+            LSExchange => match price {
+                p if p < 1.0 => 0.0001,
+                p if p < 5.0 => 0.0005,
+                p if p < 10.0 => 0.001,
+                p if p < 50.0 => 0.005,
+                p if p < 100.0 => 0.01,
+                p if p < 500.0 => 0.05,
+                p if p < 1_000.0 => 0.1,
+                p if p < 5_000.0 => 0.5,
+                _ => 1.0,
+            },
+        };
+
+        Price::from(tick)
+    }
+
+    /// rounds the price to a valid price of the exchange
+    /// ```
+    /// # use trading_utils::{Price, RoundingMode, StockExchange};
+    /// let price = StockExchange::NYSE.round_to_tick(Price::from(12.3456), RoundingMode::HalfUp);
+    /// assert_eq!(price, Price::from(12.35));
+    ///
+    /// let price = StockExchange::LSExchange.round_to_tick(Price::from(123.48), RoundingMode::Down);
+    /// assert_eq!(price, Price::from(123.45));
+    /// ```
+    pub fn round_to_tick(&self, price: Price, mode: RoundingMode) -> Price {
+        price.round_to_tick(self.tick_size(price), mode)
+    }
+
+    /// returns the regular trading hours in standard time
+    ///
+    /// * __NASDAQ__, __NYSE__: 09:30 to 16:00 in New York (UTC-5)
+    /// * __LSExchange__: 07:30 to 23:00 in Germany (UTC+1)
+    pub fn trading_hours(&self) -> TradingHours {
+        use StockExchange::*;
+
+        let time = |hour, minute| NaiveTime::from_hms_opt(hour, minute, 0).unwrap();
+        match self {
+            NASDAQ | NYSE => TradingHours::new(time(9, 30), time(16, 0), -5 * 3600),
+            LSExchange => TradingHours::new(time(7, 30), time(23, 0), 3600),
+        }
+    }
+}
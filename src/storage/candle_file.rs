@@ -0,0 +1,252 @@
+//! The binary format of the candle files of a `DataStore`
+//!
+//! A file starts with a header, followed by any number of blocks. Every append writes a new
+//! block, so existing data is never rewritten.
+//!
+//! * header: the magic bytes `TUCANDLE`, the format version (u16) and 2 reserved bytes
+//! * block header: the amount of candles (u32), the time of the first and the last candle
+//!   (i64 nanoseconds since the unix epoch) and the CRC-32 of the block header fields before
+//!   it and the payload (u32)
+//! * payload: the columns time (i64), open, high, low, close (f64) and volume (u64)
+//!
+//! All numbers are little endian.
+
+use std::convert::TryInto;
+use std::fs::{File, OpenOptions};
+use std::io::{ErrorKind as IoErrorKind, Read, Seek, SeekFrom, Write};
+use std::path::Path;
+
+use chrono::{DateTime, Local, TimeZone};
+
+use crate::{Candle, Error, ErrorKind, Price};
+
+const MAGIC: &[u8; 8] = b"TUCANDLE";
+const VERSION: u16 = 2;
+const HEADER_SIZE: u64 = 12;
+const BLOCK_HEADER_SIZE: u64 = 24;
+const CANDLE_SIZE: u64 = 48;
+
+/// The position and the time range of a block inside of a file
+#[derive(Copy, Clone, Debug)]
+pub(crate) struct BlockInfo {
+    pub offset: u64,
+    pub count: u32,
+    pub first: i64,
+    pub last: i64,
+}
+
+/// reads the headers of all blocks of a file
+pub(crate) fn scan(path: &Path) -> Result<Vec<BlockInfo>, Error<ErrorKind>> {
+    let mut file = File::open(path)?;
+    let length = file.metadata()?.len();
+    read_header(&mut file, path)?;
+
+    let mut blocks = Vec::new();
+    read_block_headers(&mut file, path, length, &mut blocks)?;
+    Ok(blocks)
+}
+
+/// truncates a file after its last valid block and returns the amount of removed bytes
+///
+/// An interrupted append leaves a truncated block or a block with a wrong checksum at the end of
+/// the file, only these blocks at the end are removed.
+pub(crate) fn repair(path: &Path) -> Result<u64, Error<ErrorKind>> {
+    let mut file = OpenOptions::new().read(true).write(true).open(path)?;
+    let length = file.metadata()?.len();
+    let end = if length < HEADER_SIZE {
+        0
+    } else {
+        read_header(&mut file, path)?;
+        // a truncated block is removed with the blocks behind it
+        let mut blocks = Vec::new();
+        match read_block_headers(&mut file, path, length, &mut blocks) {
+            Err(error) if error.kind() != ErrorKind::InvalidData => return Err(error),
+            _ => {}
+        }
+        loop {
+            match blocks.pop() {
+                Some(block) => match read_block(path, &block) {
+                    Ok(_) => break block.offset + BLOCK_HEADER_SIZE + block.count as u64 * CANDLE_SIZE,
+                    Err(error) if error.kind() == ErrorKind::InvalidData => continue,
+                    Err(error) => return Err(error)
+                },
+                None => break HEADER_SIZE
+            }
+        }
+    };
+
+    if end < length {
+        file.set_len(end)?;
+        file.sync_data()?;
+    }
+    Ok(length - end)
+}
+
+/// reads the block headers until the end of the file or the first truncated block
+///
+/// The blocks in front of a truncated block are kept in `blocks`.
+fn read_block_headers(file: &mut File, path: &Path, length: u64, blocks: &mut Vec<BlockInfo>) -> Result<(), Error<ErrorKind>> {
+    let mut offset = HEADER_SIZE;
+    while offset < length {
+        if offset + BLOCK_HEADER_SIZE > length {
+            return Err(corrupted(path, offset, "truncated block header"));
+        }
+
+        let mut header = [0; BLOCK_HEADER_SIZE as usize];
+        file.seek(SeekFrom::Start(offset))?;
+        file.read_exact(&mut header)?;
+        let block = BlockInfo {
+            offset,
+            count: u32::from_le_bytes(header[0..4].try_into().unwrap()),
+            first: i64::from_le_bytes(header[4..12].try_into().unwrap()),
+            last: i64::from_le_bytes(header[12..20].try_into().unwrap()),
+        };
+
+        offset += BLOCK_HEADER_SIZE + block.count as u64 * CANDLE_SIZE;
+        if offset > length {
+            return Err(corrupted(path, block.offset, "truncated block"));
+        }
+        blocks.push(block);
+    }
+
+    Ok(())
+}
+
+/// reads and verifies the candles of a block
+pub(crate) fn read_block(path: &Path, block: &BlockInfo) -> Result<Vec<Candle>, Error<ErrorKind>> {
+    let mut file = File::open(path)?;
+    file.seek(SeekFrom::Start(block.offset))?;
+    let mut header = [0; BLOCK_HEADER_SIZE as usize];
+    file.read_exact(&mut header)?;
+
+    let mut payload = vec![0; block.count as usize * CANDLE_SIZE as usize];
+    file.read_exact(&mut payload)?;
+    if crc32(&[&header[0..20], &payload]) != u32::from_le_bytes(header[20..24].try_into().unwrap()) {
+        return Err(corrupted(path, block.offset, "checksum mismatch"));
+    }
+
+    let count = block.count as usize;
+    let column = |index: usize, row: usize| -> [u8; 8] {
+        let start = (index * count + row) * 8;
+        payload[start..start + 8].try_into().unwrap()
+    };
+    let price = |index: usize, row: usize| Price::from(f64::from_le_bytes(column(index, row)));
+
+    Ok((0..count)
+        .map(|row| Candle::new(
+            Local.timestamp_nanos(i64::from_le_bytes(column(0, row))),
+            price(1, row),
+            price(2, row),
+            price(3, row),
+            price(4, row),
+            u64::from_le_bytes(column(5, row)),
+        ))
+        .collect())
+}
+
+/// appends the candles as a new block, the file is created if it doesn't exist
+///
+/// The candles need to be sorted by time.
+pub(crate) fn append(path: &Path, candles: &[Candle]) -> Result<(), Error<ErrorKind>> {
+    if candles.is_empty() {
+        return Ok(());
+    }
+
+    let times = candles
+        .iter()
+        .map(|candle| nanos(candle.time))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let mut payload = Vec::with_capacity(candles.len() * CANDLE_SIZE as usize);
+    times.iter().for_each(|time| payload.extend_from_slice(&time.to_le_bytes()));
+    candles.iter().for_each(|candle| payload.extend_from_slice(&f64::from(candle.open).to_le_bytes()));
+    candles.iter().for_each(|candle| payload.extend_from_slice(&f64::from(candle.high).to_le_bytes()));
+    candles.iter().for_each(|candle| payload.extend_from_slice(&f64::from(candle.low).to_le_bytes()));
+    candles.iter().for_each(|candle| payload.extend_from_slice(&f64::from(candle.close).to_le_bytes()));
+    candles.iter().for_each(|candle| payload.extend_from_slice(&candle.volume.to_le_bytes()));
+
+    let mut block = Vec::with_capacity(BLOCK_HEADER_SIZE as usize + payload.len());
+    block.extend_from_slice(&(candles.len() as u32).to_le_bytes());
+    block.extend_from_slice(&times[0].to_le_bytes());
+    block.extend_from_slice(&times[times.len() - 1].to_le_bytes());
+    let checksum = crc32(&[&block, &payload]);
+    block.extend_from_slice(&checksum.to_le_bytes());
+    block.extend_from_slice(&payload);
+
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)?;
+    if file.metadata()?.len() == 0 {
+        file.write_all(MAGIC)?;
+        file.write_all(&VERSION.to_le_bytes())?;
+        file.write_all(&[0; 2])?;
+    }
+    file.write_all(&block)?;
+    file.sync_data()?;
+
+    Ok(())
+}
+
+/// converts a time into the stored nanoseconds
+pub(crate) fn nanos(time: DateTime<Local>) -> Result<i64, Error<ErrorKind>> {
+    time.timestamp_nanos_opt().ok_or_else(|| Error::new(
+        format!("The time {} can't be stored", time),
+        ErrorKind::InvalidData,
+    ))
+}
+
+fn read_header(file: &mut File, path: &Path) -> Result<(), Error<ErrorKind>> {
+    let mut header = [0; HEADER_SIZE as usize];
+    if let Err(error) = file.read_exact(&mut header) {
+        return Err(match error.kind() {
+            IoErrorKind::UnexpectedEof => corrupted(path, 0, "truncated file header"),
+            _ => error.into()
+        });
+    }
+
+    if &header[0..8] != MAGIC {
+        return Err(corrupted(path, 0, "not a candle file"));
+    }
+    let version = u16::from_le_bytes(header[8..10].try_into().unwrap());
+    if version != VERSION {
+        return Err(Error::new(
+            format!("{:?} has the unsupported version {}", path, version),
+            ErrorKind::InvalidData,
+        ));
+    }
+
+    Ok(())
+}
+
+fn corrupted(path: &Path, offset: u64, reason: &str) -> Error<ErrorKind> {
+    Error::new(
+        format!("{:?} is corrupted at byte {}: {}", path, offset, reason),
+        ErrorKind::InvalidData,
+    )
+}
+
+/// the CRC-32 (IEEE) of the concatenated data
+fn crc32(data: &[&[u8]]) -> u32 {
+    const TABLE: [u32; 256] = crc32_table();
+
+    !data.iter().flat_map(|part| part.iter()).fold(!0, |crc, byte| {
+        TABLE[((crc ^ *byte as u32) & 0xff) as usize] ^ (crc >> 8)
+    })
+}
+
+const fn crc32_table() -> [u32; 256] {
+    let mut table = [0; 256];
+    let mut index = 0;
+    while index < 256 {
+        let mut crc = index as u32;
+        let mut bit = 0;
+        while bit < 8 {
+            crc = if crc & 1 == 1 { 0xedb8_8320 ^ (crc >> 1) } else { crc >> 1 };
+            bit += 1;
+        }
+        table[index] = crc;
+        index += 1;
+    }
+    table
+}
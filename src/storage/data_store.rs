@@ -0,0 +1,247 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, Duration, Local, TimeZone};
+
+use crate::{Candle, Derivative, Error, ErrorKind, Gap, StockExchange, StoreFeed};
+
+use super::candle_file;
+
+/// An overview of the stored candles of a derivative
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct SeriesInfo {
+    pub candles: u64,
+    pub blocks: usize,
+    pub first: DateTime<Local>,
+    pub last: DateTime<Local>,
+}
+
+/// A store for historical candles on disk
+///
+/// The candles are stored per `StockExchange` and `Derivative` in
+/// `<root>/<stock exchange>/<symbol>.candles`. Characters of symbols that are not ASCII
+/// alphanumeric, `-` or `.` are escaped as `_` followed by the hex digits of their UTF-8 bytes, so
+/// `BRK/B` is stored in `BRK_2FB.candles` and `BRK_B` in `BRK_5FB.candles`.
+///
+/// A file is a sequence of binary blocks with a column per field of the candles and a checksum.
+/// Every append writes a new block, existing blocks are never rewritten. The time range of every
+/// block is part of its header, so range reads only load the blocks they need.
+/// Corrupted blocks are detected while reading and result in an `ErrorKind::InvalidData`.
+/// Blocks of interrupted appends at the end of the files are removed when the store is opened.
+#[derive(Clone, Debug)]
+pub struct DataStore {
+    root: PathBuf,
+}
+
+impl DataStore {
+    /// opens a store, the root directory is created if it doesn't exist
+    ///
+    /// Every file is truncated after its last valid block, see `DataStore::repair`.
+    pub fn open<P: AsRef<Path>>(root: &P) -> Result<Self, Error<ErrorKind>> {
+        fs::create_dir_all(root.as_ref())?;
+        let store = Self {
+            root: root.as_ref().to_path_buf()
+        };
+        store.repair()?;
+        Ok(store)
+    }
+
+    /// removes the truncated blocks and blocks with a wrong checksum at the end of every file
+    ///
+    /// These are left by appends that were interrupted, for example by a crash. Corrupted blocks
+    /// in front of valid blocks are kept and still detected while reading.
+    /// Returns the amount of removed bytes.
+    pub fn repair(&self) -> Result<u64, Error<ErrorKind>> {
+        let mut removed = 0;
+        for exchange in fs::read_dir(&self.root)? {
+            let exchange = exchange?.path();
+            if !exchange.is_dir() {
+                continue;
+            }
+            for file in fs::read_dir(&exchange)? {
+                let path = file?.path();
+                if path.extension().is_some_and(|extension| extension == "candles") {
+                    removed += candle_file::repair(&path)?;
+                }
+            }
+        }
+        Ok(removed)
+    }
+
+    pub fn root(&self) -> &Path { &self.root }
+
+    /// returns the path of the file that contains the candles of a derivative
+    pub fn path(&self, derivative: &Derivative, stock_exchange: StockExchange) -> PathBuf {
+        let mut symbol = String::with_capacity(derivative.symbol.len());
+        for byte in derivative.symbol.bytes() {
+            if byte.is_ascii_alphanumeric() || byte == b'-' || byte == b'.' {
+                symbol.push(byte as char);
+            } else {
+                symbol.push_str(&format!("_{:02X}", byte));
+            }
+        }
+
+        self.root
+            .join(format!("{:?}", stock_exchange))
+            .join(format!("{}.candles", symbol))
+    }
+
+    pub fn contains(&self, derivative: &Derivative, stock_exchange: StockExchange) -> bool {
+        self.path(derivative, stock_exchange).exists()
+    }
+
+    /// appends candles to the stored candles of a derivative
+    ///
+    /// The candles are sorted first. Candles that are not newer than the last stored candle are
+    /// skipped, so overlapping downloads can be appended without creating duplicates.
+    /// Returns the amount of appended candles.
+    pub fn append(&self, derivative: &Derivative, stock_exchange: StockExchange, candles: &[Candle]) -> Result<usize, Error<ErrorKind>> {
+        let path = self.path(derivative, stock_exchange);
+        let last = match self.info(derivative, stock_exchange)? {
+            Some(info) => Some(candle_file::nanos(info.last)?),
+            None => None
+        };
+
+        let mut candles = candles.to_vec();
+        candles.sort_by_key(|candle| candle.time);
+        let mut new_candles = Vec::with_capacity(candles.len());
+        let mut previous = last;
+        for candle in candles {
+            let time = candle_file::nanos(candle.time)?;
+            if previous.is_none_or(|previous| time > previous) {
+                new_candles.push(candle);
+                previous = Some(time);
+            }
+        }
+
+        if let Some(directory) = path.parent() {
+            fs::create_dir_all(directory)?;
+        }
+        candle_file::append(&path, &new_candles)?;
+        Ok(new_candles.len())
+    }
+
+    /// returns the candles of a derivative with `from <= time < to`
+    ///
+    /// Returns an empty vector if nothing is stored for the derivative.
+    pub fn read(&self, derivative: &Derivative, stock_exchange: StockExchange, from: DateTime<Local>, to: DateTime<Local>) -> Result<Vec<Candle>, Error<ErrorKind>> {
+        let path = self.path(derivative, stock_exchange);
+        if !path.exists() {
+            return Ok(Vec::new());
+        }
+        let (from_nanos, to_nanos) = (clamped_nanos(from), clamped_nanos(to));
+
+        let mut candles = Vec::new();
+        for block in candle_file::scan(&path)? {
+            if block.last < from_nanos || block.first >= to_nanos {
+                continue;
+            }
+            candles.extend(
+                candle_file::read_block(&path, &block)?
+                    .into_iter()
+                    .filter(|candle| candle.time >= from && candle.time < to)
+            );
+        }
+
+        Ok(candles)
+    }
+
+    /// returns all stored candles of a derivative
+    pub fn read_all(&self, derivative: &Derivative, stock_exchange: StockExchange) -> Result<Vec<Candle>, Error<ErrorKind>> {
+        let path = self.path(derivative, stock_exchange);
+        if !path.exists() {
+            return Ok(Vec::new());
+        }
+
+        let mut candles = Vec::new();
+        for block in candle_file::scan(&path)? {
+            candles.extend(candle_file::read_block(&path, &block)?);
+        }
+        Ok(candles)
+    }
+
+    /// returns the latest `amount` candles of a derivative, for example to warm up an algorithm
+    ///
+    /// Only the blocks at the end of the file are read.
+    pub fn read_last(&self, derivative: &Derivative, stock_exchange: StockExchange, amount: usize) -> Result<Vec<Candle>, Error<ErrorKind>> {
+        let path = self.path(derivative, stock_exchange);
+        if !path.exists() || amount == 0 {
+            return Ok(Vec::new());
+        }
+
+        let mut blocks = Vec::new();
+        for block in candle_file::scan(&path)?.into_iter().rev() {
+            let candles = candle_file::read_block(&path, &block)?;
+            let enough = blocks.iter().map(Vec::len).sum::<usize>() + candles.len() >= amount;
+            blocks.push(candles);
+            if enough {
+                break;
+            }
+        }
+
+        let mut candles = blocks
+            .into_iter()
+            .rev()
+            .flatten()
+            .collect::<Vec<_>>();
+        let skip = candles.len().saturating_sub(amount);
+        candles.drain(..skip);
+        Ok(candles)
+    }
+
+    /// returns an overview of the stored candles of a derivative, None if nothing is stored
+    ///
+    /// Only the block headers are read, so the checksums are not verified.
+    /// Use `DataStore::verify` to check the data.
+    pub fn info(&self, derivative: &Derivative, stock_exchange: StockExchange) -> Result<Option<SeriesInfo>, Error<ErrorKind>> {
+        let path = self.path(derivative, stock_exchange);
+        if !path.exists() {
+            return Ok(None);
+        }
+
+        let blocks = candle_file::scan(&path)?;
+        let (first, last) = match (blocks.first(), blocks.last()) {
+            (Some(first), Some(last)) => (first.first, last.last),
+            _ => return Ok(None)
+        };
+
+        Ok(Some(SeriesInfo {
+            candles: blocks.iter().map(|block| block.count as u64).sum(),
+            blocks: blocks.len(),
+            first: Local.timestamp_nanos(first),
+            last: Local.timestamp_nanos(last),
+        }))
+    }
+
+    /// reads all blocks of a derivative and verifies their checksums
+    pub fn verify(&self, derivative: &Derivative, stock_exchange: StockExchange) -> Result<(), Error<ErrorKind>> {
+        self.read_all(derivative, stock_exchange).map(|_| ())
+    }
+
+    /// finds the gaps in the stored candles of a derivative with `from <= time < to`
+    ///
+    /// Candles are expected every `step`, see `Gap::find`.
+    pub fn gaps(&self, derivative: &Derivative, stock_exchange: StockExchange, step: Duration, from: DateTime<Local>, to: DateTime<Local>) -> Result<Vec<Gap>, Error<ErrorKind>> {
+        let candles = self.read(derivative, stock_exchange, from, to)?;
+        Ok(Gap::find(&candles, step))
+    }
+
+    /// removes all stored candles of a derivative
+    pub fn remove(&self, derivative: &Derivative, stock_exchange: StockExchange) -> Result<(), Error<ErrorKind>> {
+        let path = self.path(derivative, stock_exchange);
+        if path.exists() {
+            fs::remove_file(path)?;
+        }
+        Ok(())
+    }
+
+    /// returns a `PriceFeed` that reads the candles of a stock exchange from the store
+    pub fn feed(&self, stock_exchange: StockExchange) -> StoreFeed {
+        StoreFeed::new(self.clone(), stock_exchange)
+    }
+}
+
+/// converts a time into nanoseconds, times outside of the storable range are clamped
+fn clamped_nanos(time: DateTime<Local>) -> i64 {
+    time.timestamp_nanos_opt().unwrap_or(if time.timestamp() < 0 { i64::MIN } else { i64::MAX })
+}
@@ -0,0 +1,42 @@
+use chrono::{DateTime, Duration, Local};
+
+use crate::Candle;
+
+/// A period without candles inside of a series
+///
+/// #### Fields:
+/// * __after__: The time of the last candle before the gap
+/// * __before__: The time of the first candle after the gap
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct Gap {
+    pub after: DateTime<Local>,
+    pub before: DateTime<Local>,
+}
+
+impl Gap {
+    /// finds all gaps of sorted candles that are expected every `step`
+    ///
+    /// Periods in which the market is closed, like nights and weekends, are gaps as well.
+    /// Use `Gap::duration` to filter them.
+    pub fn find(candles: &[Candle], step: Duration) -> Vec<Gap> {
+        candles
+            .windows(2)
+            .filter(|pair| pair[1].time - pair[0].time > step)
+            .map(|pair| Gap {
+                after: pair[0].time,
+                before: pair[1].time,
+            })
+            .collect()
+    }
+
+    pub fn duration(&self) -> Duration {
+        self.before - self.after
+    }
+
+    /// returns the amount of candles that are missing if a candle is expected every `step`
+    pub fn missing_candles(&self, step: Duration) -> i64 {
+        let step = step.num_nanoseconds().unwrap_or(i64::MAX).max(1);
+        let duration = self.duration().num_nanoseconds().unwrap_or(i64::MAX);
+        (duration - 1) / step
+    }
+}
@@ -0,0 +1,8 @@
+pub use data_store::*;
+pub use gap::*;
+pub use store_feed::*;
+
+mod candle_file;
+pub mod data_store;
+pub mod gap;
+pub mod store_feed;
@@ -0,0 +1,70 @@
+use std::collections::HashMap;
+
+use chrono::{DateTime, Duration, Local};
+
+use crate::{Candle, DataStore, Derivative, Error, ErrorKind, FeedUpdate, PriceFeed, StockExchange};
+
+/// A `PriceFeed` that reads the candles of a stock exchange from a `DataStore`
+///
+/// Every poll only reads the blocks that contain new candles, so long backtests don't need
+/// to keep the whole history in memory.
+#[derive(Clone, Debug)]
+pub struct StoreFeed {
+    store: DataStore,
+    stock_exchange: StockExchange,
+    /// the subscribed derivatives and the time of the last polled candle
+    subscriptions: HashMap<String, (Derivative, Option<DateTime<Local>>)>,
+}
+
+impl StoreFeed {
+    pub fn new(store: DataStore, stock_exchange: StockExchange) -> Self {
+        Self {
+            store,
+            stock_exchange,
+            subscriptions: HashMap::new(),
+        }
+    }
+
+    pub fn store(&self) -> &DataStore { &self.store }
+    pub fn stock_exchange(&self) -> StockExchange { self.stock_exchange }
+}
+
+impl PriceFeed for StoreFeed {
+    fn history(&self, derivative: &Derivative, from: DateTime<Local>, to: DateTime<Local>) -> Result<Vec<Candle>, Error<ErrorKind>> {
+        self.store.read(derivative, self.stock_exchange, from, to)
+    }
+
+    fn subscribe(&mut self, derivative: &Derivative) -> Result<(), Error<ErrorKind>> {
+        self.subscriptions
+            .entry(derivative.symbol.clone())
+            .or_insert_with(|| (derivative.clone(), None));
+        Ok(())
+    }
+
+    fn poll(&mut self, time: DateTime<Local>) -> Result<Vec<FeedUpdate>, Error<ErrorKind>> {
+        let mut updates = Vec::new();
+
+        for (symbol, (derivative, polled)) in self.subscriptions.iter_mut() {
+            let to = time + Duration::nanoseconds(1);
+            let candles = match polled {
+                Some(polled) => self.store.read(derivative, self.stock_exchange, *polled + Duration::nanoseconds(1), to)?,
+                None => self.store
+                    .read_all(derivative, self.stock_exchange)?
+                    .into_iter()
+                    .filter(|candle| candle.time < to)
+                    .collect()
+            };
+
+            if let Some(last) = candles.last() {
+                *polled = Some(last.time);
+            }
+            updates.extend(candles.into_iter().map(|candle| FeedUpdate {
+                symbol: symbol.clone(),
+                candle,
+            }));
+        }
+
+        updates.sort_by_key(|update| update.candle.time);
+        Ok(updates)
+    }
+}
@@ -0,0 +1,71 @@
+use chrono::{DateTime, Datelike, FixedOffset, Local, NaiveTime, TimeZone, Weekday};
+
+/// The regular trading hours of a stock exchange or a derivative
+///
+/// #### Fields:
+/// * __open__, __close__: The time of day the trading starts and ends, the close is exclusive
+/// * __utc_offset__: The offset of the times to UTC in seconds, daylight saving time is not
+///   taken into account
+/// * __weekdays__: The days the market is open, holidays are not taken into account
+/// ```
+/// # use chrono::{FixedOffset, TimeZone};
+/// # use trading_utils::*;
+/// let hours = StockExchange::NYSE.trading_hours();
+/// let new_york = FixedOffset::west_opt(5 * 3600).unwrap();
+///
+/// // a monday
+/// assert!(hours.is_open(new_york.with_ymd_and_hms(2021, 3, 1, 9, 30, 0).unwrap()));
+/// assert!(!hours.is_open(new_york.with_ymd_and_hms(2021, 3, 1, 16, 0, 0).unwrap()));
+/// // a saturday
+/// assert!(!hours.is_open(new_york.with_ymd_and_hms(2021, 3, 6, 12, 0, 0).unwrap()));
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TradingHours {
+    pub open: NaiveTime,
+    pub close: NaiveTime,
+    pub utc_offset: i32,
+    pub weekdays: Vec<Weekday>,
+}
+
+impl TradingHours {
+    /// creates trading hours from monday to friday
+    pub fn new(open: NaiveTime, close: NaiveTime, utc_offset: i32) -> Self {
+        use Weekday::*;
+        Self {
+            open,
+            close,
+            utc_offset,
+            weekdays: vec![Mon, Tue, Wed, Thu, Fri],
+        }
+    }
+
+    pub fn with_weekdays(mut self, weekdays: Vec<Weekday>) -> Self {
+        self.weekdays = weekdays;
+        self
+    }
+
+    /// returns true if the market is open at the given time
+    pub fn is_open<Tz: TimeZone>(&self, time: DateTime<Tz>) -> bool {
+        let time = time.with_timezone(&self.offset());
+        self.weekdays.contains(&time.weekday())
+            && self.open <= time.time()
+            && time.time() < self.close
+    }
+
+    /// returns the close of the trading day that is open at the given time
+    pub fn close_at<Tz: TimeZone>(&self, time: DateTime<Tz>) -> Option<DateTime<Local>> {
+        if !self.is_open(time.clone()) {
+            return None;
+        }
+        let time = time.with_timezone(&self.offset());
+        self.offset()
+            .from_local_datetime(&time.date_naive().and_time(self.close))
+            .single()
+            .map(|close| close.with_timezone(&Local))
+    }
+
+    fn offset(&self) -> FixedOffset {
+        FixedOffset::east_opt(self.utc_offset).unwrap_or_else(|| FixedOffset::east_opt(0).unwrap())
+    }
+}
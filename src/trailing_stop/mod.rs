@@ -0,0 +1,231 @@
+pub use trail::*;
+pub use trailing_stop_event::*;
+
+use crate::{Candle, Deposit, Direction, EventBus, Order, OrderChanges, OrderType, PlannedAction, Position, StopLoss};
+
+pub mod trail;
+pub mod trailing_stop_event;
+
+/// Moves the stops of positions after their best price and closes them when it's crossed
+///
+/// The engine follows the highest price of long positions and the lowest price of short
+/// positions. The stop keeps the distance of its `Trail` to this water mark and never moves
+/// back.
+///
+/// Every open position of the deposit whose order has a `StopLoss::Trailing` is tracked with its
+/// distance, other positions can be tracked with `track`. When the price crosses the stop:
+/// * a position of an active `Bracket` is closed by the stop loss of the bracket, the returned
+///   `PlannedAction::Modify` actions move its stop price along with the trailing stop, so the
+///   broker fills it
+/// * other positions are closed by the returned `PlannedAction::Close` actions
+///
+/// The actions are executed by an `Executor`, see `ExecutionPlan::new`, the `Runner` does this
+/// with every new candle. If a position is still open after its stop was triggered, the close
+/// failed and the stop is checked again.
+///
+/// A candle is checked against the stop of the previous candles, since the order of the prices
+/// within a candle is unknown. So `update` should be called after the orders were matched
+/// against the same candle, then a moved stop of a bracket is filled from the next candle on.
+/// ```
+/// # use chrono::{Duration, Local};
+/// # use trading_utils::*;
+/// let share = Derivative::new("SAP".to_string(), DerivativeKind::Stock, Currency::EUR);
+/// let mut deposit = Deposit::empty("deposit".to_string(), Currency::EUR);
+/// let order = Order::Single(OrderData::new(
+///     "position".to_string(), share, StockExchange::LSExchange, 10,
+///     OrderType::LimitOrder(Price::from(100.0)), PositionType::LongCall,
+///     TakeProfit::None, StopLoss::Trailing(Price::from(-5.0)),
+///     OrderMoment::Instant, OrderValidity::OneDay,
+/// ));
+/// deposit.update_positions(vec![Position::from_order(order, Local::now(), Price::from(100.0)).unwrap()]);
+///
+/// let mut engine = TrailingStopEngine::new();
+/// let candle = |day, high, low| Candle::new(
+///     Local::now() + Duration::days(day), Price::from(low), Price::from(high), Price::from(low), Price::from(high), 0,
+/// );
+///
+/// // the stop follows the high of 110 to 105
+/// assert!(engine.update(&deposit, "SAP", &[candle(0, 110.0, 101.0)]).is_empty());
+/// assert_eq!(engine.stop("position").unwrap().stop, Some(Price::from(105.0)));
+///
+/// // a lower high doesn't move it back, but the low crosses it
+/// let exits = engine.update(&deposit, "SAP", &[candle(1, 107.0, 104.0)]);
+/// assert!(matches!(exits[0], PlannedAction::Close { pieces: None, .. }));
+/// ```
+pub struct TrailingStopEngine {
+    stops: Vec<TrailingStop>,
+    events: EventBus<TrailingStopEvent>,
+}
+
+impl TrailingStopEngine {
+    pub fn new() -> Self {
+        Self {
+            stops: Vec::new(),
+            events: EventBus::new(),
+        }
+    }
+
+    /// uses an existing bus for the trailing stop events
+    pub fn with_events(mut self, events: &EventBus<TrailingStopEvent>) -> Self {
+        self.events = events.clone();
+        self
+    }
+
+    pub fn stops(&self) -> &[TrailingStop] { &self.stops }
+    pub fn events(&self) -> &EventBus<TrailingStopEvent> { &self.events }
+
+    /// returns the trailing stop of a position by its id
+    pub fn stop(&self, position: &str) -> Option<&TrailingStop> {
+        self.stops
+            .iter()
+            .find(|stop| stop.position == position)
+    }
+
+    /// trails the stop of a position, starting at its entry price
+    ///
+    /// An existing trailing stop of the position is replaced.
+    pub fn track(&mut self, position: &Position, trail: Trail) {
+        self.untrack(&position.id);
+
+        let water_mark = position.entry_price();
+        let mut stop = TrailingStop {
+            position: position.id.clone(),
+            symbol: position.derivative().symbol.clone(),
+            direction: position.direction(),
+            trail,
+            water_mark,
+            stop: None,
+            triggered: false,
+        };
+        stop.stop = stop.trail
+            .distance(water_mark, &[])
+            .map(|distance| match stop.direction {
+                Direction::Long => water_mark - distance,
+                Direction::Short => water_mark + distance,
+            });
+
+        self.events.emit(TrailingStopEvent::Tracked { position: stop.position.clone(), stop: stop.stop });
+        self.stops.push(stop);
+    }
+
+    /// stops trailing the stop of a position
+    pub fn untrack(&mut self, position: &str) -> Option<TrailingStop> {
+        let index = self.stops
+            .iter()
+            .position(|stop| stop.position == position)?;
+        Some(self.stops.remove(index))
+    }
+
+    /// follows the last candle of a symbol with the stops of its positions
+    ///
+    /// The candles are the recent candles of the symbol, the last one is the current candle.
+    /// The earlier ones are only used by trails with the average true range.
+    /// Returns the actions that close the positions whose stop was crossed and that move the
+    /// stops of brackets.
+    pub fn update(&mut self, deposit: &Deposit, symbol: &str, candles: &[Candle]) -> Vec<PlannedAction> {
+        let mut exits = Vec::new();
+        let candle = match candles.last() {
+            Some(candle) => candle,
+            None => return exits,
+        };
+        self.sync(deposit);
+
+        for stop in self.stops.iter_mut().filter(|stop| stop.symbol == symbol && !stop.triggered) {
+            let bracket_stop = bracket_stop(deposit, &stop.position);
+
+            if stop.is_crossed(candle) {
+                stop.triggered = true;
+                self.events.emit(TrailingStopEvent::Triggered {
+                    position: stop.position.clone(),
+                    stop: stop.stop.unwrap_or(candle.close),
+                });
+                if bracket_stop.is_none() {
+                    let position = deposit
+                        .positions()
+                        .iter()
+                        .find(|position| position.id == stop.position);
+                    if let Some(position) = position {
+                        exits.push(PlannedAction::Close { position: position.hashed_id(), pieces: None });
+                    }
+                }
+                continue;
+            }
+
+            let from = stop.stop;
+            if let Some(to) = stop.follow(candle, candles) {
+                self.events.emit(TrailingStopEvent::Moved {
+                    position: stop.position.clone(),
+                    water_mark: stop.water_mark,
+                    from,
+                    to,
+                });
+                if let Some(order_id) = bracket_stop {
+                    exits.push(PlannedAction::Modify {
+                        order_id,
+                        changes: OrderChanges {
+                            order_type: Some(OrderType::StopOrder(to)),
+                            ..OrderChanges::default()
+                        },
+                    });
+                }
+            }
+        }
+
+        exits
+    }
+
+    /// tracks the new positions with a trailing stop loss and forgets the closed ones
+    ///
+    /// A triggered stop of a position that is still open is checked again.
+    fn sync(&mut self, deposit: &Deposit) {
+        let open = |id: &str| deposit
+            .positions()
+            .iter()
+            .any(|position| position.id == id && position.is_open());
+
+        let events = &self.events;
+        self.stops.retain_mut(|stop| {
+            let open = open(&stop.position);
+            if open {
+                // the position wasn't closed, so the stop is checked again
+                stop.triggered = false;
+            } else if !stop.triggered {
+                events.emit(TrailingStopEvent::Untracked { position: stop.position.clone() });
+            }
+            open
+        });
+
+        for position in deposit.positions() {
+            if !position.is_open() || self.stop(&position.id).is_some() {
+                continue;
+            }
+            let stop_loss = position.order
+                .data()
+                .iter()
+                .find(|order_data| *order_data.raw_id() == position.id)
+                .or_else(|| position.order.data().first())
+                .map(|order_data| order_data.stop_loss());
+            if let Some(StopLoss::Trailing(distance)) = stop_loss {
+                self.track(position, Trail::Absolute(*distance));
+            }
+        }
+    }
+}
+
+impl Default for TrailingStopEngine {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// the id of the stop loss of the active bracket that protects the position
+fn bracket_stop(deposit: &Deposit, position: &str) -> Option<u64> {
+    deposit
+        .orders()
+        .iter()
+        .find_map(|order| match order {
+            Order::Bracket(bracket) if bracket.position().map(String::as_str) == Some(position) =>
+                bracket.stop_loss_order().map(|order_data| order_data.id()),
+            _ => None,
+        })
+}
@@ -0,0 +1,93 @@
+use crate::{Candle, Direction, Percent, Price};
+
+/// How far a trailing stop follows the best price of a position
+///
+/// #### Variants:
+/// * __Absolute__: A fixed distance, in the currency of the derivative
+/// * __Percent__: A share of the best price, `0.05` for 5%
+/// * __Atr__: A multiple of the average true range of the last `periods` candles, see
+///   `Candle::average_true_range`
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(tag = "type", content = "value", rename_all = "snake_case"))]
+pub enum Trail {
+    Absolute(Price),
+    Percent(Percent),
+    Atr { multiple: f64, periods: usize },
+}
+
+impl Trail {
+    /// returns the distance of the stop to the best price,
+    /// `None` if there are not enough candles for the average true range
+    pub fn distance(&self, water_mark: Price, candles: &[Candle]) -> Option<Price> {
+        match self {
+            Trail::Absolute(distance) => Some(Price::from(distance.abs())),
+            Trail::Percent(percent) => Some(Price::from((water_mark * *percent).abs())),
+            Trail::Atr { multiple, periods } => Candle::average_true_range(candles, *periods)
+                .map(|atr| atr * multiple.abs()),
+        }
+    }
+}
+
+/// The state of the trailing stop of a position
+///
+/// #### Fields:
+/// * __position__: The id of the position
+/// * __symbol__: The symbol of the derivative of the position
+/// * __direction__: The direction of the position
+/// * __trail__: How the stop follows the price
+/// * __water_mark__: The highest price since the position was opened for long positions,
+///   the lowest for short positions
+/// * __stop__: The price that closes the position, `None` until the distance is known
+/// * __triggered__: True once the price crossed the stop, until the position turns out to be
+///   still open
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TrailingStop {
+    pub position: String,
+    pub symbol: String,
+    pub direction: Direction,
+    pub trail: Trail,
+    pub water_mark: Price,
+    pub stop: Option<Price>,
+    pub triggered: bool,
+}
+
+impl TrailingStop {
+    /// returns true if the price crossed the stop
+    pub fn is_crossed(&self, candle: &Candle) -> bool {
+        match (self.stop, self.direction) {
+            (Some(stop), Direction::Long) => candle.low <= stop,
+            (Some(stop), Direction::Short) => candle.high >= stop,
+            (None, _) => false,
+        }
+    }
+
+    /// moves the water mark to the best price of the candle and the stop after it,
+    /// returns the new stop if it moved
+    ///
+    /// A stop only moves in the direction of the position.
+    pub fn follow(&mut self, candle: &Candle, candles: &[Candle]) -> Option<Price> {
+        self.water_mark = match self.direction {
+            Direction::Long if candle.high > self.water_mark => candle.high,
+            Direction::Short if candle.low < self.water_mark => candle.low,
+            _ => self.water_mark,
+        };
+
+        let distance = self.trail.distance(self.water_mark, candles)?;
+        let stop = match self.direction {
+            Direction::Long => self.water_mark - distance,
+            Direction::Short => self.water_mark + distance,
+        };
+        let better = match (self.stop, self.direction) {
+            (None, _) => true,
+            (Some(current), Direction::Long) => stop > current,
+            (Some(current), Direction::Short) => stop < current,
+        };
+
+        if !better {
+            return None;
+        }
+        self.stop = Some(stop);
+        Some(stop)
+    }
+}
@@ -0,0 +1,47 @@
+use std::fmt;
+use std::fmt::Formatter;
+
+use crate::{Event, EventLevel, MarketValue, Price};
+
+/// The events emitted by a `TrailingStopEngine`
+#[derive(Clone, Debug)]
+pub enum TrailingStopEvent {
+    /// the trailing stop of a position is tracked, the stop is `None` until its distance is known
+    Tracked { position: String, stop: Option<Price> },
+    /// the best price moved, so the stop followed it
+    Moved { position: String, water_mark: Price, from: Option<Price>, to: Price },
+    /// the price crossed the stop, the position is closed
+    Triggered { position: String, stop: Price },
+    /// the position was closed otherwise, its stop isn't tracked anymore
+    Untracked { position: String },
+}
+
+impl Event for TrailingStopEvent {
+    fn level(&self) -> EventLevel {
+        use TrailingStopEvent::*;
+        match self {
+            Tracked { .. } | Triggered { .. } => EventLevel::Info,
+            Moved { .. } | Untracked { .. } => EventLevel::Debug,
+        }
+    }
+}
+
+impl fmt::Display for TrailingStopEvent {
+    fn fmt(&self, formatter: &mut Formatter<'_>) -> fmt::Result {
+        use TrailingStopEvent::*;
+        match self {
+            Tracked { position, stop: Some(stop) } =>
+                write!(formatter, "trailing the stop of the position `{}` from {:.4}", position, stop.as_f64()),
+            Tracked { position, stop: None } =>
+                write!(formatter, "trailing the stop of the position `{}`", position),
+            Moved { position, water_mark, from: Some(from), to } =>
+                write!(formatter, "moved the stop of the position `{}` from {:.4} to {:.4} (best price {:.4})", position, from.as_f64(), to.as_f64(), water_mark.as_f64()),
+            Moved { position, water_mark, from: None, to } =>
+                write!(formatter, "set the stop of the position `{}` to {:.4} (best price {:.4})", position, to.as_f64(), water_mark.as_f64()),
+            Triggered { position, stop } =>
+                write!(formatter, "the price crossed the stop {:.4} of the position `{}`", stop.as_f64(), position),
+            Untracked { position } =>
+                write!(formatter, "stopped trailing the position `{}`", position),
+        }
+    }
+}
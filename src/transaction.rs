@@ -1 +1,84 @@
-pub struct Transaction {}
\ No newline at end of file
+use chrono::{DateTime, Local};
+
+use crate::{Currency, Derivative, MarketValue, Price};
+
+/// A booking on the cash of a deposit
+///
+/// #### Fields:
+/// * __id__: The id of the transaction, usually provided by the broker
+/// * __time__: When the transaction was booked
+/// * __kind__: What the transaction was for
+/// * __amount__: The change of the cash, positive amounts increase it, negative ones reduce it
+/// * __currency__: The currency of the amount
+/// * __derivative__: The derivative that was traded or paid a dividend
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Transaction {
+    pub id: String,
+    pub time: DateTime<Local>,
+    pub kind: TransactionKind,
+    pub amount: Price,
+    pub currency: Currency,
+    pub derivative: Option<Derivative>,
+}
+
+impl Transaction {
+    pub fn new(id: String, time: DateTime<Local>, kind: TransactionKind, amount: Price, currency: Currency) -> Self {
+        Self {
+            id,
+            time,
+            kind,
+            amount,
+            currency,
+            derivative: None,
+        }
+    }
+
+    pub fn with_derivative(mut self, derivative: Derivative) -> Self {
+        self.derivative = Some(derivative);
+        self
+    }
+
+    /// returns the sum of the amounts per currency, in the order the currencies first appear
+    pub fn cash_balances(transactions: &[Transaction]) -> Vec<(Currency, Price)> {
+        let mut balances: Vec<(Currency, Price)> = Vec::new();
+
+        for transaction in transactions {
+            match balances.iter_mut().find(|(currency, _)| *currency == transaction.currency) {
+                Some((_, balance)) => *balance += transaction.amount,
+                None => balances.push((transaction.currency, transaction.amount)),
+            }
+        }
+
+        balances
+    }
+
+    /// returns the sum of the amounts of the given kind, ignoring the currency
+    pub fn sum(transactions: &[Transaction], kind: TransactionKind) -> Price {
+        transactions
+            .iter()
+            .filter(|transaction| transaction.kind == kind)
+            .fold(Price::zero(), |sum, transaction| sum + transaction.amount)
+    }
+}
+
+/// What a transaction was for
+///
+/// #### Variants:
+/// * __CashIn__, __CashOut__: Money was paid in or withdrawn
+/// * __Buy__, __Sell__: A derivative was bought or sold
+/// * __Fee__: The broker charged a fee
+/// * __Dividend__, __Interest__: Income of a derivative or the cash
+/// * __Tax__: Taxes that were paid or refunded
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(rename_all = "snake_case"))]
+pub enum TransactionKind {
+    CashIn,
+    CashOut,
+    Buy,
+    Sell,
+    Fee,
+    Dividend,
+    Interest,
+    Tax,
+}
@@ -0,0 +1,291 @@
+use std::env::consts::{DLL_EXTENSION, DLL_PREFIX, DLL_SUFFIX};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::{Arc, OnceLock};
+
+use trading_utils::*;
+
+const IDLE: &str = r#"
+use trading_utils::*;
+
+pub struct Idle;
+
+impl AlgorithmInterface for Idle {
+    fn algorithm(&mut self, _: &[Position], _: &[Price]) -> Result<&[Instruction<'_>], Error<TradingErrorKind>> {
+        Ok(&[])
+    }
+}
+"#;
+
+/// the libraries that are built for the tests, with the registrations they export
+const FIXTURES: &[(&str, &str)] = &[
+    ("counter", r#"
+/// adds the step to its count with every call and returns the count as the id of a cancelled order
+pub struct Counter {
+    step: u64,
+    count: u64,
+    instructions: Vec<Instruction<'static>>,
+}
+
+impl AlgorithmInterface for Counter {
+    fn algorithm(&mut self, _: &[Position], _: &[Price]) -> Result<&[Instruction<'_>], Error<TradingErrorKind>> {
+        self.count += self.step;
+        self.instructions = vec![Instruction::CancelOrder { order_id: self.count }];
+        Ok(&self.instructions)
+    }
+}
+
+export_algorithm! {
+    (
+        "counter", "counts its calls", 2, 10,
+        [ParameterDefinition::integer("step", "the amount added per call", 1, 1, 10)],
+        |parameters: &Parameters| Counter { step: parameters.integer("step").unwrap() as u64, count: 0, instructions: Vec::new() }
+    );
+    ("idle", "does nothing", Idle);
+}
+"#),
+    ("duplicates", r#"
+export_algorithm! {
+    ("twin", "does nothing", Idle);
+    ("twin", "does nothing as well", Idle);
+}
+"#),
+    ("others", r#"
+export_algorithm! {
+    ("other", "does nothing", Idle);
+    ("counter", "does not count", Idle);
+}
+"#),
+];
+
+/// builds the fixture libraries once and returns the directory of the build
+///
+/// The libraries use the same features of trading-utils as the tests, so the algorithms they
+/// return have the same layout.
+fn fixtures() -> &'static Path {
+    static FIXTURES_DIR: OnceLock<PathBuf> = OnceLock::new();
+    FIXTURES_DIR.get_or_init(|| {
+        let features: Vec<&str> = [("serde", cfg!(feature = "serde")), ("decimal", cfg!(feature = "decimal"))]
+            .iter()
+            .filter(|(_, enabled)| *enabled)
+            .map(|(feature, _)| *feature)
+            .collect();
+        let dir = Path::new(env!("CARGO_TARGET_TMPDIR")).join(format!("algorithm-fixtures-{}", features.join("-")));
+        let manifest_dir = Path::new(env!("CARGO_MANIFEST_DIR"));
+
+        let members: Vec<String> = FIXTURES.iter().map(|(name, _)| format!("{:?}", name)).collect();
+        write(&dir.join("Cargo.toml"), &format!("[workspace]\nmembers = [{}]\nresolver = \"2\"\n", members.join(", ")));
+        // the lock file of the crate pins the dependencies to the versions of the tests
+        if let Ok(lock) = fs::read(manifest_dir.join("Cargo.lock")) {
+            if !dir.join("Cargo.lock").exists() {
+                fs::write(dir.join("Cargo.lock"), lock).unwrap();
+            }
+        }
+
+        for (name, source) in FIXTURES {
+            write(&dir.join(name).join("Cargo.toml"), &format!(
+                "[package]\nname = {:?}\nversion = \"0.0.0\"\nedition = \"2018\"\n\n\
+                [lib]\ncrate-type = [\"cdylib\"]\n\n\
+                [dependencies]\ntrading-utils = {{ path = {:?}, features = {:?} }}\n",
+                name, manifest_dir, features,
+            ));
+            write(&dir.join(name).join("src").join("lib.rs"), &format!("{}{}", IDLE, source));
+        }
+
+        let output = Command::new(env!("CARGO"))
+            .args(["build", "--quiet", "--manifest-path"])
+            .arg(dir.join("Cargo.toml"))
+            .arg("--target-dir")
+            .arg(dir.join("target"))
+            .output()
+            .unwrap();
+        assert!(output.status.success(), "the fixtures could not be built:\n{}", String::from_utf8_lossy(&output.stderr));
+        dir
+    })
+}
+
+/// the path of a fixture library
+fn library(name: &str) -> PathBuf {
+    fixtures()
+        .join("target")
+        .join("debug")
+        .join(format!("{}{}{}", DLL_PREFIX, name, DLL_SUFFIX))
+}
+
+/// writes a file, unless it already has the content, so cargo doesn't rebuild the fixtures
+fn write(path: &Path, content: &str) {
+    if fs::read_to_string(path).is_ok_and(|existing| existing == content) {
+        return;
+    }
+    fs::create_dir_all(path.parent().unwrap()).unwrap();
+    fs::write(path, content).unwrap();
+}
+
+/// an empty directory for a single test
+fn empty_dir(name: &str) -> PathBuf {
+    let dir = Path::new(env!("CARGO_TARGET_TMPDIR")).join(name);
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+#[test]
+fn libraries_export_several_algorithms() {
+    let mut algorithms = Algorithms::empty();
+    let log = Arc::new(EventLog::new());
+    algorithms.subscribe(log.clone());
+
+    assert_eq!(algorithms.load(&library("counter")).unwrap(), vec!["counter", "idle"]);
+    assert!(algorithms.contains("counter") && algorithms.contains("idle"));
+
+    let counter = algorithms.get("counter").unwrap();
+    assert_eq!(counter.description(), "counts its calls");
+    assert_eq!((counter.min_data_length(), counter.max_data_length()), (2, 10));
+    assert_eq!(counter.parameters().integer("step"), Some(1));
+    assert_eq!(counter.path(), &fs::canonicalize(library("counter")).unwrap());
+
+    let loaded: Vec<String> = log
+        .events()
+        .into_iter()
+        .filter_map(|event| match event {
+            AlgorithmEvent::Loaded { algorithm, .. } => Some(algorithm),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(loaded, vec!["counter", "idle"]);
+
+    // a single algorithm can only be loaded from a library that exports exactly one
+    let error = Algorithm::load(&library("counter")).err().unwrap();
+    assert_eq!(error.kind(), ErrorKind::LibLoading);
+    let all = Algorithm::load_all(&library("counter")).unwrap();
+    assert_eq!(all.iter().map(Algorithm::name).collect::<Vec<_>>(), vec!["counter", "idle"]);
+}
+
+#[test]
+fn duplicated_names_within_a_library_are_rejected() {
+    let mut algorithms = Algorithms::empty();
+
+    let error = algorithms.load(&library("duplicates")).unwrap_err();
+    assert_eq!(error.kind(), ErrorKind::LibLoading);
+    assert!(error.msg().contains("`twin`"));
+    assert!(!algorithms.contains("twin"));
+}
+
+#[test]
+fn duplicated_names_across_libraries_are_rejected() {
+    let mut algorithms = Algorithms::empty();
+    algorithms.load(&library("counter")).unwrap();
+
+    let error = algorithms.load(&library("others")).unwrap_err();
+    assert_eq!(error.kind(), ErrorKind::LibLoading);
+    assert!(error.msg().contains("`counter`"));
+
+    // none of the algorithms of the library is added and the loaded one is kept
+    assert!(!algorithms.contains("other"));
+    assert_eq!(algorithms.get("counter").unwrap().description(), "counts its calls");
+}
+
+#[test]
+fn load_all_reports_every_entry() {
+    let dir = empty_dir("load-all");
+    fs::copy(library("counter"), dir.join(format!("counter.{}", DLL_EXTENSION))).unwrap();
+    fs::copy(library("duplicates"), dir.join(format!("duplicates.{}", DLL_EXTENSION))).unwrap();
+    fs::write(dir.join("notes.txt"), "no algorithms").unwrap();
+    fs::create_dir(dir.join("empty")).unwrap();
+
+    let mut algorithms = Algorithms::empty();
+    let log = Arc::new(EventLog::new());
+    algorithms.subscribe(log.clone());
+    let report = algorithms.load_all(&dir).unwrap();
+
+    assert!(!report.is_success());
+    assert_eq!(report.loaded.len(), 1);
+    assert!(report.loaded[0].0.ends_with(format!("counter.{}", DLL_EXTENSION)));
+    assert_eq!(report.algorithms().collect::<Vec<_>>(), vec!["counter", "idle"]);
+
+    assert_eq!(report.failed.len(), 1);
+    assert!(report.failed[0].0.ends_with(format!("duplicates.{}", DLL_EXTENSION)));
+    assert_eq!(report.failed[0].1.kind(), ErrorKind::LibLoading);
+
+    let mut skipped: Vec<(&Path, &str)> = report.skipped
+        .iter()
+        .map(|(path, reason)| (path.strip_prefix(&dir).unwrap(), reason.as_str()))
+        .collect();
+    skipped.sort();
+    assert_eq!(skipped, vec![
+        (Path::new("empty"), "the directory does not contain any algorithms"),
+        (Path::new("notes.txt"), "the file is no dynamic library"),
+    ]);
+
+    let count = |matches: fn(&AlgorithmEvent) -> bool| log.events().iter().filter(|event| matches(event)).count();
+    assert_eq!(count(|event| matches!(event, AlgorithmEvent::LibraryFound { .. })), 2);
+    assert_eq!(count(|event| matches!(event, AlgorithmEvent::Loaded { .. })), 2);
+    assert_eq!(count(|event| matches!(event, AlgorithmEvent::Failed { .. })), 1);
+    assert_eq!(count(|event| matches!(event, AlgorithmEvent::Skipped { .. })), 2);
+}
+
+/// calls the counter once and returns its count
+fn count(instance: &mut AlgorithmInstance) -> u64 {
+    match instance.algorithm(&[], &[]).unwrap() {
+        [Instruction::CancelOrder { order_id }] => *order_id,
+        instructions => panic!("expected the count, got {:?}", instructions),
+    }
+}
+
+#[test]
+fn spawned_instances_have_their_own_state() {
+    let mut algorithms = Algorithms::empty();
+    let log = Arc::new(EventLog::new());
+    algorithms.subscribe(log.clone());
+    algorithms.load(&library("counter")).unwrap();
+
+    let mut first = algorithms.spawn("counter", &Parameters::empty()).unwrap();
+    let mut second = algorithms.get("counter").unwrap().spawn();
+    let mut third = algorithms.spawn("counter", &Parameters::empty().with("step", 5_i64)).unwrap();
+    assert_eq!((first.name(), first.min_data_length(), first.max_data_length()), ("counter", 2, 10));
+    assert_eq!(third.parameters().integer("step"), Some(5));
+
+    assert_eq!(count(&mut first), 1);
+    assert_eq!(count(&mut first), 2);
+    assert_eq!(count(&mut second), 1);
+    assert_eq!(count(&mut third), 5);
+
+    // changed parameters only apply to instances that are spawned afterwards
+    algorithms.get_mut("counter").unwrap().set_parameters(&Parameters::empty().with("step", 3_i64)).unwrap();
+    let mut fourth = algorithms.get("counter").unwrap().spawn();
+    assert_eq!(count(&mut fourth), 3);
+    assert_eq!(count(&mut second), 2);
+
+    let spawned = log.events().iter().filter(|event| matches!(event, AlgorithmEvent::Spawned { .. })).count();
+    assert_eq!(spawned, 4);
+}
+
+#[test]
+fn spawning_checks_the_parameters() {
+    let mut algorithms = Algorithms::empty();
+    algorithms.load(&library("counter")).unwrap();
+
+    let error = algorithms.spawn("counter", &Parameters::empty().with("step", 11_i64)).err().unwrap();
+    assert_eq!(error.kind(), ErrorKind::InvalidParameter);
+    let error = algorithms.spawn("counter", &Parameters::empty().with("steps", 1_i64)).err().unwrap();
+    assert_eq!(error.kind(), ErrorKind::InvalidParameter);
+    assert!(algorithms.spawn("missing", &Parameters::empty()).is_err());
+}
+
+#[test]
+fn instances_keep_the_shared_library_loaded() {
+    let mut algorithms = Algorithms::empty();
+    algorithms.load(&library("counter")).unwrap();
+    let mut counter = algorithms.spawn("counter", &Parameters::empty()).unwrap();
+    let mut idle = algorithms.spawn("idle", &Parameters::empty()).unwrap();
+    assert_eq!(count(&mut counter), 1);
+
+    // the algorithms and their library handle are gone, the instances still call into the library
+    drop(algorithms);
+    assert_eq!(count(&mut counter), 2);
+    assert!(idle.algorithm(&[], &[]).unwrap().is_empty());
+
+    drop(counter);
+    assert!(idle.algorithm(&[], &[]).unwrap().is_empty());
+}
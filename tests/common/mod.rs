@@ -0,0 +1,197 @@
+#![allow(dead_code)]
+
+use std::cell::{Cell, RefCell};
+
+use chrono::{DateTime, Duration, Local, TimeZone};
+use trading_utils::*;
+
+pub fn start() -> DateTime<Local> {
+    Local.with_ymd_and_hms(2021, 3, 1, 10, 0, 0).unwrap()
+}
+
+pub fn share(symbol: &str) -> Derivative {
+    Derivative::new(symbol.to_string(), DerivativeKind::Stock, Currency::EUR)
+}
+
+/// a candle every minute from the start on, with the given close prices
+pub fn candles(closes: &[f64]) -> Vec<Candle> {
+    closes
+        .iter()
+        .enumerate()
+        .map(|(minute, close)| Candle::from_price(start() + Duration::minutes(minute as i64), Price::from(*close)))
+        .collect()
+}
+
+pub fn deposit() -> Deposit {
+    let mut deposit = Deposit::empty("deposit".to_string(), Currency::EUR);
+    deposit.update_balance(Price::from(10_000.0));
+    deposit
+}
+
+pub fn market_buy(pieces: u64) -> OrderRequest<'static> {
+    OrderRequest::new(pieces, OrderType::MarketOrder, PositionType::LongCall)
+}
+
+/// A broker that fills every order right away
+///
+/// Market orders are filled at `price`, limit and stop orders at their price. The filled
+/// orders are removed from the deposit, like a broker that reconciles the deposit would.
+/// The exits of active brackets for open positions stay open.
+pub struct TestBroker {
+    pub price: Cell<Price>,
+    /// the index of the leg of a multi leg order that is filled
+    pub leg: Cell<usize>,
+    pub reject_orders: Cell<bool>,
+    pub reject_changes: Cell<bool>,
+    pub calls: RefCell<Vec<String>>,
+}
+
+impl TestBroker {
+    pub fn new(price: f64) -> Self {
+        Self {
+            price: Cell::new(Price::from(price)),
+            leg: Cell::new(0),
+            reject_orders: Cell::new(false),
+            reject_changes: Cell::new(false),
+            calls: RefCell::new(Vec::new()),
+        }
+    }
+
+    pub fn calls(&self) -> Vec<String> {
+        self.calls.borrow().clone()
+    }
+
+    fn call(&self, call: String) {
+        self.calls.borrow_mut().push(call);
+    }
+
+    fn error<T>(msg: &str, kind: BrokerErrorKind) -> Result<T, Error<BrokerErrorKind>> {
+        Err(Error::new(msg.to_string(), kind))
+    }
+}
+
+impl BrokerInterface for TestBroker {
+    const NAME: &'static str = "test broker";
+    const CAPABILITIES: &'static [BrokerCapability] = &[
+        BrokerCapability::OrderChange,
+        BrokerCapability::OrderDelete,
+        BrokerCapability::BuyMarketOrder,
+        BrokerCapability::BuyLimitOrder,
+        BrokerCapability::BuyStopOrder,
+        BrokerCapability::SellMarketOrder,
+        BrokerCapability::SellLimitOrder,
+        BrokerCapability::SellStopOrder,
+        BrokerCapability::OneCancelsTheOtherOrder,
+        BrokerCapability::PositionChange,
+        BrokerCapability::LongCallPosition,
+        BrokerCapability::ShortCallPosition,
+        BrokerCapability::TrailingStopLoss,
+        BrokerCapability::TakeProfit,
+    ];
+    const STOCK_EXCHANGES: &'static [StockExchange] = &[StockExchange::LSExchange];
+
+    fn login(&mut self) -> Result<(), Error<BrokerErrorKind>> { Ok(()) }
+    fn logout(&mut self) -> Result<(), Error<BrokerErrorKind>> { Ok(()) }
+    fn is_logged_in(&self) -> bool { true }
+
+    fn all_deposits(&self) -> Result<Vec<Deposit>, Error<BrokerErrorKind>> { Ok(Vec::new()) }
+    fn update_deposit_transactions(&self, _: &mut Deposit) -> Result<(), Error<BrokerErrorKind>> { Ok(()) }
+    fn update_deposit_balance(&self, _: &mut Deposit) -> Result<(), Error<BrokerErrorKind>> { Ok(()) }
+
+    fn all_orders(&self) -> Result<&[Order], Error<BrokerErrorKind>> { Ok(&[]) }
+    fn get_order(&self) -> Result<&Order, Error<BrokerErrorKind>> { Self::error("no orders", BrokerErrorKind::NoSuchOrder) }
+
+    fn change_order(&self, _: &mut Deposit, order: u64) -> Result<(), Error<BrokerErrorKind>> {
+        self.call(format!("change {}", order));
+        if self.reject_changes.get() {
+            return Self::error("the change was rejected", BrokerErrorKind::Other);
+        }
+        Ok(())
+    }
+
+    fn delete_order(&self, deposit: &mut Deposit, order: u64) -> Result<Order, Error<BrokerErrorKind>> {
+        self.call(format!("delete {}", order));
+        match deposit.orders().iter().find(|open| open.has_id(order)) {
+            Some(open) => Ok(open.clone()),
+            None => Self::error("the order does not exist", BrokerErrorKind::NoSuchOrder),
+        }
+    }
+
+    fn update_deposit_orders(&self, _: &mut Deposit) -> Result<(), Error<BrokerErrorKind>> { Ok(()) }
+
+    fn all_positions(&self) -> Result<&[Position], Error<BrokerErrorKind>> { Ok(&[]) }
+    fn get_positions(&self) -> Result<&Position, Error<BrokerErrorKind>> { Self::error("no positions", BrokerErrorKind::NoSuchPosition) }
+    fn update_deposit_positions(&self, _: &mut Deposit) -> Result<(), Error<BrokerErrorKind>> { Ok(()) }
+
+    fn buy(&self, deposit: &mut Deposit, order: u64) -> Result<&Position, Error<BrokerErrorKind>> {
+        if self.reject_orders.get() {
+            self.call(format!("reject {}", order));
+            return Self::error("the order was rejected", BrokerErrorKind::Other);
+        }
+        let open = match deposit.remove_order(order) {
+            Some(open) => open,
+            None => return Self::error("the order does not exist", BrokerErrorKind::NoSuchOrder),
+        };
+        let data = &open.data()[self.leg.get().min(open.data().len() - 1)];
+        self.call(format!("buy {}", data.raw_id()));
+
+        let price = match data.order_type() {
+            OrderType::LimitOrder(price) | OrderType::StopOrder(price) => *price,
+            OrderType::MarketOrder => self.price.get(),
+        };
+        let position = Position::new(
+            data.raw_id().clone(),
+            data.derivative().clone(),
+            data.position_type(),
+            open.clone(),
+            Fill::new(start(), data.pieces(), price),
+        );
+
+        let mut positions = deposit.positions().clone();
+        positions.push(position.clone());
+        deposit.update_positions(positions);
+
+        // the interface returns a reference, a test can afford to leak it
+        Ok(Box::leak(Box::new(position)))
+    }
+
+    fn sell(&self, deposit: &mut Deposit, position: u64) -> Result<Position, Error<BrokerErrorKind>> {
+        self.call(format!("sell {}", position));
+        let mut positions = deposit.positions().clone();
+        let index = match positions.iter().position(|open| open.hashed_id() == position) {
+            Some(index) => index,
+            None => return Self::error("the position does not exist", BrokerErrorKind::NoSuchPosition),
+        };
+
+        let mut closed = positions.remove(index);
+        closed.close(start(), self.price.get(), Price::zero()).unwrap();
+        deposit.update_positions(positions);
+        Ok(closed)
+    }
+
+    fn sell_partially(&self, deposit: &mut Deposit, position: u64, pieces: u64) -> Result<Position, Error<BrokerErrorKind>> {
+        self.call(format!("sell {} of {}", pieces, position));
+        let mut positions = deposit.positions().clone();
+        let open = match positions.iter_mut().find(|open| open.hashed_id() == position) {
+            Some(open) => open,
+            None => return Self::error("the position does not exist", BrokerErrorKind::NoSuchPosition),
+        };
+
+        open.scale_out(Fill::new(start(), pieces, self.price.get())).unwrap();
+        let reduced = open.clone();
+        deposit.update_positions(positions);
+        Ok(reduced)
+    }
+
+    fn place_exits(&self, deposit: &mut Deposit, order: u64) -> Result<(), Error<BrokerErrorKind>> {
+        if self.reject_orders.get() {
+            self.call(format!("reject {}", order));
+            return Self::error("the exits were rejected", BrokerErrorKind::Other);
+        }
+        self.call(format!("protect {}", order));
+        if !deposit.orders().iter().any(|open| open.has_id(order)) {
+            return Self::error("the order does not exist", BrokerErrorKind::NoSuchOrder);
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,69 @@
+use std::fmt;
+use std::sync::{Arc, Mutex};
+
+use trading_utils::*;
+
+#[derive(Clone, Debug, PartialEq)]
+struct Ping(u32);
+
+impl Event for Ping {
+    fn level(&self) -> EventLevel { EventLevel::Info }
+}
+
+impl fmt::Display for Ping {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(formatter, "ping {}", self.0)
+    }
+}
+
+#[test]
+fn clones_share_their_listeners() {
+    let bus = EventBus::new();
+    let log = Arc::new(EventLog::new());
+    bus.clone().subscribe(log.clone());
+
+    bus.emit(Ping(1));
+    bus.clone().emit(Ping(2));
+
+    assert_eq!(log.events(), vec![Ping(1), Ping(2)]);
+    log.clear();
+    assert!(log.events().is_empty());
+}
+
+#[test]
+fn every_listener_receives_the_event() {
+    let bus = EventBus::new();
+    let first = Arc::new(EventLog::new());
+    let second = Arc::new(EventLog::new());
+    bus.subscribe(first.clone());
+    bus.subscribe(second.clone());
+
+    bus.emit(Ping(1));
+
+    assert_eq!(first.events(), vec![Ping(1)]);
+    assert_eq!(second.events(), vec![Ping(1)]);
+}
+
+#[test]
+fn listeners_can_subscribe_while_an_event_is_emitted() {
+    let bus: EventBus<Ping> = EventBus::new();
+    let log = Arc::new(EventLog::new());
+    let subscribed = Arc::new(Mutex::new(false));
+
+    let inner = bus.clone();
+    let inner_log = log.clone();
+    let inner_subscribed = subscribed.clone();
+    bus.subscribe(Arc::new(move |_: &Ping| {
+        let mut subscribed = inner_subscribed.lock().unwrap();
+        if !*subscribed {
+            *subscribed = true;
+            inner.subscribe(inner_log.clone());
+        }
+    }));
+
+    // the new listener only receives the following events
+    bus.emit(Ping(1));
+    bus.emit(Ping(2));
+
+    assert_eq!(log.events(), vec![Ping(2)]);
+}
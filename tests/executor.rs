@@ -0,0 +1,430 @@
+mod common;
+
+use trading_utils::*;
+
+use common::*;
+
+/// buys again after every fill
+#[derive(Default)]
+struct Pyramid {
+    fills: Vec<String>,
+    instructions: Vec<Instruction<'static>>,
+}
+
+impl AlgorithmInterface for Pyramid {
+    fn algorithm(&mut self, _: &[Position], _: &[Price]) -> Result<&[Instruction<'_>], Error<TradingErrorKind>> {
+        Ok(&[])
+    }
+
+    fn on_fill(&mut self, order: &OrderData, _: &Position) -> Result<&[Instruction<'_>], Error<TradingErrorKind>> {
+        self.fills.push(order.raw_id().clone());
+        self.instructions = vec![Instruction::Buy(market_buy(1))];
+        Ok(&self.instructions)
+    }
+}
+
+fn executor() -> Executor {
+    Executor::new(StockExchange::LSExchange).with_raw_id_prefix("test")
+}
+
+fn limit_buy(pieces: u64, price: f64) -> OrderRequest<'static> {
+    OrderRequest::new(pieces, OrderType::LimitOrder(Price::from(price)), PositionType::LongCall)
+}
+
+fn kinds(errors: &[Error<TradingErrorKind>]) -> Vec<TradingErrorKind> {
+    errors.iter().map(Error::kind).collect()
+}
+
+#[test]
+fn plan_translates_instructions_into_actions() {
+    let share = share("SAP");
+    let instructions = [
+        Instruction::Buy(market_buy(10)),
+        Instruction::CancelOrder { order_id: 42 },
+        Instruction::ModifyOrder { order_id: 42, changes: OrderChanges::default() },
+        Instruction::None,
+    ];
+    let plan = executor().plan::<TestBroker>(&instructions, &share, ExecutionPhase::Trading);
+
+    assert!(plan.rejected().is_empty());
+    assert_eq!(plan.actions().len(), 2);
+    match &plan.actions()[0] {
+        PlannedAction::Submit(order) => {
+            assert_eq!(order.data().len(), 1);
+            assert_eq!(order.data()[0].raw_id(), "test-1");
+            assert_eq!(order.data()[0].derivative().symbol, "SAP");
+        }
+        action => panic!("expected a new order, got {:?}", action),
+    }
+    assert_eq!(plan.actions()[1], PlannedAction::Cancel { order_id: 42 });
+}
+
+#[test]
+fn plan_rejects_invalid_instructions() {
+    let share = share("SAP");
+    let instructions = [
+        Instruction::OneCancelsTheOther(Vec::new()),
+        Instruction::Buy(OrderRequest::new(1, OrderType::MarketOrder, PositionType::LongPut)),
+        Instruction::Buy(market_buy(1)),
+    ];
+
+    let plan = executor().plan::<TestBroker>(&instructions, &share, ExecutionPhase::Trading);
+    assert_eq!(plan.actions().len(), 1);
+    assert_eq!(
+        kinds(plan.rejected()),
+        vec![TradingErrorKind::InvalidOrder, TradingErrorKind::UnsupportedInstruction]
+    );
+
+    let plan = executor().plan::<TestBroker>(&instructions[2..], &share, ExecutionPhase::Shutdown);
+    assert!(plan.actions().is_empty());
+    assert_eq!(kinds(plan.rejected()), vec![TradingErrorKind::BuyingInShutdown]);
+}
+
+#[test]
+fn filled_event_names_the_filled_leg() {
+    let (share, broker, mut deposit, mut executor) = (share("SAP"), TestBroker::new(100.0), deposit(), executor());
+    broker.leg.set(1);
+
+    let instructions = [Instruction::OneCancelsTheOther(vec![limit_buy(5, 95.0), limit_buy(5, 90.0)])];
+    let plan = executor.plan::<TestBroker>(&instructions, &share, ExecutionPhase::Trading);
+    let report = executor.execute(&broker, &mut deposit, &mut Pyramid::default(), &share, plan, ExecutionPhase::Trading);
+
+    match &report.events[0] {
+        OrderEvent::Filled { order, position } => {
+            assert_eq!(order.raw_id(), "test-2");
+            assert_eq!(position.id, "test-2");
+        }
+        event => panic!("expected a fill, got {}", event),
+    }
+}
+
+#[test]
+fn follow_ups_are_limited() {
+    let (share, broker, mut deposit) = (share("SAP"), TestBroker::new(100.0), deposit());
+    let mut executor = executor().with_max_follow_ups(2);
+    let mut algorithm = Pyramid::default();
+
+    let plan = executor.plan::<TestBroker>(&[Instruction::Buy(market_buy(1))], &share, ExecutionPhase::Trading);
+    let report = executor.execute(&broker, &mut deposit, &mut algorithm, &share, plan, ExecutionPhase::Trading);
+
+    // the first order and two levels of follow ups
+    assert_eq!(report.submitted.len(), 3);
+    assert_eq!(algorithm.fills, vec!["test-1", "test-2", "test-3"]);
+    assert_eq!(kinds(&report.errors), vec![TradingErrorKind::FollowUpLimit]);
+    assert_eq!(deposit.positions().len(), 3);
+}
+
+#[test]
+fn rejected_changes_are_rolled_back() {
+    let (share, broker, mut deposit, mut executor) = (share("SAP"), TestBroker::new(100.0), deposit(), executor());
+    broker.reject_changes.set(true);
+
+    let order = match &executor.plan::<TestBroker>(&[Instruction::Buy(limit_buy(5, 95.0))], &share, ExecutionPhase::Trading).actions()[0] {
+        PlannedAction::Submit(order) => order.clone(),
+        action => panic!("expected a new order, got {:?}", action),
+    };
+    let order_id = order.data()[0].id();
+    deposit.add_order(order.clone());
+
+    let changes = OrderChanges {
+        pieces: Some(10),
+        ..OrderChanges::default()
+    };
+    let plan = ExecutionPlan::new(vec![PlannedAction::Modify { order_id, changes }]);
+    let report = executor.execute(&broker, &mut deposit, &mut Pyramid::default(), &share, plan, ExecutionPhase::Trading);
+
+    assert!(report.modified.is_empty());
+    assert!(matches!(report.events[..], [OrderEvent::BrokerError { .. }]));
+    assert_eq!(deposit.orders(), &[order]);
+    assert_eq!(broker.calls(), vec![format!("change {}", order_id)]);
+}
+
+/// a broker that only buys, so it can't open short positions or close long ones
+struct BuyingBroker;
+
+impl BrokerInterface for BuyingBroker {
+    const NAME: &'static str = "buying broker";
+    const CAPABILITIES: &'static [BrokerCapability] = &[
+        BrokerCapability::OrderChange,
+        BrokerCapability::BuyMarketOrder,
+        BrokerCapability::BuyLimitOrder,
+        BrokerCapability::BuyStopOrder,
+        BrokerCapability::LongCallPosition,
+        BrokerCapability::ShortCallPosition,
+        BrokerCapability::TakeProfit,
+    ];
+    const STOCK_EXCHANGES: &'static [StockExchange] = &[StockExchange::LSExchange];
+
+    fn login(&mut self) -> Result<(), Error<BrokerErrorKind>> { unreachable!() }
+    fn logout(&mut self) -> Result<(), Error<BrokerErrorKind>> { unreachable!() }
+    fn is_logged_in(&self) -> bool { true }
+    fn all_deposits(&self) -> Result<Vec<Deposit>, Error<BrokerErrorKind>> { unreachable!() }
+    fn update_deposit_transactions(&self, _: &mut Deposit) -> Result<(), Error<BrokerErrorKind>> { unreachable!() }
+    fn update_deposit_balance(&self, _: &mut Deposit) -> Result<(), Error<BrokerErrorKind>> { unreachable!() }
+    fn all_orders(&self) -> Result<&[Order], Error<BrokerErrorKind>> { unreachable!() }
+    fn get_order(&self) -> Result<&Order, Error<BrokerErrorKind>> { unreachable!() }
+    fn change_order(&self, _: &mut Deposit, _: u64) -> Result<(), Error<BrokerErrorKind>> { unreachable!() }
+    fn delete_order(&self, _: &mut Deposit, _: u64) -> Result<Order, Error<BrokerErrorKind>> { unreachable!() }
+    fn update_deposit_orders(&self, _: &mut Deposit) -> Result<(), Error<BrokerErrorKind>> { unreachable!() }
+    fn all_positions(&self) -> Result<&[Position], Error<BrokerErrorKind>> { unreachable!() }
+    fn get_positions(&self) -> Result<&Position, Error<BrokerErrorKind>> { unreachable!() }
+    fn update_deposit_positions(&self, _: &mut Deposit) -> Result<(), Error<BrokerErrorKind>> { unreachable!() }
+    fn buy(&self, _: &mut Deposit, _: u64) -> Result<&Position, Error<BrokerErrorKind>> { unreachable!() }
+    fn sell(&self, _: &mut Deposit, _: u64) -> Result<Position, Error<BrokerErrorKind>> { unreachable!() }
+    fn sell_partially(&self, _: &mut Deposit, _: u64, _: u64) -> Result<Position, Error<BrokerErrorKind>> { unreachable!() }
+}
+
+#[test]
+fn capabilities_follow_the_direction_of_the_orders() {
+    let share = share("SAP");
+    let position = Position::from_order(Order::Single(limit_buy(10, 100.0).to_order_data("long".to_string(), &share, StockExchange::LSExchange)), start(), Price::from(100.0)).unwrap();
+    let instructions = [
+        Instruction::Buy(limit_buy(1, 100.0).with_take_profit(TakeProfit::Relative(Price::from(5.0)))),
+        Instruction::Buy(OrderRequest::new(1, OrderType::LimitOrder(Price::from(100.0)), PositionType::ShortCall)),
+        Instruction::Buy(limit_buy(1, 100.0).with_stop_loss(StopLoss::Relative(Price::from(-5.0)))),
+        Instruction::Bracket {
+            entry: OrderRequest::new(1, OrderType::StopOrder(Price::from(100.0)), PositionType::LongCall),
+            take_profit: TakeProfit::None,
+            stop_loss: StopLoss::Absolute(Price::from(95.0)),
+        },
+        Instruction::Sell { position: &position },
+    ];
+
+    let plan = executor().plan::<BuyingBroker>(&instructions[1..], &share, ExecutionPhase::Trading);
+    assert!(plan.actions().is_empty());
+    let missing: Vec<&str> = plan.rejected()
+        .iter()
+        .map(|error| error.msg().rsplit(' ').next().unwrap())
+        .collect();
+    // the short entry sells, the stop losses and the close of the long position sell as well
+    assert_eq!(missing, vec!["SellLimitOrder", "SellStopOrder", "SellStopOrder", "SellMarketOrder"]);
+
+    // the take profit of a long position sells at its limit
+    let plan = executor().plan::<BuyingBroker>(&instructions[..1], &share, ExecutionPhase::Trading);
+    assert_eq!(plan.rejected()[0].msg(), "buying broker does not support SellLimitOrder");
+
+    let plan = executor().plan::<TestBroker>(&instructions, &share, ExecutionPhase::Trading);
+    assert!(plan.rejected().is_empty());
+}
+
+#[test]
+fn changed_exits_need_the_capabilities_of_the_opposite_direction() {
+    let (share, mut deposit) = (share("SAP"), deposit());
+    let mut bracket = Bracket::new(limit_buy(10, 100.0)
+        .with_stop_loss(StopLoss::Relative(Price::from(-5.0)))
+        .to_order_data("entry".to_string(), &share, StockExchange::LSExchange));
+    let position = Position::from_order(Order::Bracket(bracket.clone()), start(), Price::from(100.0)).unwrap();
+    bracket.activate(&position, Price::from(100.0));
+    let stop_id = bracket.stop_loss_order().unwrap().id();
+    deposit.update_positions(vec![position]);
+    deposit.add_order(Order::Bracket(bracket.clone()));
+
+    // moving the stop of a long position changes a sell stop order
+    let changes = OrderChanges {
+        order_type: Some(OrderType::StopOrder(Price::from(97.0))),
+        ..OrderChanges::default()
+    };
+    let plan = ExecutionPlan::new(vec![PlannedAction::Modify { order_id: stop_id, changes: changes.clone() }]);
+    let report = executor().execute(&BuyingBroker, &mut deposit, &mut Pyramid::default(), &share, plan, ExecutionPhase::Trading);
+    assert_eq!(kinds(&report.errors), vec![TradingErrorKind::UnsupportedInstruction]);
+    assert_eq!(deposit.orders(), &[Order::Bracket(bracket)]);
+
+    let broker = TestBroker::new(100.0);
+    let plan = ExecutionPlan::new(vec![PlannedAction::Modify { order_id: stop_id, changes }]);
+    let report = executor().execute(&broker, &mut deposit, &mut Pyramid::default(), &share, plan, ExecutionPhase::Trading);
+    assert_eq!(report.modified, vec![stop_id]);
+}
+
+/// records the triggered exits and buys again after each of them
+#[derive(Default)]
+struct Exits {
+    triggered: Vec<String>,
+    instructions: Vec<Instruction<'static>>,
+}
+
+impl Exits {
+    fn buy_again(&mut self, exit: &str, position: &Position) -> Result<&[Instruction<'_>], Error<TradingErrorKind>> {
+        self.triggered.push(format!("{} {}", exit, position.id));
+        self.instructions = vec![Instruction::Buy(market_buy(1))];
+        Ok(&self.instructions)
+    }
+}
+
+impl AlgorithmInterface for Exits {
+    fn algorithm(&mut self, _: &[Position], _: &[Price]) -> Result<&[Instruction<'_>], Error<TradingErrorKind>> {
+        Ok(&[])
+    }
+
+    fn on_stop_loss_triggered(&mut self, position: &Position) -> Result<&[Instruction<'_>], Error<TradingErrorKind>> {
+        self.buy_again("stop loss", position)
+    }
+
+    fn on_take_profit_triggered(&mut self, position: &Position) -> Result<&[Instruction<'_>], Error<TradingErrorKind>> {
+        self.buy_again("take profit", position)
+    }
+}
+
+/// a bracket at 50 with the given distances of its take profit and its stop loss
+fn bracket(raw_id: &str, take_profit: f64, stop_loss: f64) -> Bracket {
+    Bracket::new(limit_buy(10, 50.0)
+        .with_take_profit(TakeProfit::Relative(Price::from(take_profit)))
+        .with_stop_loss(StopLoss::Relative(Price::from(stop_loss)))
+        .to_order_data(raw_id.to_string(), &share("SAP"), StockExchange::LSExchange))
+}
+
+fn candle(minute: i64, open: f64, high: f64, low: f64) -> Candle {
+    Candle::new(start() + chrono::Duration::minutes(minute), Price::from(open), Price::from(high), Price::from(low), Price::from(open), 0)
+}
+
+#[test]
+fn exits_of_the_matching_engine_reach_the_algorithm() {
+    let (share, broker, mut deposit, mut executor) = (share("SAP"), TestBroker::new(50.0), deposit(), executor());
+    let mut engine = MatchingEngine::new();
+    let mut algorithm = Exits::default();
+    deposit.add_order(Order::Bracket(bracket("wide-stop", 10.0, -2.0)));
+    deposit.add_order(Order::Bracket(bracket("wide-profit", 2.0, -10.0)));
+
+    // both entries are filled at 50, the first exits are at 52 and 48
+    let mut reports = Vec::new();
+    for candle in [candle(0, 50.0, 50.0, 49.0), candle(1, 50.0, 52.0, 49.0), candle(2, 49.0, 49.0, 46.0)] {
+        let events = engine.process(&mut deposit, "SAP", &candle);
+        reports.push(executor.dispatch(&broker, &mut deposit, &mut algorithm, &share, events, ExecutionPhase::Trading));
+    }
+
+    assert_eq!(algorithm.triggered, vec!["take profit wide-profit", "stop loss wide-stop"]);
+    assert!(matches!(reports[1].events[..], [OrderEvent::TakeProfitTriggered { .. }, OrderEvent::Filled { .. }, OrderEvent::Cancelled { .. }]));
+    // the follow ups are executed right after their event
+    assert_eq!(reports[1].submitted.len(), 1);
+    assert_eq!(reports[2].submitted.len(), 1);
+    assert_eq!(broker.calls(), vec!["buy test-1", "buy test-2"]);
+    assert!(deposit.orders().is_empty());
+}
+
+/// a test broker that lists its orders and positions, the positions of `closed` are gone
+struct ClosingBroker {
+    inner: TestBroker,
+    closed: std::cell::RefCell<Vec<String>>,
+}
+
+impl BrokerInterface for ClosingBroker {
+    const NAME: &'static str = "closing broker";
+    const CAPABILITIES: &'static [BrokerCapability] = &[
+        BrokerCapability::OrderOverview,
+        BrokerCapability::PositionOverview,
+        BrokerCapability::BuyMarketOrder,
+        BrokerCapability::LongCallPosition,
+    ];
+    const STOCK_EXCHANGES: &'static [StockExchange] = &[StockExchange::LSExchange];
+
+    fn login(&mut self) -> Result<(), Error<BrokerErrorKind>> { self.inner.login() }
+    fn logout(&mut self) -> Result<(), Error<BrokerErrorKind>> { self.inner.logout() }
+    fn is_logged_in(&self) -> bool { true }
+    fn all_deposits(&self) -> Result<Vec<Deposit>, Error<BrokerErrorKind>> { self.inner.all_deposits() }
+    fn update_deposit_transactions(&self, deposit: &mut Deposit) -> Result<(), Error<BrokerErrorKind>> { self.inner.update_deposit_transactions(deposit) }
+    fn update_deposit_balance(&self, deposit: &mut Deposit) -> Result<(), Error<BrokerErrorKind>> { self.inner.update_deposit_balance(deposit) }
+    fn all_orders(&self) -> Result<&[Order], Error<BrokerErrorKind>> { self.inner.all_orders() }
+    fn get_order(&self) -> Result<&Order, Error<BrokerErrorKind>> { self.inner.get_order() }
+    fn change_order(&self, deposit: &mut Deposit, order: u64) -> Result<(), Error<BrokerErrorKind>> { self.inner.change_order(deposit, order) }
+    fn delete_order(&self, deposit: &mut Deposit, order: u64) -> Result<Order, Error<BrokerErrorKind>> { self.inner.delete_order(deposit, order) }
+
+    fn update_deposit_orders(&self, deposit: &mut Deposit) -> Result<(), Error<BrokerErrorKind>> {
+        // the broker dropped both exits of the closed positions
+        let closed = self.closed.borrow();
+        let orders = deposit.orders()
+            .iter()
+            .filter(|order| !matches!(order, Order::Bracket(bracket) if bracket.position().is_some_and(|id| closed.contains(id))))
+            .cloned()
+            .collect();
+        deposit.update_orders(orders);
+        Ok(())
+    }
+
+    fn all_positions(&self) -> Result<&[Position], Error<BrokerErrorKind>> { self.inner.all_positions() }
+    fn get_positions(&self) -> Result<&Position, Error<BrokerErrorKind>> { self.inner.get_positions() }
+
+    fn update_deposit_positions(&self, deposit: &mut Deposit) -> Result<(), Error<BrokerErrorKind>> {
+        let closed = self.closed.borrow();
+        let positions = deposit.positions()
+            .iter()
+            .filter(|position| !closed.contains(&position.id))
+            .cloned()
+            .collect();
+        deposit.update_positions(positions);
+        Ok(())
+    }
+
+    fn buy(&self, deposit: &mut Deposit, order: u64) -> Result<&Position, Error<BrokerErrorKind>> { self.inner.buy(deposit, order) }
+    fn sell(&self, deposit: &mut Deposit, position: u64) -> Result<Position, Error<BrokerErrorKind>> { self.inner.sell(deposit, position) }
+    fn sell_partially(&self, deposit: &mut Deposit, position: u64, pieces: u64) -> Result<Position, Error<BrokerErrorKind>> { self.inner.sell_partially(deposit, position, pieces) }
+}
+
+#[test]
+fn exits_filled_by_the_broker_reach_the_algorithm() {
+    let (share, mut deposit, mut executor) = (share("SAP"), deposit(), executor());
+    let broker = ClosingBroker { inner: TestBroker::new(50.0), closed: Default::default() };
+    let mut algorithm = Exits::default();
+    for mut bracket in [bracket("wide-stop", 10.0, -2.0), bracket("wide-profit", 2.0, -10.0)] {
+        let position = Position::from_order(Order::Bracket(bracket.clone()), start(), Price::from(50.0)).unwrap();
+        bracket.activate(&position, Price::from(50.0));
+        deposit.update_positions([deposit.positions().clone(), vec![position]].concat());
+        deposit.add_order(Order::Bracket(bracket));
+    }
+
+    // the broker closed a position, the last close tells which exit did it
+    broker.closed.borrow_mut().push("wide-profit".to_string());
+    executor.update_candle("SAP", &candle(0, 51.5, 52.0, 51.0));
+    let report = executor.execute(&broker, &mut deposit, &mut algorithm, &share, ExecutionPlan::new(Vec::new()), ExecutionPhase::Trading);
+    match &report.events[..] {
+        [OrderEvent::TakeProfitTriggered { position }, OrderEvent::Filled { .. }, OrderEvent::Cancelled { order }] => {
+            assert!(!position.is_open());
+            assert_eq!(position.realized(), Price::from(20.0));
+            assert_eq!(order.raw_id(), "wide-profit-stop-loss");
+        }
+        events => panic!("expected the take profit and the follow up, got {:?}", events),
+    }
+
+    broker.closed.borrow_mut().push("wide-stop".to_string());
+    executor.update_candle("SAP", &candle(1, 48.5, 49.0, 47.0));
+    let report = executor.execute(&broker, &mut deposit, &mut algorithm, &share, ExecutionPlan::new(Vec::new()), ExecutionPhase::Trading);
+    assert!(matches!(report.events[..], [OrderEvent::StopLossTriggered { .. }, OrderEvent::Filled { .. }, OrderEvent::Cancelled { .. }]));
+
+    assert_eq!(algorithm.triggered, vec!["take profit wide-profit", "stop loss wide-stop"]);
+    assert_eq!(broker.inner.calls(), vec!["buy test-1", "buy test-2"]);
+    // only the positions of the follow ups are left
+    assert!(deposit.orders().is_empty());
+    assert_eq!(deposit.positions().len(), 2);
+}
+
+#[test]
+fn exits_for_open_positions_are_checked() {
+    let (share, mut deposit) = (share("SAP"), deposit());
+    let position = Position::from_order(Order::Single(limit_buy(10, 100.0).to_order_data("long".to_string(), &share, StockExchange::LSExchange)), start(), Price::from(100.0)).unwrap();
+    deposit.update_positions(vec![position.clone()]);
+    let mut bracket = Bracket::new(limit_buy(10, 100.0)
+        .with_stop_loss(StopLoss::Relative(Price::from(-5.0)))
+        .to_order_data("protective".to_string(), &share, StockExchange::LSExchange));
+    bracket.activate(&position, Price::from(100.0));
+    let exits = || ExecutionPlan::new(vec![PlannedAction::Submit(Order::Bracket(bracket.clone()))]);
+
+    // the stop of a long position sells
+    let report = executor().execute(&BuyingBroker, &mut deposit, &mut Pyramid::default(), &share, exits(), ExecutionPhase::Shutdown);
+    assert_eq!(kinds(&report.errors), vec![TradingErrorKind::UnsupportedInstruction]);
+
+    let broker = TestBroker::new(100.0);
+    broker.reject_orders.set(true);
+    let report = executor().execute(&broker, &mut deposit, &mut Pyramid::default(), &share, exits(), ExecutionPhase::Shutdown);
+    assert!(report.submitted.is_empty());
+    assert!(matches!(report.events[..], [OrderEvent::Rejected { .. }]));
+    assert!(deposit.orders().is_empty());
+
+    broker.reject_orders.set(false);
+    let report = executor().execute(&broker, &mut deposit, &mut Pyramid::default(), &share, exits(), ExecutionPhase::Shutdown);
+    assert_eq!(report.submitted, vec![Order::Bracket(bracket.clone())]);
+    assert!(report.events.is_empty());
+    assert_eq!(deposit.orders().len(), 1);
+
+    // exits need an open position
+    deposit.update_positions(Vec::new());
+    let report = executor().execute(&broker, &mut deposit, &mut Pyramid::default(), &share, exits(), ExecutionPhase::Shutdown);
+    assert_eq!(kinds(&report.errors), vec![TradingErrorKind::NoSuchPosition]);
+}
@@ -0,0 +1,156 @@
+mod common;
+
+use chrono::{DateTime, Duration, Local};
+use trading_utils::*;
+
+use common::*;
+
+fn seconds(seconds: i64) -> DateTime<Local> {
+    start() + Duration::seconds(seconds)
+}
+
+fn polled(updates: Vec<FeedUpdate>) -> Vec<(String, DateTime<Local>)> {
+    updates
+        .into_iter()
+        .map(|update| (update.symbol, update.candle.time))
+        .collect()
+}
+
+fn update(symbol: &str, seconds: i64) -> (String, DateTime<Local>) {
+    (symbol.to_string(), self::seconds(seconds))
+}
+
+/// the candles of SAP every minute and those of BMW half a minute later
+fn memory_feed() -> MemoryFeed {
+    let bmw = candles(&[50.0, 51.0, 52.0, 53.0])
+        .into_iter()
+        .map(|candle| Candle { time: candle.time + Duration::seconds(30), ..candle })
+        .collect();
+    MemoryFeed::new()
+        .with_candles("SAP", candles(&[100.0, 101.0, 102.0, 103.0]))
+        .with_candles("BMW", bmw)
+}
+
+#[test]
+fn memory_feed_polls_every_candle_once() {
+    let mut feed = memory_feed();
+    assert!(feed.poll(seconds(600)).unwrap().is_empty());
+
+    feed.subscribe(&share("SAP")).unwrap();
+    feed.subscribe(&share("BMW")).unwrap();
+    assert_eq!(polled(feed.poll(seconds(60)).unwrap()), vec![update("SAP", 0), update("BMW", 30), update("SAP", 60)]);
+    assert!(feed.poll(seconds(60)).unwrap().is_empty());
+
+    // candles that are older than the polled ones are skipped
+    feed.push("SAP", Candle::from_price(seconds(45), Price::from(100.5)));
+    feed.push("SAP", Candle::from_price(seconds(240), Price::from(104.0)));
+    assert_eq!(polled(feed.poll(seconds(150)).unwrap()), vec![update("BMW", 90), update("SAP", 120), update("BMW", 150)]);
+    assert_eq!(feed.candles("SAP").len(), 6);
+
+    let history = feed.history(&share("SAP"), seconds(45), seconds(180)).unwrap();
+    assert_eq!(history.iter().map(|candle| candle.time).collect::<Vec<_>>(), vec![seconds(45), seconds(60), seconds(120)]);
+}
+
+#[test]
+fn csv_feed_parses_every_format() {
+    let csv = "\
+time,open,high,low,close,volume
+# a comment
+
+2021-03-01T10:00:00+01:00,100,102,99,101,1500
+2021-03-01 10:01:00, 101, 103, 100, 102
+1614589320,102.5
+";
+    let candles = CsvFeed::parse(csv.as_bytes()).unwrap();
+
+    assert_eq!(candles.len(), 3);
+    assert_eq!(candles[0], Candle::new(
+        DateTime::parse_from_rfc3339("2021-03-01T10:00:00+01:00").unwrap().with_timezone(&Local),
+        Price::from(100.0), Price::from(102.0), Price::from(99.0), Price::from(101.0), 1500,
+    ));
+    assert_eq!(candles[1].time, seconds(60));
+    assert_eq!((candles[1].close, candles[1].volume), (Price::from(102.0), 0));
+    assert_eq!(candles[2].time.timestamp(), 1_614_589_320);
+    assert_eq!(candles[2].close, Price::from(102.5));
+}
+
+#[test]
+fn csv_feed_skips_the_header_after_comments() {
+    let csv = "\
+# exported candles
+
+time,price
+2021-03-01 10:00:00,100
+";
+    let candles = CsvFeed::parse(csv.as_bytes()).unwrap();
+    assert_eq!(candles.len(), 1);
+    assert_eq!(candles[0].close, Price::from(100.0));
+
+    // a header after the first candle is invalid
+    let error = CsvFeed::parse("2021-03-01 10:00:00,100\ntime,price".as_bytes()).unwrap_err();
+    assert_eq!(error.msg(), "line 2: invalid time `time`");
+}
+
+#[test]
+fn csv_feed_reports_the_invalid_line() {
+    let invalid = [
+        ("time,price\n2021-03-01 10:00:00,100\n2021-03-01 10:01:00,abc", "line 3: invalid price `abc`"),
+        ("2021-03-01 10:00:00,100,101", "line 1: expected 2, 5 or 6 columns, found 3"),
+        ("yesterday,100", "line 1: invalid time `yesterday`"),
+        ("2021-03-01 10:00:00,100,102,99,101,-5", "line 1: invalid volume `-5`"),
+    ];
+
+    for (csv, msg) in invalid.iter() {
+        let error = CsvFeed::parse(csv.as_bytes()).unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::InvalidData);
+        assert_eq!(error.msg(), *msg);
+    }
+}
+
+#[test]
+fn csv_feed_reads_files() {
+    let path = std::env::temp_dir().join(format!("trading-utils-csv-feed-{}.csv", std::process::id()));
+    std::fs::write(&path, "2021-03-01 10:00:00,100\n2021-03-01 10:01:00,101\n").unwrap();
+
+    let mut feed = CsvFeed::new().with_file("SAP", &path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(feed.memory_feed().candles("SAP").len(), 2);
+
+    feed.subscribe(&share("SAP")).unwrap();
+    assert_eq!(polled(feed.poll(seconds(60)).unwrap()), vec![update("SAP", 0), update("SAP", 60)]);
+
+    assert!(CsvFeed::new().with_file("SAP", &path).is_err());
+}
+
+#[test]
+fn replay_feed_plays_the_history_back_in_order() {
+    let now = start() + Duration::days(1);
+    let mut feed = ReplayFeed::new(memory_feed(), start(), 60.0).unwrap();
+    feed.subscribe(&share("SAP")).unwrap();
+    feed.subscribe(&share("BMW")).unwrap();
+
+    // nothing before the start of the replay is known
+    assert!(feed.history(&share("SAP"), seconds(-600), seconds(600)).unwrap().is_empty());
+
+    assert_eq!(polled(feed.poll(now).unwrap()), vec![update("SAP", 0)]);
+    // a second replays a minute
+    assert_eq!(polled(feed.poll(now + Duration::seconds(1)).unwrap()), vec![update("BMW", 30), update("SAP", 60)]);
+    assert!(feed.poll(now + Duration::seconds(1)).unwrap().is_empty());
+    assert_eq!(
+        polled(feed.poll(now + Duration::seconds(3)).unwrap()),
+        vec![update("BMW", 90), update("SAP", 120), update("BMW", 150), update("SAP", 180)]
+    );
+    assert_eq!(feed.replayed(), Some(seconds(180)));
+
+    // the future of the replay is unknown
+    let history = feed.history(&share("BMW"), seconds(-600), seconds(600)).unwrap();
+    assert_eq!(history.iter().map(|candle| candle.time).collect::<Vec<_>>(), vec![seconds(30), seconds(90), seconds(150)]);
+}
+
+#[test]
+fn replay_feed_needs_a_positive_speed() {
+    for speed in [0.0, -1.0, f64::NAN, f64::INFINITY].iter() {
+        let error = ReplayFeed::new(MemoryFeed::new(), start(), *speed).unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::InvalidParameter);
+    }
+}
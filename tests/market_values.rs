@@ -0,0 +1,18 @@
+use trading_utils::*;
+
+#[test]
+fn dividing_by_a_percent_divides_the_value() {
+    assert_eq!(Price::from(100.0) / Percent::from(0.05), Price::from(2_000.0));
+    assert_eq!(Points::from(100.0) / Percent::from(0.05), Points::from(2_000.0));
+
+    let mut price = Price::from(100.0);
+    price /= Percent::from(0.05);
+    assert_eq!(price, Price::from(2_000.0));
+}
+
+#[test]
+fn multiplying_by_a_percent_takes_the_percentage() {
+    assert_eq!(Price::from(100.0) * Percent::from(0.05), Price::from(5.0));
+    assert_eq!(Price::from(100.0) + Percent::from(0.05), Price::from(105.0));
+    assert_eq!(Price::from(100.0) - Percent::from(0.05), Price::from(95.0));
+}
@@ -0,0 +1,164 @@
+mod common;
+
+use chrono::Duration;
+use trading_utils::*;
+
+use common::*;
+
+fn bracket(position_type: PositionType, take_profit: TakeProfit, stop_loss: StopLoss) -> Order {
+    Order::Bracket(Bracket::new(OrderData::new(
+        "entry".to_string(), share("SAP"), StockExchange::LSExchange, 10,
+        OrderType::LimitOrder(Price::from(50.0)), position_type, take_profit, stop_loss,
+        OrderMoment::Instant, OrderValidity::OneDay,
+    )))
+}
+
+fn candle(minute: i64, open: f64, high: f64, low: f64) -> Candle {
+    Candle::new(start() + Duration::minutes(minute), Price::from(open), Price::from(high), Price::from(low), Price::from(open), 0)
+}
+
+/// the cash of the deposit according to its transactions
+fn booked(deposit: &Deposit) -> Price {
+    Transaction::cash_balances(deposit.transactions())
+        .into_iter()
+        .map(|(_, balance)| balance)
+        .fold(Price::zero(), |sum, balance| sum + balance)
+}
+
+fn kinds(deposit: &Deposit) -> Vec<TransactionKind> {
+    deposit.transactions().iter().map(|transaction| transaction.kind).collect()
+}
+
+#[test]
+fn fills_are_booked_as_transactions() {
+    let mut engine = MatchingEngine::new().with_fee(Price::from(1.0));
+    let mut deposit = deposit();
+    deposit.add_order(bracket(PositionType::LongCall, TakeProfit::Relative(Price::from(5.0)), StopLoss::Relative(Price::from(-2.0))));
+
+    engine.process(&mut deposit, "SAP", &candle(0, 49.0, 51.0, 48.0));
+    assert_eq!(deposit.balance(), Price::from(10_000.0 - 490.0 - 1.0));
+    assert_eq!(booked(&deposit), deposit.balance());
+    assert_eq!(kinds(&deposit), vec![TransactionKind::CashIn, TransactionKind::Buy, TransactionKind::Fee]);
+    assert_eq!(deposit.transactions()[1].amount, Price::from(-490.0));
+
+    // the stop loss at 47 is filled
+    engine.process(&mut deposit, "SAP", &candle(1, 48.0, 48.0, 46.0));
+    assert_eq!(deposit.balance(), Price::from(10_000.0 - 490.0 + 470.0 - 2.0));
+    assert_eq!(booked(&deposit), deposit.balance());
+    assert_eq!(deposit.transactions()[3].kind, TransactionKind::Sell);
+    assert_eq!(deposit.transactions()[3].id, "entry-stop-loss");
+
+    // the portfolio counts the transactions, so the cash matches the balance
+    let portfolio = Portfolio::compute([&deposit], &Default::default(), &ExchangeRates::new(Currency::EUR)).unwrap();
+    assert_eq!(portfolio.cash(), deposit.balance());
+}
+
+#[test]
+fn short_fills_are_booked_as_sells() {
+    let mut engine = MatchingEngine::new();
+    let mut deposit = Deposit::empty("deposit".to_string(), Currency::EUR);
+    deposit.update_transactions(vec![Transaction::new("cash".to_string(), start(), TransactionKind::CashIn, Price::from(1_000.0), Currency::EUR)]);
+    deposit.update_balance(Price::from(1_000.0));
+    deposit.add_order(bracket(PositionType::ShortCall, TakeProfit::Relative(Price::from(-5.0)), StopLoss::None));
+
+    // a short limit order sells at 50 or better
+    engine.process(&mut deposit, "SAP", &candle(0, 51.0, 52.0, 50.0));
+    assert_eq!(deposit.balance(), Price::from(1_510.0));
+    assert_eq!(kinds(&deposit), vec![TransactionKind::CashIn, TransactionKind::Sell]);
+
+    // the take profit buys back at 46
+    engine.process(&mut deposit, "SAP", &candle(1, 47.0, 47.0, 45.0));
+    assert_eq!(deposit.balance(), Price::from(1_510.0 - 460.0));
+    assert_eq!(booked(&deposit), deposit.balance());
+}
+
+#[test]
+fn brackets_without_exits_are_done_once_filled() {
+    let mut engine = MatchingEngine::new();
+    let mut deposit = deposit();
+    deposit.add_order(bracket(PositionType::LongCall, TakeProfit::None, StopLoss::None));
+
+    let events = engine.process(&mut deposit, "SAP", &candle(0, 49.0, 51.0, 48.0));
+    assert!(matches!(events[..], [OrderEvent::Filled { .. }]));
+    assert!(deposit.orders().is_empty());
+    assert_eq!(deposit.positions().len(), 1);
+}
+
+#[test]
+fn exits_close_the_open_pieces() {
+    let mut engine = MatchingEngine::new();
+    let mut deposit = deposit();
+    deposit.add_order(bracket(PositionType::LongCall, TakeProfit::None, StopLoss::Relative(Price::from(-2.0))));
+    engine.process(&mut deposit, "SAP", &candle(0, 49.0, 51.0, 48.0));
+
+    // 4 of the 10 pieces were sold since the bracket was activated
+    let mut positions = deposit.positions().clone();
+    positions[0].scale_out(Fill::new(start(), 4, Price::from(49.0))).unwrap();
+    deposit.update_positions(positions);
+    let balance = deposit.balance();
+
+    let events = engine.process(&mut deposit, "SAP", &candle(1, 48.0, 48.0, 46.0));
+    match &events[0] {
+        OrderEvent::StopLossTriggered { position } => assert_eq!(position.fills().last().unwrap().pieces, 6),
+        event => panic!("expected the stop loss, got {}", event),
+    }
+    assert_eq!(deposit.balance(), balance + Price::from(6.0 * 47.0));
+    assert_eq!(deposit.transactions().last().unwrap().amount, Price::from(6.0 * 47.0));
+}
+
+#[test]
+fn expired_orders_are_cancelled() {
+    let mut engine = MatchingEngine::new();
+    let mut deposit = deposit();
+    let order = |raw_id: &str, validity| Order::Single(OrderData::new(
+        raw_id.to_string(), share("SAP"), StockExchange::LSExchange, 10,
+        OrderType::LimitOrder(Price::from(50.0)), PositionType::LongCall, TakeProfit::None, StopLoss::None,
+        OrderMoment::Instant, validity,
+    ));
+    deposit.add_order(order("day", OrderValidity::OneDay));
+    deposit.add_order(order("forever", OrderValidity::Forever));
+
+    assert!(engine.process(&mut deposit, "SAP", &candle(0, 60.0, 61.0, 59.0)).is_empty());
+    assert!(engine.process(&mut deposit, "SAP", &candle(60 * 24 - 1, 60.0, 61.0, 59.0)).is_empty());
+    assert_eq!(deposit.orders().len(), 2);
+
+    // a day after it was placed the order isn't valid anymore, even though the candle reaches it
+    let events = engine.process(&mut deposit, "SAP", &candle(60 * 24, 50.0, 51.0, 49.0));
+    match &events[..] {
+        [OrderEvent::Cancelled { order }, OrderEvent::Filled { position, .. }] => {
+            assert_eq!(order.raw_id(), "day");
+            assert_eq!(position.id, "forever");
+        }
+        events => panic!("expected a cancelled and a filled order, got {:?}", events),
+    }
+    assert!(deposit.orders().is_empty());
+    assert_eq!(deposit.positions().len(), 1);
+}
+
+#[test]
+fn legs_are_only_checked_with_candles_of_their_symbol() {
+    let mut engine = MatchingEngine::new();
+    let mut deposit = deposit();
+    let leg = |symbol: &str, limit: f64| OrderData::new(
+        format!("{}-leg", symbol), share(symbol), StockExchange::LSExchange, 10,
+        OrderType::LimitOrder(Price::from(limit)), PositionType::LongCall, TakeProfit::None, StopLoss::None,
+        OrderMoment::Instant, OrderValidity::OneDay,
+    );
+    deposit.add_order(Order::OneCancelsTheOther(vec![leg("SAP", 50.0), leg("BMW", 80.0)]));
+
+    // the candle of SAP would reach the limit of BMW
+    assert!(engine.process(&mut deposit, "SAP", &candle(0, 75.0, 76.0, 70.0)).is_empty());
+    assert_eq!(deposit.orders().len(), 1);
+    assert!(engine.process(&mut deposit, "BMW", &candle(0, 85.0, 86.0, 81.0)).is_empty());
+
+    let events = engine.process(&mut deposit, "BMW", &candle(1, 81.0, 82.0, 79.0));
+    match &events[..] {
+        [OrderEvent::Cancelled { order: cancelled }, OrderEvent::Filled { order, position }] => {
+            assert_eq!(order.raw_id(), "BMW-leg");
+            assert_eq!(position.entry_price(), Price::from(80.0));
+            assert_eq!(cancelled.raw_id(), "SAP-leg");
+        }
+        events => panic!("expected the fill of BMW, got {:?}", events),
+    }
+    assert!(deposit.orders().is_empty());
+}
@@ -0,0 +1,92 @@
+mod common;
+
+use chrono::Duration;
+use trading_utils::*;
+
+use common::*;
+
+#[derive(Default)]
+struct Pairs {
+    derivatives: Vec<String>,
+    collected: Vec<usize>,
+    traded: Vec<usize>,
+    shut_down: bool,
+    instructions: Vec<Instruction<'static>>,
+}
+
+impl AlgorithmInterface for Pairs {
+    fn algorithm(&mut self, _: &[Position], _: &[Price]) -> Result<&[Instruction<'_>], Error<TradingErrorKind>> {
+        unreachable!("the runner uses the multi asset interface")
+    }
+
+    fn as_multi_asset(&mut self) -> Option<&mut dyn MultiAssetAlgorithmInterface> {
+        Some(self)
+    }
+}
+
+impl MultiAssetAlgorithmInterface for Pairs {
+    fn init(&mut self, derivatives: &[Derivative], _: Duration) -> Result<(), Error<TradingErrorKind>> {
+        self.derivatives = derivatives
+            .iter()
+            .map(|derivative| derivative.symbol.clone())
+            .collect();
+        Ok(())
+    }
+
+    fn collect_prices(&mut self, series: &AlignedSeries) -> Result<(), Error<TradingErrorKind>> {
+        self.collected.push(series.len());
+        Ok(())
+    }
+
+    fn trade(&mut self, context: &MultiAssetContext<'_>) -> Result<&[Instruction<'_>], Error<TradingErrorKind>> {
+        let (sap, bmw) = (context.prices("SAP").unwrap(), context.prices("BMW").unwrap());
+        assert_eq!(sap.len(), bmw.len());
+        self.traded.push(sap.len());
+
+        // buys the cheaper share once
+        self.instructions.clear();
+        if self.traded.len() == 1 && bmw.last() < sap.last() {
+            let bmw = Box::leak(Box::new(context.derivative("BMW").unwrap().clone()));
+            self.instructions.push(Instruction::Buy(market_buy(10).for_derivative(bmw)));
+        }
+        Ok(&self.instructions)
+    }
+
+    fn shutdown(&mut self, _: &MultiAssetContext<'_>) -> Result<&[Instruction<'_>], Error<TradingErrorKind>> {
+        self.shut_down = true;
+        Ok(&[])
+    }
+}
+
+#[test]
+fn runner_aligns_the_derivatives_of_a_multi_asset_algorithm() {
+    let bmw = candles(&[50.0, 51.0, 52.0, 53.0, 54.0]);
+    let feed = MemoryFeed::new()
+        .with_candles("SAP", candles(&[100.0, 101.0, 102.0, 103.0, 104.0]))
+        // the third candle of BMW is missing, so the third timestamp can't be aligned
+        .with_candles("BMW", vec![bmw[0], bmw[1], bmw[3], bmw[4]]);
+
+    let mut runner = Runner::new(Pairs::default(), TestBroker::new(50.0), feed, deposit(), share("SAP"), Duration::minutes(1))
+        .with_derivatives(vec![share("BMW")])
+        .with_data_length(2, 3)
+        .with_end(start() + Duration::minutes(4))
+        .with_end_of_session(EndOfSession::KeepOpen)
+        .with_clock(ManualClock::new(start()));
+    let report = runner.run().unwrap();
+
+    let algorithm = runner.algorithm();
+    assert_eq!(algorithm.derivatives, vec!["SAP".to_string(), "BMW".to_string()]);
+    assert_eq!(algorithm.collected, vec![1]);
+    // the window is capped at three aligned timestamps
+    assert_eq!(algorithm.traded, vec![2, 3, 3]);
+    assert!(algorithm.shut_down);
+
+    assert_eq!(report.steps, 4);
+    assert_eq!(report.warm_up_steps, 1);
+    assert!(report.error.is_none());
+    assert_eq!(report.trading.submitted.len(), 1);
+    assert_eq!(runner.deposit().positions()[0].derivative().symbol, "BMW");
+
+    let times = runner.aligned_series().times();
+    assert_eq!(times, &[bmw[1].time, bmw[3].time, bmw[4].time]);
+}
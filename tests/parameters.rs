@@ -0,0 +1,62 @@
+use trading_utils::*;
+
+const DEFINITIONS: &[ParameterDefinition] = &[
+    ParameterDefinition::integer("period", "the length of the moving average", 20, 2, 200),
+    ParameterDefinition::float("threshold", "the minimal cross over", 0.5, 0.0, 1.0),
+    ParameterDefinition::boolean("short", "allows short positions", false),
+];
+
+#[test]
+fn defaults_fill_missing_values() {
+    let parameters = Parameters::resolve(DEFINITIONS, &Parameters::empty().with("period", 50_i64)).unwrap();
+
+    assert_eq!(parameters.integer("period"), Some(50));
+    assert_eq!(parameters.float("threshold"), Some(0.5));
+    assert_eq!(parameters.boolean("short"), Some(false));
+    assert_eq!(Parameters::resolve(DEFINITIONS, &Parameters::empty()).unwrap(), Parameters::defaults(DEFINITIONS));
+}
+
+#[test]
+fn bounds_are_inclusive() {
+    for period in [2_i64, 200] {
+        assert!(Parameters::resolve(DEFINITIONS, &Parameters::empty().with("period", period)).is_ok());
+    }
+    for period in [1_i64, 201] {
+        let error = Parameters::resolve(DEFINITIONS, &Parameters::empty().with("period", period)).unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::InvalidParameter);
+    }
+
+    assert!(DEFINITIONS[1].validate(ParameterValue::Float(1.0)).is_ok());
+    assert!(DEFINITIONS[1].validate(ParameterValue::Float(-0.1)).is_err());
+    assert!(DEFINITIONS[1].validate(ParameterValue::Float(1.1)).is_err());
+}
+
+#[test]
+fn wrong_types_are_rejected() {
+    assert!(DEFINITIONS[0].validate(ParameterValue::Float(20.0)).is_err());
+    assert!(DEFINITIONS[1].validate(ParameterValue::Integer(0)).is_err());
+    assert!(DEFINITIONS[2].validate(ParameterValue::Integer(1)).is_err());
+
+    let error = Parameters::resolve(DEFINITIONS, &Parameters::empty().with("short", 1.0)).unwrap_err();
+    assert_eq!(error.kind(), ErrorKind::InvalidParameter);
+}
+
+#[test]
+fn unknown_parameters_are_rejected() {
+    let error = Parameters::resolve(DEFINITIONS, &Parameters::empty().with("periods", 20_i64)).unwrap_err();
+    assert_eq!(error.kind(), ErrorKind::InvalidParameter);
+    assert!(error.msg().contains("periods"));
+}
+
+#[test]
+fn non_finite_floats_are_rejected() {
+    for float in [f64::NAN, f64::INFINITY, f64::NEG_INFINITY] {
+        let error = DEFINITIONS[1].validate(ParameterValue::Float(float)).unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::InvalidParameter);
+    }
+
+    // without bounds NaN would pass every comparison as well
+    let unbounded = ParameterDefinition { min: None, max: None, ..DEFINITIONS[1] };
+    assert!(unbounded.validate(ParameterValue::Float(f64::NAN)).is_err());
+    assert!(Parameters::resolve(DEFINITIONS, &Parameters::empty().with("threshold", f64::NAN)).is_err());
+}
@@ -0,0 +1,50 @@
+mod common;
+
+use trading_utils::*;
+
+use common::*;
+
+fn order_data(raw_id: &str, pieces: u64, order_type: OrderType) -> OrderData {
+    OrderData::new(
+        raw_id.to_string(), share("SAP"), StockExchange::LSExchange, pieces, order_type,
+        PositionType::LongCall, TakeProfit::Relative(Price::from(10.0)), StopLoss::Relative(Price::from(-5.0)),
+        OrderMoment::Instant, OrderValidity::OneDay,
+    )
+}
+
+#[test]
+fn positions_are_opened_at_the_fill() {
+    let order = Order::Single(order_data("market", 10, OrderType::MarketOrder));
+    let position = Position::from_order(order, start(), Price::from(101.0)).unwrap();
+
+    assert_eq!(position.id, "market");
+    assert_eq!(position.bought, start());
+    assert_eq!((position.pieces(), position.entry_price()), (10, Price::from(101.0)));
+    assert_eq!(position.fills(), &[Fill::new(start(), 10, Price::from(101.0))]);
+
+    let order = Order::OneCancelsTheOther(vec![
+        order_data("first", 5, OrderType::LimitOrder(Price::from(95.0))),
+        order_data("second", 7, OrderType::StopOrder(Price::from(105.0))),
+    ]);
+    let position = Position::from_order(order, start(), Price::from(95.0)).unwrap();
+    assert_eq!((position.id.as_str(), position.pieces()), ("first", 5));
+}
+
+#[test]
+fn only_the_entry_of_a_bracket_opens_a_position() {
+    let mut bracket = Bracket::new(order_data("entry", 10, OrderType::LimitOrder(Price::from(100.0))));
+    let position = Position::from_order(Order::Bracket(bracket.clone()), start(), Price::from(100.0)).unwrap();
+    bracket.activate(&position, Price::from(100.0));
+    assert_eq!(bracket.data().len(), 2);
+
+    let position = Position::from_order(Order::Bracket(bracket), start(), Price::from(100.0)).unwrap();
+    assert_eq!((position.id.as_str(), position.pieces()), ("entry", 10));
+}
+
+#[test]
+fn orders_without_data_open_no_position() {
+    for order in [Order::OneCancelsTheOther(Vec::new()), Order::AllOrNone(Vec::new())] {
+        let error = Position::from_order(order, start(), Price::from(100.0)).unwrap_err();
+        assert_eq!(error.kind(), TradingErrorKind::InvalidOrder);
+    }
+}
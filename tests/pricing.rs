@@ -0,0 +1,31 @@
+use trading_utils::*;
+
+fn call(style: ExerciseStyle) -> OptionParameters {
+    OptionParameters::new(
+        OptionRight::Call,
+        style,
+        Price::from(100.0),
+        Price::from(100.0),
+        0.5,
+        Percent::from(0.03),
+        Percent::from(0.25),
+    )
+}
+
+#[test]
+fn binomial_gamma_matches_black_scholes() {
+    let european = call(ExerciseStyle::European);
+    let expected = BlackScholes.greeks(&european);
+
+    let greeks = Binomial::default().greeks(&european);
+    assert!((greeks.delta - expected.delta).abs() < 0.01, "{} != {}", greeks.delta, expected.delta);
+    assert!((greeks.gamma - expected.gamma).abs() < 0.002, "{} != {}", greeks.gamma, expected.gamma);
+
+    // even the smallest tree has a gamma
+    for steps in 0..=3 {
+        let tree = Binomial::new(steps);
+        let gamma = tree.greeks(&european).gamma;
+        assert_eq!(tree.steps(), 3);
+        assert!((gamma - expected.gamma).abs() < 0.005, "{} steps: {} != {}", steps, gamma, expected.gamma);
+    }
+}
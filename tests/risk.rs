@@ -0,0 +1,127 @@
+mod common;
+
+use std::sync::Arc;
+
+use chrono::Duration;
+use trading_utils::*;
+
+use common::*;
+
+fn order_data(raw_id: &str, pieces: u64, order_type: OrderType, stop_loss: StopLoss) -> OrderData {
+    OrderData::new(
+        raw_id.to_string(), share("SAP"), StockExchange::LSExchange, pieces, order_type,
+        PositionType::LongCall, TakeProfit::None, stop_loss, OrderMoment::Instant, OrderValidity::OneDay,
+    )
+}
+
+#[test]
+fn kill_switch_never_blocks_exits() {
+    let mut bracket = Bracket::new(order_data("entry", 10, OrderType::LimitOrder(Price::from(100.0)), StopLoss::Relative(Price::from(-5.0))));
+    let position = Position::from_order(Order::Bracket(bracket.clone()), start(), Price::from(100.0)).unwrap();
+    bracket.activate(&position, Price::from(100.0));
+    let stop_id = bracket.stop_loss_order().unwrap().id();
+    let pending = Order::Single(order_data("pending", 5, OrderType::LimitOrder(Price::from(90.0)), StopLoss::None));
+
+    let mut deposit = deposit();
+    deposit.update_positions(vec![position.clone()]);
+    deposit.add_order(Order::Bracket(bracket));
+    deposit.add_order(pending.clone());
+
+    let mut risk_manager = RiskManager::new(RiskLimits::new());
+    risk_manager.kill_switch().engage();
+
+    let move_stop = PlannedAction::Modify {
+        order_id: stop_id,
+        changes: OrderChanges { order_type: Some(OrderType::StopOrder(Price::from(98.0))), ..OrderChanges::default() },
+    };
+    assert!(risk_manager.check(&move_stop, &deposit).is_ok());
+    assert!(risk_manager.check(&PlannedAction::Close { position: position.hashed_id(), pieces: None }, &deposit).is_ok());
+    assert!(risk_manager.check(&PlannedAction::Cancel { order_id: pending.data()[0].id() }, &deposit).is_ok());
+
+    // orders that open positions are still rejected, also when they are changed
+    let raise_entry = PlannedAction::Modify {
+        order_id: pending.data()[0].id(),
+        changes: OrderChanges { pieces: Some(10), ..OrderChanges::default() },
+    };
+    assert_eq!(risk_manager.check(&raise_entry, &deposit).unwrap_err().kind(), TradingErrorKind::RiskLimit);
+    let submit = PlannedAction::Submit(Order::Single(order_data("new", 1, OrderType::MarketOrder, StopLoss::None)));
+    assert_eq!(risk_manager.check(&submit, &deposit).unwrap_err().kind(), TradingErrorKind::RiskLimit);
+
+    // the executor moves the stop as well
+    let mut executor = Executor::new(StockExchange::LSExchange).with_risk_manager(risk_manager);
+    let plan = ExecutionPlan::new(vec![move_stop]);
+    let report = executor.execute(&TestBroker::new(100.0), &mut deposit, &mut Buyer::default(), &share("SAP"), plan, ExecutionPhase::Trading);
+    assert_eq!(report.modified, vec![stop_id]);
+    assert!(report.is_success());
+}
+
+/// buys a piece at market in every step
+#[derive(Default)]
+struct Buyer {
+    instructions: Vec<Instruction<'static>>,
+}
+
+impl AlgorithmInterface for Buyer {
+    fn algorithm(&mut self, _: &[Position], _: &[Price]) -> Result<&[Instruction<'_>], Error<TradingErrorKind>> {
+        self.instructions = vec![Instruction::Buy(market_buy(1))];
+        Ok(&self.instructions)
+    }
+}
+
+#[test]
+fn sessions_check_the_daily_loss() {
+    let held = Position::from_order(Order::Single(order_data("held", 100, OrderType::MarketOrder, StopLoss::None)), start(), Price::from(100.0)).unwrap();
+    let mut deposit = Deposit::empty("deposit".to_string(), Currency::EUR);
+    deposit.update_balance(Price::from(1_000.0));
+    deposit.update_positions(vec![held]);
+
+    // the daily loss engages the kill switch
+    let kill_switch = KillSwitch::new();
+    let risk_events = EventBus::new();
+    let log = Arc::new(EventLog::new());
+    risk_events.subscribe(log.clone());
+    let engage = kill_switch.clone();
+    risk_events.subscribe(Arc::new(move |event: &RiskEvent| {
+        if let RiskEvent::DailyLossLimitReached { .. } = event {
+            engage.engage();
+        }
+    }));
+    // market orders are only valued with the prices of the session
+    let limits = RiskLimits::new()
+        .with_max_daily_loss(Percent::from(0.05))
+        .with_max_notional(Price::from(50_000.0));
+    let risk_manager = RiskManager::new(limits)
+        .with_kill_switch(&kill_switch)
+        .with_events(&risk_events);
+
+    let feed = MemoryFeed::new().with_candles("SAP", candles(&[100.0, 100.0, 90.0, 90.0]));
+    let mut runner = Runner::new(Buyer::default(), TestBroker::new(100.0), feed, deposit, share("SAP"), Duration::minutes(1))
+        .with_clock(ManualClock::new(start()))
+        .with_end(start() + Duration::minutes(3))
+        .with_executor(Executor::new(StockExchange::LSExchange).with_risk_manager(risk_manager))
+        .with_end_of_session(EndOfSession::Liquidate);
+    let report = runner.run().unwrap();
+
+    // 1 000 cash and 100 pieces at 100 are 11 000, at 90 the deposit lost more than 5%
+    assert_eq!(report.steps, 4);
+    assert_eq!(report.trading.submitted.len(), 2);
+    assert!(report.trading.errors.iter().all(|error| error.kind() == TradingErrorKind::RiskLimit));
+    assert_eq!(report.trading.errors.len(), 2);
+    assert!(kill_switch.is_engaged());
+
+    let events: Vec<String> = log
+        .events()
+        .iter()
+        .map(|event| match event {
+            RiskEvent::DayStarted { .. } => "day started".to_string(),
+            RiskEvent::DailyLossLimitReached { .. } => "daily loss".to_string(),
+            RiskEvent::Rejected { rule, .. } => format!("rejected by {:?}", rule),
+        })
+        .collect();
+    assert_eq!(events, vec!["day started", "daily loss", "rejected by KillSwitch", "rejected by KillSwitch"]);
+
+    // the kill switch doesn't stop the liquidation
+    assert!(report.end_of_session.is_success());
+    assert_eq!(report.end_of_session.execution.closed.len(), 3);
+    assert!(runner.deposit().positions().is_empty());
+}
@@ -0,0 +1,350 @@
+mod common;
+
+use std::cell::RefCell;
+use std::rc::Rc;
+use std::sync::Arc;
+
+use chrono::{DateTime, Duration, Local};
+use trading_utils::*;
+
+use common::*;
+
+/// buys on its first trade and records every call of the runner
+#[derive(Default)]
+struct Trader {
+    collected: Vec<usize>,
+    traded: Vec<(DateTime<Local>, usize)>,
+    shut_down: bool,
+    /// stops the session after the given amount of trades
+    stop: Rc<RefCell<Option<(StopHandle, usize)>>>,
+    instructions: Vec<Instruction<'static>>,
+}
+
+impl AlgorithmInterface for Trader {
+    fn collect_prices(&mut self, prices: &[Price]) -> Result<(), Error<TradingErrorKind>> {
+        self.collected.push(prices.len());
+        Ok(())
+    }
+
+    fn algorithm(&mut self, _: &[Position], _: &[Price]) -> Result<&[Instruction<'_>], Error<TradingErrorKind>> {
+        unreachable!("the runner calls trade")
+    }
+
+    fn trade(&mut self, context: &MarketContext<'_>) -> Result<&[Instruction<'_>], Error<TradingErrorKind>> {
+        self.traded.push((context.time(), context.candles().len()));
+        if let Some((stop, after)) = &*self.stop.borrow() {
+            if self.traded.len() >= *after {
+                stop.stop();
+            }
+        }
+
+        self.instructions.clear();
+        if self.traded.len() == 1 {
+            self.instructions.push(Instruction::Buy(market_buy(10)));
+        }
+        Ok(&self.instructions)
+    }
+
+    fn shutdown(&mut self, _: &[Position], _: &[Price]) -> Result<&[Instruction<'_>], Error<TradingErrorKind>> {
+        self.shut_down = true;
+        Ok(&[])
+    }
+}
+
+fn runner(algorithm: Trader, candles: Vec<Candle>) -> Runner<Trader, TestBroker, MemoryFeed, ManualClock> {
+    Runner::new(algorithm, TestBroker::new(100.0), MemoryFeed::new().with_candles("SAP", candles), deposit(), share("SAP"), Duration::minutes(1))
+        .with_data_length(2, 3)
+        .with_clock(ManualClock::new(start()))
+}
+
+fn minutes(minutes: i64) -> DateTime<Local> {
+    start() + Duration::minutes(minutes)
+}
+
+#[test]
+fn manual_clock_only_moves_when_told() {
+    let mut clock = ManualClock::new(start());
+    assert_eq!(clock.now(), start());
+
+    clock.sleep(Duration::seconds(30));
+    clock.advance(Duration::seconds(30));
+    assert_eq!(clock.now(), minutes(1));
+
+    clock.set(minutes(10));
+    assert_eq!(clock.now(), minutes(10));
+}
+
+#[test]
+fn runner_steps_until_the_end() {
+    let candles = candles(&[100.0, 101.0, 102.0, 103.0, 104.0]);
+    let log = Arc::new(EventLog::new());
+    let events = EventBus::new();
+    events.subscribe(log.clone());
+
+    // the feed has no candle for the third minute
+    let mut runner = runner(Trader::default(), vec![candles[0], candles[1], candles[3], candles[4]])
+        .with_end(minutes(4))
+        .with_events(&events);
+    let report = runner.run().unwrap();
+
+    let algorithm = runner.algorithm();
+    assert_eq!(algorithm.collected, vec![1]);
+    // the window is capped at three candles
+    assert_eq!(algorithm.traded, vec![(minutes(1), 2), (minutes(3), 3), (minutes(4), 3)]);
+    assert!(algorithm.shut_down);
+    assert_eq!(runner.candles(), &[candles[1], candles[3], candles[4]]);
+    assert_eq!(runner.clock().now(), minutes(4));
+
+    assert_eq!(report.steps, 4);
+    assert_eq!(report.warm_up_steps, 1);
+    assert_eq!(report.trading.submitted.len(), 1);
+    assert!(report.trading.is_success() && report.shutdown.is_success());
+    assert!(report.error.is_none());
+    assert_eq!(report.end_of_session.policy, EndOfSession::KeepOpen);
+    assert_eq!(report.end_of_session.open_positions().count(), 1);
+
+    let events: Vec<String> = log
+        .events()
+        .iter()
+        .map(|event| match event {
+            SessionEvent::Started { .. } => "started",
+            SessionEvent::WarmingUp { .. } => "warming up",
+            SessionEvent::Traded { .. } => "traded",
+            SessionEvent::NoData { .. } => "no data",
+            SessionEvent::FeedFailed { .. } => "feed failed",
+            SessionEvent::AlgorithmFailed { .. } => "failed",
+            SessionEvent::ShuttingDown { .. } => "shutting down",
+            SessionEvent::EndOfSessionApplied { .. } => "end of session",
+            SessionEvent::Finished { .. } => "finished",
+        }.to_string())
+        .collect();
+    assert_eq!(events, vec![
+        "started", "warming up", "traded", "no data", "traded", "traded", "shutting down", "end of session", "finished",
+    ]);
+}
+
+#[test]
+fn stop_handle_ends_the_session_after_the_current_step() {
+    let algorithm = Trader::default();
+    let stop = algorithm.stop.clone();
+    let mut runner = runner(algorithm, candles(&[100.0; 10]));
+    *stop.borrow_mut() = Some((runner.stop_handle(), 2));
+
+    let report = runner.run().unwrap();
+    assert_eq!(report.steps, 3);
+    assert_eq!(runner.algorithm().traded.len(), 2);
+    assert!(runner.algorithm().shut_down);
+    assert_eq!(runner.clock().now(), minutes(2));
+    assert!(runner.stop_handle().is_stopped());
+}
+
+#[test]
+fn stopped_session_only_shuts_down() {
+    let mut runner = runner(Trader::default(), candles(&[100.0; 10]));
+    runner.stop_handle().stop();
+
+    let report = runner.run().unwrap();
+    assert_eq!(report.steps, 0);
+    assert!(runner.algorithm().collected.is_empty() && runner.algorithm().traded.is_empty());
+    assert!(runner.algorithm().shut_down);
+    assert!(report.end_of_session.positions.is_empty());
+}
+
+fn end_of_session(policy: EndOfSession) -> (RunReport, Runner<Trader, TestBroker, MemoryFeed, ManualClock>) {
+    end_of_session_with(policy, Executor::new(StockExchange::LSExchange).with_raw_id_prefix("test"))
+}
+
+/// buys 10 pieces at 100, the last close is 110
+fn end_of_session_with(policy: EndOfSession, executor: Executor) -> (RunReport, Runner<Trader, TestBroker, MemoryFeed, ManualClock>) {
+    let mut runner = runner(Trader::default(), candles(&[100.0, 105.0, 110.0]))
+        .with_executor(executor)
+        .with_end(minutes(2))
+        .with_end_of_session(policy);
+    let report = runner.run().unwrap();
+    (report, runner)
+}
+
+fn outcome(report: &RunReport) -> &PositionOutcome {
+    assert_eq!(report.end_of_session.positions.len(), 1);
+    &report.end_of_session.positions[0].1
+}
+
+#[test]
+fn end_of_session_keeps_or_hands_over_positions() {
+    let (report, runner) = end_of_session(EndOfSession::KeepOpen);
+    assert!(matches!(outcome(&report), PositionOutcome::KeptOpen));
+    assert_eq!(runner.deposit().positions().len(), 1);
+
+    let (report, runner) = end_of_session(EndOfSession::HandOver);
+    assert!(matches!(outcome(&report), PositionOutcome::HandedOver));
+    assert_eq!(runner.deposit().positions().len(), 1);
+    assert!(runner.deposit().orders().is_empty());
+}
+
+#[test]
+fn end_of_session_liquidates_positions() {
+    let (report, runner) = end_of_session(EndOfSession::Liquidate);
+    assert!(matches!(outcome(&report), PositionOutcome::Liquidated));
+    assert_eq!(report.end_of_session.execution.closed.len(), 1);
+    assert_eq!(report.end_of_session.open_positions().count(), 0);
+    assert!(runner.deposit().positions().is_empty());
+}
+
+#[test]
+fn end_of_session_protects_positions_with_a_stop_order() {
+    // the executor places the stop, even though its kill switch was engaged after the buy
+    let kill_switch = KillSwitch::new();
+    let engage = kill_switch.clone();
+    let order_events = EventBus::new();
+    order_events.subscribe(Arc::new(move |_: &OrderEvent| engage.engage()));
+    let executor = Executor::new(StockExchange::LSExchange)
+        .with_raw_id_prefix("test")
+        .with_events(&order_events)
+        .with_risk_manager(RiskManager::new(RiskLimits::new()).with_kill_switch(&kill_switch));
+    let (report, runner) = end_of_session_with(EndOfSession::ProtectiveStop(StopLoss::Relative(Price::from(-5.0))), executor);
+    assert!(matches!(outcome(&report), PositionOutcome::Protected));
+    assert!(report.end_of_session.is_success());
+    assert_eq!(report.end_of_session.execution.submitted.len(), 1);
+    assert!(kill_switch.is_engaged());
+
+    // the filled entry order is left as it is
+    let position = &runner.deposit().positions()[0];
+    assert_eq!(position.order.data()[0].stop_loss(), &StopLoss::None);
+
+    let stop = match &runner.deposit().orders()[..] {
+        [Order::Bracket(bracket)] => {
+            assert_eq!(bracket.position(), Some(&position.id));
+            assert!(bracket.take_profit_order().is_none());
+            bracket.stop_loss_order().unwrap().clone()
+        }
+        orders => panic!("expected a single protective stop, got {:?}", orders),
+    };
+    // relative to the last close of 110, not to the entry at 100
+    assert_eq!(stop.order_type(), &OrderType::StopOrder(Price::from(105.0)));
+    assert_eq!(stop.pieces(), position.pieces());
+    assert_eq!(runner.broker().calls().last(), Some(&format!("protect {}", stop.id())));
+
+    let (report, runner) = end_of_session(EndOfSession::ProtectiveStop(StopLoss::None));
+    assert!(outcome(&report).is_failed());
+    assert!(runner.deposit().orders().is_empty());
+}
+
+/// opens a bracket on its first trade and buys on its second one, records the callbacks
+#[derive(Default)]
+struct Simulated {
+    trades: usize,
+    callbacks: Vec<String>,
+    instructions: Vec<Instruction<'static>>,
+}
+
+impl AlgorithmInterface for Simulated {
+    fn algorithm(&mut self, _: &[Position], _: &[Price]) -> Result<&[Instruction<'_>], Error<TradingErrorKind>> {
+        self.trades += 1;
+        self.instructions = match self.trades {
+            1 => vec![Instruction::Bracket {
+                entry: market_buy(10),
+                take_profit: TakeProfit::Relative(Price::from(5.0)),
+                stop_loss: StopLoss::Relative(Price::from(-3.0)),
+            }],
+            2 => vec![Instruction::Buy(market_buy(5))],
+            _ => Vec::new(),
+        };
+        Ok(&self.instructions)
+    }
+
+    fn on_fill(&mut self, order: &OrderData, position: &Position) -> Result<&[Instruction<'_>], Error<TradingErrorKind>> {
+        self.callbacks.push(format!("{} filled at {}", order.raw_id(), position.entry_price().as_f64()));
+        Ok(&[])
+    }
+
+    fn on_take_profit_triggered(&mut self, position: &Position) -> Result<&[Instruction<'_>], Error<TradingErrorKind>> {
+        self.callbacks.push(format!("take profit of {}", position.id));
+        Ok(&[])
+    }
+}
+
+#[test]
+fn matching_engine_simulates_the_broker() {
+    let candle = |minute, open, high, low, close| Candle::new(minutes(minute), Price::from(open), Price::from(high), Price::from(low), Price::from(close), 0);
+    let candles = vec![
+        candle(0, 100.0, 100.0, 100.0, 100.0),
+        candle(1, 101.0, 102.0, 100.0, 101.0),
+        candle(2, 104.0, 107.0, 103.0, 105.0),
+    ];
+    let executor = Executor::new(StockExchange::LSExchange)
+        .with_raw_id_prefix("test")
+        .with_matching_engine(MatchingEngine::new());
+    let mut runner = Runner::new(Simulated::default(), TestBroker::new(0.0), MemoryFeed::new().with_candles("SAP", candles), deposit(), share("SAP"), Duration::minutes(1))
+        .with_clock(ManualClock::new(start()))
+        .with_executor(executor)
+        .with_end(minutes(2))
+        .with_end_of_session(EndOfSession::Liquidate);
+    let report = runner.run().unwrap();
+
+    // market orders are filled at the open of the next candle, the take profit at 106
+    assert_eq!(runner.algorithm().callbacks, vec!["test-1 filled at 101", "take profit of test-1", "test-2 filled at 104"]);
+    assert_eq!(report.trading.submitted.len(), 2);
+    assert!(matches!(report.trading.events[..], [
+        OrderEvent::Filled { .. }, OrderEvent::TakeProfitTriggered { .. }, OrderEvent::Cancelled { .. }, OrderEvent::Filled { .. },
+    ]));
+    assert!(report.trading.is_success());
+
+    // the remaining position is sold at the last close
+    assert!(matches!(outcome(&report), PositionOutcome::Liquidated));
+    assert_eq!(report.end_of_session.execution.closed[0].realized(), Price::from(5.0));
+    let deposit = runner.deposit();
+    assert!(deposit.orders().is_empty() && deposit.positions().is_empty());
+    assert_eq!(deposit.balance(), Price::from(10_000.0 - 1_010.0 + 1_060.0 - 520.0 + 525.0));
+    assert!(runner.broker().calls().is_empty());
+}
+
+/// buys with a trailing stop loss on its first trade
+#[derive(Default)]
+struct TrailingBuyer {
+    instructions: Vec<Instruction<'static>>,
+}
+
+impl AlgorithmInterface for TrailingBuyer {
+    fn algorithm(&mut self, positions: &[Position], _: &[Price]) -> Result<&[Instruction<'_>], Error<TradingErrorKind>> {
+        self.instructions.clear();
+        if positions.is_empty() && self.instructions.capacity() == 0 {
+            self.instructions.push(Instruction::Buy(market_buy(10).with_stop_loss(StopLoss::Trailing(Price::from(-5.0)))));
+        }
+        Ok(&self.instructions)
+    }
+}
+
+#[test]
+fn trailing_stops_follow_the_candles_of_the_session() {
+    let candle = |minute, high, low| Candle::new(minutes(minute), Price::from(low), Price::from(high), Price::from(low), Price::from(high), 0);
+    let candles = vec![candle(0, 100.0, 100.0), candle(1, 110.0, 101.0), candle(2, 107.0, 106.0), candle(3, 106.0, 104.0), candle(4, 100.0, 99.0)];
+    let log = Arc::new(EventLog::new());
+    let trailing_events = EventBus::new();
+    trailing_events.subscribe(log.clone());
+
+    let mut runner = Runner::new(TrailingBuyer::default(), TestBroker::new(100.0), MemoryFeed::new().with_candles("SAP", candles), deposit(), share("SAP"), Duration::minutes(1))
+        .with_clock(ManualClock::new(start()))
+        .with_trailing_stops(TrailingStopEngine::new().with_events(&trailing_events))
+        .with_end(minutes(4));
+    let report = runner.run().unwrap();
+
+    let events: Vec<String> = log
+        .events()
+        .iter()
+        .map(|event| match event {
+            TrailingStopEvent::Tracked { stop, .. } => format!("tracked at {}", stop.unwrap().as_f64()),
+            TrailingStopEvent::Moved { to, .. } => format!("moved to {}", to.as_f64()),
+            TrailingStopEvent::Triggered { stop, .. } => format!("triggered at {}", stop.as_f64()),
+            TrailingStopEvent::Untracked { .. } => "untracked".to_string(),
+        })
+        .collect();
+    // the high of 110 moves the stop to 105, the low of 104 crosses it
+    assert_eq!(events, vec!["tracked at 95", "moved to 105", "triggered at 105"]);
+
+    assert_eq!(report.trading.submitted.len(), 1);
+    assert_eq!(report.trading.closed.len(), 1);
+    assert!(report.trading.is_success());
+    assert!(runner.deposit().positions().is_empty());
+    assert!(runner.trailing_stops().stops().is_empty());
+    assert_eq!(runner.broker().calls().len(), 2);
+}
@@ -0,0 +1,108 @@
+#![cfg(feature = "serde")]
+
+mod common;
+
+use chrono::DateTime;
+use serde_json::{json, Value};
+use trading_utils::*;
+
+use common::*;
+
+fn order_data(raw_id: &str) -> OrderData {
+    OrderData::new(
+        raw_id.to_string(), share("SAP"), StockExchange::LSExchange, 10,
+        OrderType::LimitOrder(Price::from(100.5)), PositionType::LongCall,
+        TakeProfit::Relative(Price::from(5.0)), StopLoss::None, OrderMoment::Instant, OrderValidity::OneDay,
+    )
+}
+
+fn position() -> Position {
+    let mut position = Position::from_order(Order::Single(order_data("entry")), start(), Price::from(100.5)).unwrap();
+    position.scale_out(Fill::new(start(), 4, Price::from(102.0)).with_fee(Price::from(1.0))).unwrap();
+    position
+}
+
+#[test]
+fn order_data_round_trips() {
+    let data = order_data("order");
+    let json = serde_json::to_value(&data).unwrap();
+
+    // the id is written as a string
+    assert_eq!(json["id"], Value::String(data.id().to_string()));
+    assert_eq!(json["raw_id"], "order");
+    assert_eq!(json["stock_exchange"], "LSExchange");
+    assert_eq!(json["pieces"], 10);
+    assert_eq!(json["order_type"], json!({"type": "limit_order", "value": 100.5}));
+    assert_eq!(json["position_type"], "long_call");
+    assert_eq!(json["take_profit"], json!({"type": "relative", "value": 5.0}));
+    assert_eq!(json["stop_loss"], json!({"type": "none"}));
+    assert_eq!(json["validity"], "one_day");
+    assert_eq!(serde_json::from_value::<OrderData>(json.clone()).unwrap(), data);
+
+    // the id is the hash of the raw id, a different one is ignored
+    let mut changed = json;
+    changed["id"] = Value::String("1".to_string());
+    assert_eq!(serde_json::from_value::<OrderData>(changed.clone()).unwrap().id(), data.id());
+    changed.as_object_mut().unwrap().remove("id");
+    assert_eq!(serde_json::from_value::<OrderData>(changed).unwrap(), data);
+}
+
+#[test]
+fn position_round_trips() {
+    let position = position();
+    let json = serde_json::to_value(&position).unwrap();
+
+    assert_eq!(json["id"], "entry");
+    assert_eq!(DateTime::parse_from_rfc3339(json["bought"].as_str().unwrap()).unwrap(), start());
+    assert_eq!(json["order"]["type"], "single");
+    assert_eq!(json["order"]["value"]["raw_id"], "entry");
+    assert_eq!(json["position_type"], "long_call");
+    assert_eq!(json["pieces"], 6);
+    assert_eq!(json["entry_price"], 100.5);
+    assert_eq!(json["fills"][1], json!({"time": json["bought"], "pieces": 4, "price": 102.0, "fee": 1.0}));
+    assert_eq!(json["closed"], Value::Null);
+    assert_eq!(serde_json::from_value::<Position>(json).unwrap(), position);
+}
+
+#[test]
+fn deposit_round_trips() {
+    let mut deposit = deposit();
+    deposit.update_transactions(vec![Transaction::new("cash".to_string(), start(), TransactionKind::CashIn, Price::from(10_000.0), Currency::EUR)]);
+    deposit.add_order(Order::Single(order_data("order")));
+    deposit.update_positions(vec![position()]);
+
+    let json = serde_json::to_value(&deposit).unwrap();
+    assert_eq!(json["id"], Value::String(deposit.id().to_string()));
+    assert_eq!(json["raw_id"], "deposit");
+    assert_eq!(json["currency"], "EUR");
+    assert_eq!(json["balance"], 10_000.0);
+    assert_eq!(json["transactions"][0]["kind"], "cash_in");
+    assert_eq!(json["orders"][0]["type"], "single");
+    assert_eq!(json["positions"][0]["id"], "entry");
+
+    let read = serde_json::from_value::<Deposit>(json).unwrap();
+    assert_eq!(read.id(), deposit.id());
+    assert_eq!(read.raw_id(), deposit.raw_id());
+    assert_eq!(read.currency(), deposit.currency());
+    assert_eq!(read.balance(), deposit.balance());
+    assert_eq!(read.transactions(), deposit.transactions());
+    assert_eq!(read.orders(), deposit.orders());
+    assert_eq!(read.positions(), deposit.positions());
+}
+
+#[test]
+fn errors_round_trip() {
+    let error = Error::new("the limit is reached".to_string(), TradingErrorKind::RiskLimit);
+    let json = serde_json::to_value(&error).unwrap();
+    assert_eq!(json, json!({"msg": "the limit is reached", "kind": "risk_limit"}));
+
+    let read = serde_json::from_value::<Error<TradingErrorKind>>(json).unwrap();
+    assert_eq!((read.msg(), read.kind()), (error.msg(), error.kind()));
+
+    let error = Error::new("unknown order".to_string(), BrokerErrorKind::NoSuchOrder);
+    let json = serde_json::to_string(&error).unwrap();
+    assert_eq!(json, r#"{"msg":"unknown order","kind":"no_such_order"}"#);
+    let read = serde_json::from_str::<Error<BrokerErrorKind>>(&json).unwrap();
+    assert_eq!(read.msg(), error.msg());
+    assert!(matches!(read.kind(), BrokerErrorKind::NoSuchOrder));
+}
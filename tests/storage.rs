@@ -0,0 +1,212 @@
+mod common;
+
+use std::fs;
+use std::path::PathBuf;
+
+use chrono::{DateTime, Duration, Local};
+use trading_utils::*;
+
+use common::*;
+
+const EXCHANGE: StockExchange = StockExchange::LSExchange;
+
+/// a store in its own temporary directory, which is removed afterwards
+struct TempStore {
+    root: PathBuf,
+    store: DataStore,
+}
+
+impl TempStore {
+    fn new(name: &str) -> Self {
+        let root = std::env::temp_dir().join(format!("trading-utils-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+        let store = DataStore::open(&root).unwrap();
+        Self { root, store }
+    }
+
+    /// flips a byte of the stored file of the derivative
+    fn corrupt(&self, derivative: &Derivative, byte: usize) {
+        let path = self.store.path(derivative, EXCHANGE);
+        let mut data = fs::read(&path).unwrap();
+        data[byte] ^= 0xff;
+        fs::write(&path, data).unwrap();
+    }
+}
+
+impl Drop for TempStore {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.root);
+    }
+}
+
+fn minutes(minutes: i64) -> DateTime<Local> {
+    start() + Duration::minutes(minutes)
+}
+
+fn times(candles: &[Candle]) -> Vec<DateTime<Local>> {
+    candles.iter().map(|candle| candle.time).collect()
+}
+
+#[test]
+fn store_round_trips_candles() {
+    let temp = TempStore::new("round-trip");
+    let (store, sap) = (&temp.store, share("SAP"));
+    let mut candles = candles(&[100.0, 101.5, 99.25, 102.0, 103.0, 104.0]);
+    candles[2].volume = 1_500;
+
+    assert!(!store.contains(&sap, EXCHANGE));
+    assert_eq!(store.info(&sap, EXCHANGE).unwrap(), None);
+    assert!(store.read_all(&sap, EXCHANGE).unwrap().is_empty());
+
+    // unsorted candles are sorted and already stored ones are skipped
+    assert_eq!(store.append(&sap, EXCHANGE, &[candles[1], candles[0], candles[2]]).unwrap(), 3);
+    assert_eq!(store.append(&sap, EXCHANGE, &candles[2..]).unwrap(), 3);
+    assert_eq!(store.append(&sap, EXCHANGE, &candles).unwrap(), 0);
+
+    assert_eq!(store.read_all(&sap, EXCHANGE).unwrap(), candles);
+    assert_eq!(store.read(&sap, EXCHANGE, minutes(2), minutes(4)).unwrap(), &candles[2..4]);
+    assert_eq!(store.read_last(&sap, EXCHANGE, 4).unwrap(), &candles[2..]);
+    assert_eq!(store.info(&sap, EXCHANGE).unwrap(), Some(SeriesInfo {
+        candles: 6,
+        blocks: 2,
+        first: minutes(0),
+        last: minutes(5),
+    }));
+    store.verify(&sap, EXCHANGE).unwrap();
+
+    store.remove(&sap, EXCHANGE).unwrap();
+    assert!(!store.contains(&sap, EXCHANGE));
+}
+
+#[test]
+fn store_detects_corrupted_blocks() {
+    // the file header has 12 bytes, the block header 24 bytes: count, first, last and the checksum
+    let corruptions = [
+        (0, "not a candle file"),
+        (12 + 4, "checksum mismatch"),
+        (12 + 12, "checksum mismatch"),
+        (12 + 20, "checksum mismatch"),
+        (12 + 24 + 8 * 3 + 2, "checksum mismatch"),
+    ];
+
+    for (byte, reason) in corruptions.iter() {
+        let temp = TempStore::new(&format!("corrupted-{}", byte));
+        let (store, sap) = (&temp.store, share("SAP"));
+        store.append(&sap, EXCHANGE, &candles(&[100.0, 101.0, 102.0])).unwrap();
+        temp.corrupt(&sap, *byte);
+
+        let error = store.verify(&sap, EXCHANGE).unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::InvalidData);
+        assert!(error.msg().ends_with(reason), "byte {}: {}", byte, error.msg());
+    }
+}
+
+#[test]
+fn store_detects_truncated_files() {
+    let temp = TempStore::new("truncated");
+    let (store, sap) = (&temp.store, share("SAP"));
+    store.append(&sap, EXCHANGE, &candles(&[100.0, 101.0])).unwrap();
+
+    let path = store.path(&sap, EXCHANGE);
+    let data = fs::read(&path).unwrap();
+    fs::write(&path, &data[..data.len() - 1]).unwrap();
+
+    let error = store.info(&sap, EXCHANGE).unwrap_err();
+    assert_eq!(error.kind(), ErrorKind::InvalidData);
+    assert!(error.msg().ends_with("truncated block"));
+}
+
+#[test]
+fn opening_the_store_removes_interrupted_appends() {
+    let temp = TempStore::new("interrupted");
+    let (store, sap, bmw) = (&temp.store, share("SAP"), share("BMW"));
+    let candles = candles(&[100.0, 101.0, 102.0, 103.0]);
+    store.append(&sap, EXCHANGE, &candles[..2]).unwrap();
+    let valid = fs::read(store.path(&sap, EXCHANGE)).unwrap().len();
+    store.append(&sap, EXCHANGE, &candles[2..]).unwrap();
+    store.append(&bmw, EXCHANGE, &candles).unwrap();
+
+    // the second block of SAP was only written partly, the block of BMW has a wrong checksum
+    let path = store.path(&sap, EXCHANGE);
+    let data = fs::read(&path).unwrap();
+    fs::write(&path, &data[..data.len() - 10]).unwrap();
+    temp.corrupt(&bmw, 12 + 24 + 2);
+
+    let store = DataStore::open(&temp.root).unwrap();
+    assert_eq!(fs::read(&path).unwrap().len(), valid);
+    assert_eq!(store.read_all(&sap, EXCHANGE).unwrap(), &candles[..2]);
+    assert_eq!(store.info(&bmw, EXCHANGE).unwrap(), None);
+    assert_eq!(store.repair().unwrap(), 0);
+
+    // appends continue after the last valid block
+    assert_eq!(store.append(&sap, EXCHANGE, &candles).unwrap(), 2);
+    assert_eq!(store.read_all(&sap, EXCHANGE).unwrap(), candles);
+}
+
+#[test]
+fn symbols_are_stored_in_distinct_files() {
+    let temp = TempStore::new("symbols");
+    let store = &temp.store;
+    let symbols = ["BRK/B", "BRK_B", "BRK.B", "BRK B", "BRK_2FB"];
+    for (index, symbol) in symbols.iter().enumerate() {
+        store.append(&share(symbol), EXCHANGE, &candles(&[100.0 + index as f64])).unwrap();
+    }
+
+    let names = symbols
+        .iter()
+        .map(|symbol| store.path(&share(symbol), EXCHANGE).file_name().unwrap().to_str().unwrap().to_string())
+        .collect::<Vec<_>>();
+    assert_eq!(names, vec!["BRK_2FB.candles", "BRK_5FB.candles", "BRK.B.candles", "BRK_20B.candles", "BRK_5F2FB.candles"]);
+    for (index, symbol) in symbols.iter().enumerate() {
+        assert_eq!(store.read_all(&share(symbol), EXCHANGE).unwrap()[0].close, Price::from(100.0 + index as f64));
+    }
+}
+
+#[test]
+fn gaps_are_found_between_candles() {
+    let all = candles(&[100.0; 8]);
+    let candles = [all[0], all[1], all[4], all[5], all[7]];
+
+    let gaps = Gap::find(&candles, Duration::minutes(1));
+    assert_eq!(gaps, vec![
+        Gap { after: minutes(1), before: minutes(4) },
+        Gap { after: minutes(5), before: minutes(7) },
+    ]);
+    assert_eq!(gaps[0].duration(), Duration::minutes(3));
+    assert_eq!(gaps[0].missing_candles(Duration::minutes(1)), 2);
+    assert_eq!(gaps[1].missing_candles(Duration::minutes(1)), 1);
+    assert!(Gap::find(&candles, Duration::minutes(3)).is_empty());
+
+    let temp = TempStore::new("gaps");
+    let (store, sap) = (&temp.store, share("SAP"));
+    store.append(&sap, EXCHANGE, &candles).unwrap();
+    assert_eq!(store.gaps(&sap, EXCHANGE, Duration::minutes(1), minutes(2), minutes(8)).unwrap(), &gaps[1..]);
+}
+
+#[test]
+fn store_feed_polls_new_candles() {
+    let temp = TempStore::new("feed");
+    let (store, sap, bmw) = (&temp.store, share("SAP"), share("BMW"));
+    store.append(&sap, EXCHANGE, &candles(&[100.0, 101.0, 102.0])).unwrap();
+    store.append(&bmw, EXCHANGE, &candles(&[50.0, 51.0])).unwrap();
+
+    let mut feed = store.feed(EXCHANGE);
+    feed.subscribe(&sap).unwrap();
+    feed.subscribe(&bmw).unwrap();
+
+    let updates: Vec<Candle> = feed.poll(minutes(1)).unwrap().into_iter().map(|update| update.candle).collect();
+    assert_eq!(times(&updates), vec![minutes(0), minutes(0), minutes(1), minutes(1)]);
+    assert!(feed.poll(minutes(1)).unwrap().is_empty());
+
+    // candles that are appended later are polled as well
+    store.append(&bmw, EXCHANGE, &candles(&[50.0, 51.0, 52.0, 53.0])[2..]).unwrap();
+    let updates = feed.poll(minutes(2)).unwrap();
+    let polled: Vec<(&str, DateTime<Local>)> = updates
+        .iter()
+        .map(|update| (update.symbol.as_str(), update.candle.time))
+        .collect();
+    assert_eq!(polled.len(), 2);
+    assert!(polled.contains(&("SAP", minutes(2))) && polled.contains(&("BMW", minutes(2))));
+
+    assert_eq!(times(&feed.history(&sap, minutes(1), minutes(3)).unwrap()), vec![minutes(1), minutes(2)]);
+}
@@ -0,0 +1,82 @@
+mod common;
+
+use chrono::Duration;
+use trading_utils::*;
+
+use common::*;
+
+fn entry(raw_id: &str) -> OrderData {
+    OrderData::new(
+        raw_id.to_string(), share("SAP"), StockExchange::LSExchange, 10,
+        OrderType::LimitOrder(Price::from(100.0)), PositionType::LongCall,
+        TakeProfit::None, StopLoss::Trailing(Price::from(-5.0)),
+        OrderMoment::Instant, OrderValidity::OneDay,
+    )
+}
+
+fn candle(minute: i64, high: f64, low: f64) -> Candle {
+    Candle::new(start() + Duration::minutes(minute), Price::from(low), Price::from(high), Price::from(low), Price::from(high), 0)
+}
+
+fn stop_price(deposit: &Deposit) -> OrderType {
+    match &deposit.orders()[0] {
+        Order::Bracket(bracket) => bracket.stop_loss_order().unwrap().order_type().clone(),
+        order => panic!("expected a bracket, got {:?}", order),
+    }
+}
+
+#[test]
+fn bracket_stops_are_moved_by_the_executor() {
+    let mut bracket = Bracket::new(entry("entry"));
+    let position = Position::from_order(Order::Bracket(bracket.clone()), start(), Price::from(100.0)).unwrap();
+    bracket.activate(&position, Price::from(100.0));
+    let stop_id = bracket.stop_loss_order().unwrap().id();
+
+    let mut deposit = deposit();
+    deposit.update_positions(vec![position]);
+    deposit.add_order(Order::Bracket(bracket));
+
+    let mut engine = TrailingStopEngine::new();
+    let actions = engine.update(&deposit, "SAP", &[candle(0, 110.0, 101.0)]);
+    assert_eq!(actions, vec![PlannedAction::Modify {
+        order_id: stop_id,
+        changes: OrderChanges {
+            order_type: Some(OrderType::StopOrder(Price::from(105.0))),
+            ..OrderChanges::default()
+        },
+    }]);
+    // the engine leaves the deposit to the executor
+    assert_eq!(stop_price(&deposit), OrderType::StopOrder(Price::from(95.0)));
+
+    let (share, broker, mut executor) = (share("SAP"), TestBroker::new(110.0), Executor::new(StockExchange::LSExchange));
+    let report = executor.execute(&broker, &mut deposit, &mut NoAlgorithm, &share, ExecutionPlan::new(actions), ExecutionPhase::Trading);
+    assert_eq!(report.modified, vec![stop_id]);
+    assert_eq!(stop_price(&deposit), OrderType::StopOrder(Price::from(105.0)));
+}
+
+#[test]
+fn triggered_stops_are_checked_again_while_the_position_is_open() {
+    let position = Position::from_order(Order::Single(entry("position")), start(), Price::from(100.0)).unwrap();
+    let close = PlannedAction::Close { position: position.hashed_id(), pieces: None };
+    let mut deposit = deposit();
+    deposit.update_positions(vec![position]);
+
+    let mut engine = TrailingStopEngine::new();
+    assert_eq!(engine.update(&deposit, "SAP", &[candle(0, 101.0, 94.0)]), vec![close.clone()]);
+    assert!(engine.stop("position").unwrap().triggered);
+
+    // the close failed, so the stop is crossed again
+    assert_eq!(engine.update(&deposit, "SAP", &[candle(1, 96.0, 93.0)]), vec![close]);
+
+    deposit.update_positions(Vec::new());
+    assert!(engine.update(&deposit, "SAP", &[candle(2, 96.0, 93.0)]).is_empty());
+    assert!(engine.stops().is_empty());
+}
+
+struct NoAlgorithm;
+
+impl AlgorithmInterface for NoAlgorithm {
+    fn algorithm(&mut self, _: &[Position], _: &[Price]) -> Result<&[Instruction<'_>], Error<TradingErrorKind>> {
+        Ok(&[])
+    }
+}
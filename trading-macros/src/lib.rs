@@ -1,2 +1,181 @@
+use proc_macro::TokenStream;
 
+use proc_macro2::Span;
+use quote::quote;
+use syn::{AttributeArgs, Item, ItemStruct, Lit, LitInt, LitStr, Meta, NestedMeta, parse_macro_input};
+use syn::spanned::Spanned;
 
+/// exports a struct as an algorithm
+///
+/// This attribute generates the `export_algorithm!` registration of a struct that implements
+/// `AlgorithmInterface` and `Default`. The initial state of the algorithm is created by
+/// `Default::default()`.
+/// ```ignore
+/// #[algorithm(name = "sma", description = "simple moving average cross over", min_data = 20, max_data = 200)]
+/// #[derive(Default)]
+/// struct Sma { /* ... */ }
+/// ```
+///
+/// #### Arguments:
+/// * __name__: The name of the algorithm (required)
+/// * __description__: The description of the algorithm (default: "")
+/// * __min_data__: The minimal amount of prices the algorithm needs (default: 0)
+/// * __max_data__: The maximal amount of prices the algorithm gets, 0 means unlimited (default: 0)
+///
+/// The data length constraints are checked at compile time.
+/// Since every registration is exported by the same symbol, a crate can only contain one
+/// `#[algorithm]`. Use `export_algorithm!` to export multiple algorithms.
+#[proc_macro_attribute]
+pub fn algorithm(args: TokenStream, item: TokenStream) -> TokenStream {
+    let args = parse_macro_input!(args as AttributeArgs);
+    let item = parse_macro_input!(item as Item);
+
+    let expanded = match expand_algorithm(args, &item) {
+        Ok(registration) => quote! {
+            #item
+            #registration
+        },
+        Err(error) => {
+            let error = error.to_compile_error();
+            quote! {
+                #item
+                #error
+            }
+        }
+    };
+
+    expanded.into()
+}
+
+/// The parsed arguments of the `algorithm` attribute
+struct AlgorithmArgs {
+    name: LitStr,
+    description: LitStr,
+    min_data: LitInt,
+    max_data: LitInt,
+}
+
+impl AlgorithmArgs {
+    fn parse(args: AttributeArgs) -> syn::Result<Self> {
+        let mut name = None;
+        let mut description = None;
+        let mut min_data = None;
+        let mut max_data = None;
+
+        for arg in args {
+            let name_value = match arg {
+                NestedMeta::Meta(Meta::NameValue(name_value)) => name_value,
+                other => return Err(syn::Error::new(
+                    other.span(),
+                    "expected an argument like `name = \"...\"`",
+                )),
+            };
+
+            let key = name_value.path
+                                .get_ident()
+                                .map(|ident| ident.to_string())
+                                .unwrap_or_default();
+
+            match key.as_str() {
+                "name" => set_once(&mut name, &name_value.path, lit_str(&name_value.lit)?)?,
+                "description" => set_once(&mut description, &name_value.path, lit_str(&name_value.lit)?)?,
+                "min_data" => set_once(&mut min_data, &name_value.path, lit_int(&name_value.lit)?)?,
+                "max_data" => set_once(&mut max_data, &name_value.path, lit_int(&name_value.lit)?)?,
+                _ => return Err(syn::Error::new(
+                    name_value.path.span(),
+                    "unknown argument, expected one of `name`, `description`, `min_data`, `max_data`",
+                )),
+            }
+        }
+
+        let name = name.ok_or_else(|| syn::Error::new(
+            Span::call_site(),
+            "missing argument `name`",
+        ))?;
+        if name.value().is_empty() {
+            return Err(syn::Error::new(name.span(), "the name of an algorithm can't be empty"));
+        }
+
+        Ok(Self {
+            name,
+            description: description.unwrap_or_else(|| LitStr::new("", Span::call_site())),
+            min_data: min_data.unwrap_or_else(|| LitInt::new("0", Span::call_site())),
+            max_data: max_data.unwrap_or_else(|| LitInt::new("0", Span::call_site())),
+        })
+    }
+}
+
+fn expand_algorithm(args: AttributeArgs, item: &Item) -> syn::Result<proc_macro2::TokenStream> {
+    let item = match item {
+        Item::Struct(item) => item,
+        other => return Err(syn::Error::new(
+            other.span(),
+            "#[algorithm] can only be used on structs",
+        )),
+    };
+    check_generics(item)?;
+
+    let args = AlgorithmArgs::parse(args)?;
+
+    // the same check `Algorithms::load` does, but at compile time
+    let min_data_length = args.min_data.base10_parse::<u64>()?;
+    let max_data_length = args.max_data.base10_parse::<u64>()?;
+    if max_data_length != 0 && max_data_length < min_data_length {
+        return Err(syn::Error::new(
+            args.max_data.span(),
+            format!(
+                "`max_data` ({}) needs to be greater or equal to `min_data` ({}) or needs to be equal to 0",
+                max_data_length, min_data_length,
+            ),
+        ));
+    }
+
+    let ident = &item.ident;
+    let name = &args.name;
+    let description = &args.description;
+    let min_data = LitInt::new(&min_data_length.to_string(), args.min_data.span());
+    let max_data = LitInt::new(&max_data_length.to_string(), args.max_data.span());
+
+    Ok(quote! {
+        ::trading_utils::export_algorithm!(
+            #name,
+            #description,
+            #min_data,
+            #max_data,
+            <#ident as ::std::default::Default>::default()
+        );
+    })
+}
+
+fn check_generics(item: &ItemStruct) -> syn::Result<()> {
+    if item.generics.params.is_empty() {
+        Ok(())
+    } else {
+        Err(syn::Error::new(
+            item.generics.span(),
+            "#[algorithm] can't be used on generic structs",
+        ))
+    }
+}
+
+fn set_once<T>(target: &mut Option<T>, path: &syn::Path, value: T) -> syn::Result<()> {
+    if target.is_some() {
+        return Err(syn::Error::new(path.span(), "duplicated argument"));
+    }
+    *target = Some(value);
+    Ok(())
+}
+
+fn lit_str(lit: &Lit) -> syn::Result<LitStr> {
+    match lit {
+        Lit::Str(lit) => Ok(lit.clone()),
+        other => Err(syn::Error::new(other.span(), "expected a string literal")),
+    }
+}
+
+fn lit_int(lit: &Lit) -> syn::Result<LitInt> {
+    match lit {
+        Lit::Int(lit) => Ok(lit.clone()),
+        other => Err(syn::Error::new(other.span(), "expected an integer literal")),
+    }
+}
@@ -1,2 +1,6 @@
 #[test]
-fn tests() {}
\ No newline at end of file
+fn tests() {
+    let tests = trybuild::TestCases::new();
+    tests.pass("tests/ui/pass/*.rs");
+    tests.compile_fail("tests/ui/fail/*.rs");
+}
@@ -0,0 +1,6 @@
+use trading_macros::algorithm;
+
+#[algorithm(name = "sma")]
+struct Sma<T>(T);
+
+fn main() {}
@@ -0,0 +1,6 @@
+use trading_macros::algorithm;
+
+#[algorithm(description = "simple moving average cross over")]
+struct Sma;
+
+fn main() {}
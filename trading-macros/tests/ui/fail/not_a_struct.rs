@@ -0,0 +1,6 @@
+use trading_macros::algorithm;
+
+#[algorithm(name = "sma")]
+enum Sma {}
+
+fn main() {}
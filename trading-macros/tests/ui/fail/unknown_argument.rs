@@ -0,0 +1,6 @@
+use trading_macros::algorithm;
+
+#[algorithm(name = "sma", min_prices = 20)]
+struct Sma;
+
+fn main() {}
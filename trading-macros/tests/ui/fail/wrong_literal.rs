@@ -0,0 +1,6 @@
+use trading_macros::algorithm;
+
+#[algorithm(name = "sma", min_data = "20")]
+struct Sma;
+
+fn main() {}
@@ -0,0 +1,22 @@
+use trading_utils::*;
+
+#[algorithm(name = "sma", description = "simple moving average cross over", min_data = 20, max_data = 200)]
+#[derive(Default)]
+struct Sma {
+    short: u64,
+}
+
+impl AlgorithmInterface for Sma {
+    fn algorithm(&mut self, _: &[Position], _: &[Price]) -> Result<&[Instruction<'_>], Error<TradingErrorKind>> {
+        self.short += 1;
+        Ok(&[])
+    }
+}
+
+fn main() {
+    let registration = &ALGORITHM_REGISTRATIONS[0];
+    assert_eq!(registration.name, "sma");
+    assert_eq!(registration.description, "simple moving average cross over");
+    assert_eq!(registration.min_data_length, 20);
+    assert_eq!(registration.max_data_length, 200);
+}
@@ -0,0 +1,18 @@
+use trading_utils::*;
+
+#[algorithm(name = "unlimited", min_data = 20)]
+#[derive(Default)]
+struct Unlimited;
+
+impl AlgorithmInterface for Unlimited {
+    fn algorithm(&mut self, _: &[Position], _: &[Price]) -> Result<&[Instruction<'_>], Error<TradingErrorKind>> {
+        Ok(&[])
+    }
+}
+
+fn main() {
+    let registration = &ALGORITHM_REGISTRATIONS[0];
+    assert_eq!(registration.description, "");
+    assert_eq!(registration.min_data_length, 20);
+    assert_eq!(registration.max_data_length, 0);
+}